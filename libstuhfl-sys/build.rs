@@ -75,6 +75,10 @@ fn main() {
         .allowlist_function("Gb29768_.*")
         .allowlist_function("Iso6b_.*")
         .allowlist_function("Inventory_.*")
+        .allowlist_function("STUHFL_F_EnableLog")
+        .allowlist_function("STUHFL_F_DisableLog")
+        .allowlist_function("STUHFL_F_SetParam")
+        .allowlist_function("STUHFL_F_GetParam")
         // variables to be exported into the bindings
         .allowlist_var("STUHFL_.*")
         // automatically tell cargo to update if the files are changed
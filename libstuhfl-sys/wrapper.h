@@ -1,2 +1,4 @@
 #include<stuhfl_err.h>
-#include<stuhfl_evalAPI.h>
\ No newline at end of file
+#include<stuhfl_dl.h>
+#include<stuhfl_evalAPI.h>
+#include<stuhfl_log.h>
\ No newline at end of file
@@ -10,6 +10,7 @@ use std::fmt;
 // The 'vendor' folder holds all of the referenced code.
 
 enum_from_primitive! {
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// Represents a physical antenna on an RFID reader.
@@ -48,7 +49,8 @@ impl fmt::Display for Antenna {
 }
 
 enum_from_primitive! {
-    #[derive(Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// Profiles defined in firmware for channel configurations.
     /// See [`ChannelListCfg`] for details.
@@ -75,7 +77,8 @@ enum_from_primitive! {
 }
 
 enum_from_primitive! {
-    #[derive(Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// Different types of tuning algorithms. The faster
     /// algorithms are generally less accurate.
@@ -129,7 +132,8 @@ enum_from_primitive! {
 }
 
 enum_from_primitive! {
-    #[derive(Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// Query Selection.
     ///
@@ -145,7 +149,8 @@ enum_from_primitive! {
 }
 
 enum_from_primitive! {
-    #[derive(Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// Frequency hopping modes
     pub enum FreqHopMode {
@@ -162,6 +167,96 @@ enum_from_primitive! {
 }
 
 enum_from_primitive! {
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    #[repr(u8)]
+    /// Continuous modulation modes, used for RF certification
+    /// and emission measurements. See [`ContinuousModulationCfg`].
+    pub enum ContinuousModulationMode {
+        /// Unmodulated carrier (continuous wave)
+        Static = ffi::STUHFL_D_CONTINUOUS_MODULATION_MODE_STATIC as u8,
+        /// Carrier modulated with pseudo random data
+        PseudoRandom = ffi::STUHFL_D_CONTINUOUS_MODULATION_MODE_PSEUDO_RANDOM as u8,
+        /// Carrier modulated according to the ETSI test pattern
+        Etsi = ffi::STUHFL_D_CONTINUOUS_MODULATION_MODE_ETSI as u8,
+    }
+}
+
+enum_from_primitive! {
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    #[repr(u8)]
+    /// Board-level reader configuration keys. See
+    /// [`BasicReader::get_rwd_config()`] and [`RwdConfig`].
+    pub enum RwdConfigKey {
+        /// Power down mode (see [`PowerDownMode`])
+        PowerDownMode = ffi::STUHFL_D_RWD_CFG_ID_POWER_DOWN_MODE as u8,
+        /// External voltage controlled oscillator (0 = internal, 1 = external)
+        ExternalVco = ffi::STUHFL_D_RWD_CFG_ID_EXTVCO as u8,
+        /// Power amplifier (0 = internal, 1 = external)
+        PowerAmplifier = ffi::STUHFL_D_RWD_CFG_ID_POWER_AMPLIFIER as u8,
+        /// Receiver input (0 or 1, board specific)
+        Input = ffi::STUHFL_D_RWD_CFG_ID_INPUT as u8,
+        /// Antenna switch (0 = disabled, 1 = enabled)
+        AntennaSwitch = ffi::STUHFL_D_RWD_CFG_ID_ANTENNA_SWITCH as u8,
+        /// Tuner capacitors fitted (bitmask of Cin = 0x01, Clen = 0x02, Cout = 0x04)
+        Tuner = ffi::STUHFL_D_RWD_CFG_ID_TUNER as u8,
+        /// Hardware ID (see [`HardwareId`]). Read only.
+        HardwareId = ffi::STUHFL_D_RWD_CFG_ID_HARDWARE_ID_NUM as u8,
+    }
+}
+
+impl RwdConfigKey {
+    /// Every reader configuration key, in ID order.
+    pub const ALL: [RwdConfigKey; 7] = [
+        RwdConfigKey::PowerDownMode,
+        RwdConfigKey::ExternalVco,
+        RwdConfigKey::PowerAmplifier,
+        RwdConfigKey::Input,
+        RwdConfigKey::AntennaSwitch,
+        RwdConfigKey::Tuner,
+        RwdConfigKey::HardwareId,
+    ];
+}
+
+enum_from_primitive! {
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    #[repr(u8)]
+    /// Power down modes of the ST25RU3993. See [`RwdConfigKey::PowerDownMode`].
+    pub enum PowerDownMode {
+        /// Power down mode (EN low)
+        PowerDown = ffi::STUHFL_D_POWER_DOWN as u8,
+        /// Normal mode (EN high, RF off, standby off)
+        Normal = ffi::STUHFL_D_POWER_NORMAL as u8,
+        /// Normal mode with the RF field on
+        NormalRf = ffi::STUHFL_D_POWER_NORMAL_RF as u8,
+        /// Standby mode (EN high, RF off, standby on)
+        Standby = ffi::STUHFL_D_POWER_STANDBY as u8,
+    }
+}
+
+enum_from_primitive! {
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    #[repr(u8)]
+    /// Board the firmware is running on. See [`RwdConfigKey::HardwareId`].
+    pub enum HardwareId {
+        /// Discovery board
+        Discovery = ffi::STUHFL_HWID_DISCOVERY as u8,
+        /// Evaluation board
+        Eval = ffi::STUHFL_HWID_EVAL as u8,
+        /// JIGEN board
+        Jigen = ffi::STUHFL_HWID_JIGEN as u8,
+        /// ELANCE board
+        Elance = ffi::STUHFL_HWID_ELANCE as u8,
+        /// JIGEN PHI board
+        JigenPhi = ffi::STUHFL_HWID_JIGEN_PHI as u8,
+    }
+}
+
+enum_from_primitive! {
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// Tuning Status
@@ -190,6 +285,7 @@ impl fmt::Display for TuningStatus {
 }
 
 enum_from_primitive! {
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Copy, Clone, PartialEq)]
     #[repr(u8)]
     /// Memory banks. See [`st25ru3993::read_gen2()`] for details.
@@ -206,7 +302,8 @@ enum_from_primitive! {
 }
 
 /// Listen-Before-Talk configuration.
-#[derive(Copy, Clone)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub enum Lbt {
     /// Enable Listen-Before-Talk
     Enable(LbtCfg),
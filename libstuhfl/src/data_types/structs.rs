@@ -1,16 +1,18 @@
 use super::{enums::*, traits::*, types::*};
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::helpers::{item_list_to_ffi, profile_to_item_list};
 use enum_primitive::FromPrimitive;
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
-// CB 7/14/25: Changing values found between double quotes "" changes default settings for the Gen2Cfg
-//             For example, on lines 123 and 126, you can change the numbers in the builder default section
-//             to alter the Tx and Rx levels
+// The builder defaults below fill in settings missing from a configuration
+// file. To change settings without recompiling, load a file with
+// 'Gen2Cfg::from_toml_file()' (feature 'use-serde') instead of editing them.
 
 /// Password for authentication during various protocol commands.
 /// For a list of which commands require/support password authentication,
-/// see [`ProtocolReader`].
+/// see [`ProtocolCommands`].
 ///
 /// # Example
 /// ```
@@ -43,7 +45,8 @@ impl From<[u8; 4]> for Password {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
 /// Contains the version numbering for ST Hardware & Software.
 /// `major.minor.micro.nano`
 pub struct VersionNum {
@@ -78,7 +81,8 @@ impl From<ffi::STUHFL_T_Version> for VersionNum {
     }
 }
 
-#[derive(Clone)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug)]
 /// Contains a version descriptor string
 pub struct VersionInfo {
     /// Information about version
@@ -91,7 +95,8 @@ impl fmt::Display for VersionInfo {
     }
 }
 
-#[derive(Clone)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug)]
 /// Contains version of firmware & software of ST chip
 pub struct Version {
     /// Firmware version
@@ -115,19 +120,25 @@ impl fmt::Display for Version {
 }
 
 // CB 6/30/25: changed default settings to match GUI
-#[derive(Builder, Copy, Clone)]
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "TxRxCfgBuilder"),
+    builder(derive(serde::Deserialize))
+)]
 #[builder(build_fn(validate = "Self::validate"))]
 /// Contains antenna configuration settings. See [`Self::builder()`] for details.
 pub struct TxRxCfg {
     /// Transmission output level (dB). See control register 3 for further info. Valid range [0dB..-19dB].
     #[builder(default = "0")] // default of -2
-    tx_output_level: i8,
+    pub(crate) tx_output_level: i8,
     /// Reciever sensitivity level (dB). Valid range [-17dB..+19dB].
     #[builder(default = "19")] // default of 3
     rx_sensitivity_level: i8,
     /// Antenna to be used.
     #[builder(default = "Antenna::Antenna1")]
-    antenna: Antenna,
+    pub(crate) antenna: Antenna,
     /// Time in ms for alternating the antennas when alternating mode is used.
     #[builder(default = "1")]
     alternate_antenna_interval: u16,
@@ -135,6 +146,21 @@ pub struct TxRxCfg {
 
 impl Builder<TxRxCfgBuilder> for TxRxCfg {}
 
+deserialize_via_builder!(TxRxCfg, TxRxCfgBuilder, TxRxCfgBuilderError);
+
+impl TryFrom<ffi::STUHFL_T_ST25RU3993_TxRxCfg> for TxRxCfg {
+    type Error = Error;
+
+    fn try_from(cfg: ffi::STUHFL_T_ST25RU3993_TxRxCfg) -> core::result::Result<Self, Self::Error> {
+        Ok(Self {
+            tx_output_level: cfg.txOutputLevel,
+            rx_sensitivity_level: cfg.rxSensitivity,
+            antenna: Antenna::from_u8(cfg.usedAntenna).ok_or(Error::Proto)?,
+            alternate_antenna_interval: cfg.alternateAntennaInterval,
+        })
+    }
+}
+
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_TxRxCfg> for TxRxCfg {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_TxRxCfg {
         ffi::STUHFL_T_ST25RU3993_TxRxCfg {
@@ -167,7 +193,13 @@ impl TxRxCfgBuilder {
     }
 }
 
-#[derive(Builder, Copy, Clone)]
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "AutoTuningBuilder"),
+    builder(derive(serde::Deserialize))
+)]
 /// Auto-tuning algorithm parameters. This is used during `inventory_runner` cycles.
 pub struct AutoTuning {
     /// Auto-tuning check interval (in inventory rounds)
@@ -187,6 +219,23 @@ pub struct AutoTuning {
 
 impl Builder<AutoTuningBuilder> for AutoTuning {}
 
+deserialize_via_builder!(AutoTuning, AutoTuningBuilder, AutoTuningBuilderError);
+
+impl TryFrom<ffi::STUHFL_T_ST25RU3993_AutoTuning> for AutoTuning {
+    type Error = Error;
+
+    fn try_from(
+        tuning: ffi::STUHFL_T_ST25RU3993_AutoTuning,
+    ) -> core::result::Result<Self, Self::Error> {
+        Ok(Self {
+            interval: tuning.interval,
+            level: tuning.level,
+            algo: TuningAlgorithm::from_u8(tuning.algorithm).ok_or(Error::Proto)?,
+            false_positive_detect: tuning.falsePositiveDetection,
+        })
+    }
+}
+
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_AutoTuning> for AutoTuning {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_AutoTuning {
         ffi::STUHFL_T_ST25RU3993_AutoTuning {
@@ -198,18 +247,111 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_AutoTuning> for AutoTuning {
     }
 }
 
-#[derive(Copy, Clone, PartialEq)]
-/// Automatic reciever sensitivity setting. Currently this is
-/// only a boolean, as the algorithm parameters in the firmware
-/// are too vague.
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "AutoRxSensitivityBuilder"),
+    builder(derive(serde::Deserialize))
+)]
+#[builder(build_fn(validate = "Self::validate"))]
+/// Automatic reciever sensitivity setting. The firmware counts slot events
+/// during inventory rounds, each event adding its increment value (`iv_*`)
+/// to an event counter. Once the counter reaches the increase or decrease
+/// threshold for the current sensitivity level, the sensitivity is adjusted
+/// and the counter is reset. See [`Self::builder()`] for details.
 pub struct AutoRxSensitivity {
-    /// Just a thin wrapper around a boolean
+    /// Enable automatic sensitivity adjustment
+    #[builder(default = "true")]
     enable: bool,
+    /// Initial sensitivity level (dB). Valid range [-17dB..+19dB].
+    #[builder(default = "3")]
+    start_rx: i8,
+    /// Minimum sensitivity level (dB). Valid range [-17dB..+19dB].
+    #[builder(default = "-17")]
+    min_rx: i8,
+    /// Maximum sensitivity level (dB). Valid range [-17dB..+19dB].
+    #[builder(default = "19")]
+    max_rx: i8,
+    /// Retune the antenna whenever the sensitivity is adjusted
+    #[builder(default = "false")]
+    retune_when_adjust: bool,
+    /// Counter increment for a tag reply
+    #[builder(default = "0")]
+    iv_tag: i8,
+    /// Counter increment for an empty slot
+    #[builder(default = "8")]
+    iv_empty: i8,
+    /// Counter increment for a collision
+    #[builder(default = "-1")]
+    iv_collision: i8,
+    /// Counter increment for a preamble error
+    #[builder(default = "-7")]
+    iv_preamble: i8,
+    /// Counter increment for a CRC error
+    #[builder(default = "0")]
+    iv_crc: i8,
+    /// Counter increment for a header error
+    #[builder(default = "0")]
+    iv_header: i8,
+    /// Counter increment for an RX count error
+    #[builder(default = "-7")]
+    iv_rx_count: i8,
+    /// Counter increment for a stop bit error
+    #[builder(default = "0")]
+    iv_stop_bit: i8,
+    /// Counter increment for a resent ACK
+    #[builder(default = "0")]
+    iv_resend_ack: i8,
+    /// Counter increment for suspected noise
+    #[builder(default = "0")]
+    iv_noise_suspicion: i8,
+    /// Counter value at which sensitivity is decreased, per sensitivity level (must be negative)
+    #[builder(default = "[-120; ffi::STUHFL_D_NB_RX_VALUES as usize]")]
+    dec_threshold: [i16; ffi::STUHFL_D_NB_RX_VALUES as usize],
+    /// Counter value at which sensitivity is increased, per sensitivity level (must be positive)
+    #[builder(default = "[100; ffi::STUHFL_D_NB_RX_VALUES as usize]")]
+    inc_threshold: [i16; ffi::STUHFL_D_NB_RX_VALUES as usize],
 }
 
+impl Builder<AutoRxSensitivityBuilder> for AutoRxSensitivity {}
+
+deserialize_via_builder!(
+    AutoRxSensitivity,
+    AutoRxSensitivityBuilder,
+    AutoRxSensitivityBuilderError
+);
+
 impl From<bool> for AutoRxSensitivity {
     fn from(enable: bool) -> Self {
-        Self { enable }
+        AutoRxSensitivityBuilder::default()
+            .enable(enable)
+            .build()
+            .unwrap()
+    }
+}
+
+impl From<ffi::STUHFL_T_ST25RU3993_AdaptiveSensitivity> for AutoRxSensitivity {
+    fn from(sens: ffi::STUHFL_T_ST25RU3993_AdaptiveSensitivity) -> Self {
+        Self {
+            enable: sens.adaptiveRx,
+            start_rx: sens.startRx,
+            min_rx: sens.minRx,
+            max_rx: sens.maxRx,
+            retune_when_adjust: sens.retuneWhenAdjust,
+            iv_tag: sens.ivTag,
+            iv_empty: sens.ivEmpty,
+            iv_collision: sens.ivColllision,
+            iv_preamble: sens.ivPreample,
+            iv_crc: sens.ivCrc,
+            iv_header: sens.ivHeader,
+            iv_rx_count: sens.ivRxCount,
+            iv_stop_bit: sens.ivStopBit,
+            iv_resend_ack: sens.ivResendAck,
+            iv_noise_suspicion: sens.ivNoiseSuspicion,
+            dec_threshold: sens.decThreshold,
+            inc_threshold: sens.incThreshold,
+        }
     }
 }
 
@@ -217,23 +359,135 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_AdaptiveSensitivity> for AutoRxSensitivity {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_AdaptiveSensitivity {
         ffi::STUHFL_T_ST25RU3993_AdaptiveSensitivity {
             adaptiveRx: self.enable,
-            ..Default::default()
+            startRx: self.start_rx,
+            minRx: self.min_rx,
+            maxRx: self.max_rx,
+            retuneWhenAdjust: self.retune_when_adjust,
+            ivTag: self.iv_tag,
+            ivEmpty: self.iv_empty,
+            ivColllision: self.iv_collision,
+            ivPreample: self.iv_preamble,
+            ivCrc: self.iv_crc,
+            ivHeader: self.iv_header,
+            ivRxCount: self.iv_rx_count,
+            ivStopBit: self.iv_stop_bit,
+            ivResendAck: self.iv_resend_ack,
+            ivNoiseSuspicion: self.iv_noise_suspicion,
+            decThreshold: self.dec_threshold,
+            incThreshold: self.inc_threshold,
         }
     }
 }
 
-#[derive(Copy, Clone, PartialEq)]
-/// Automatic transmission strength setting. Currently this is
-/// only a boolean, as the algorithm parameters in the firmware
-/// are too vague.
+impl AutoRxSensitivityBuilder {
+    /// Validates state by making sure the sensitivity levels are within
+    /// the range specified by the documentation, and that the thresholds
+    /// can actually be reached.
+    fn validate(&self) -> core::result::Result<(), String> {
+        for (name, level) in [
+            ("start_rx", self.start_rx),
+            ("min_rx", self.min_rx),
+            ("max_rx", self.max_rx),
+        ] {
+            if let Some(i) = level {
+                if !(-17..=19).contains(&i) {
+                    return Err(format!("{} invalid: see docs for details", name));
+                }
+            }
+        }
+
+        let start = self.start_rx.unwrap_or(3);
+        let min = self.min_rx.unwrap_or(-17);
+        let max = self.max_rx.unwrap_or(19);
+        if min > max || start < min || start > max {
+            return Err("start_rx must be between min_rx and max_rx".to_owned());
+        }
+
+        if let Some(dec) = self.dec_threshold {
+            if dec.iter().any(|&i| i >= 0) {
+                return Err("dec_threshold values must be negative".to_owned());
+            }
+        }
+
+        if let Some(inc) = self.inc_threshold {
+            if inc.iter().any(|&i| i <= 0) {
+                return Err("inc_threshold values must be positive".to_owned());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "AutoTxStrengthBuilder"),
+    builder(derive(serde::Deserialize))
+)]
+#[builder(build_fn(validate = "Self::validate"))]
+/// Automatic transmission strength setting. Output levels are given as
+/// attenuation in dB, so 0dB is the strongest setting and -19dB the weakest.
+/// See [`Self::builder()`] for details.
+///
+/// # Example
+///
+/// ```
+/// use libstuhfl::prelude::*;
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+///
+/// // Never transmit stronger than -6dB, e.g. for near-field stations
+/// let auto_tx = AutoTxStrength::builder()
+///     .enable(true)
+///     .start_tx(-8)
+///     .min_tx(-6)
+///     .build()?;
+///
+/// # Ok(())
+/// # }
+/// ```
 pub struct AutoTxStrength {
-    /// Just a thin wrapper around a boolean
+    /// Enable automatic output power adjustment
+    #[builder(default = "false")]
     enable: bool,
+    /// Initial output level (dB). Valid range [0dB..-19dB].
+    #[builder(default = "-2")]
+    start_tx: i8,
+    /// Strongest output level the algorithm may use (dB), i.e. the Tx
+    /// ceiling. Valid range [0dB..-19dB].
+    #[builder(default = "0")]
+    min_tx: i8,
+    /// Weakest output level the algorithm may use (dB). Valid range [0dB..-19dB].
+    #[builder(default = "-19")]
+    max_tx: i8,
 }
 
+impl Builder<AutoTxStrengthBuilder> for AutoTxStrength {}
+
+deserialize_via_builder!(
+    AutoTxStrength,
+    AutoTxStrengthBuilder,
+    AutoTxStrengthBuilderError
+);
+
 impl From<bool> for AutoTxStrength {
     fn from(enable: bool) -> Self {
-        Self { enable }
+        AutoTxStrengthBuilder::default()
+            .enable(enable)
+            .build()
+            .unwrap()
+    }
+}
+
+impl From<ffi::STUHFL_T_ST25RU3993_AdaptiveOutputPower> for AutoTxStrength {
+    fn from(pwr: ffi::STUHFL_T_ST25RU3993_AdaptiveOutputPower) -> Self {
+        Self {
+            enable: pwr.adaptiveTx,
+            start_tx: pwr.startTx,
+            min_tx: pwr.minTx,
+            max_tx: pwr.maxTx,
+        }
     }
 }
 
@@ -241,12 +495,49 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_AdaptiveOutputPower> for AutoTxStrength {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_AdaptiveOutputPower {
         ffi::STUHFL_T_ST25RU3993_AdaptiveOutputPower {
             adaptiveTx: self.enable,
-            ..Default::default()
+            startTx: self.start_tx,
+            minTx: self.min_tx,
+            maxTx: self.max_tx,
         }
     }
 }
 
-#[derive(Builder, Copy, Clone)]
+impl AutoTxStrengthBuilder {
+    /// Validates state by making sure the output levels are within the
+    /// range specified by the documentation, and that the initial level
+    /// does not exceed the Tx ceiling.
+    fn validate(&self) -> core::result::Result<(), String> {
+        for (name, level) in [
+            ("start_tx", self.start_tx),
+            ("min_tx", self.min_tx),
+            ("max_tx", self.max_tx),
+        ] {
+            if let Some(i) = level {
+                if !(-19..=0).contains(&i) {
+                    return Err(format!("{} invalid: see docs for details", name));
+                }
+            }
+        }
+
+        // Note: a stronger level is closer to 0
+        let start = self.start_tx.unwrap_or(-2);
+        let ceiling = self.min_tx.unwrap_or(0);
+        let floor = self.max_tx.unwrap_or(-19);
+        if ceiling < floor || start > ceiling || start < floor {
+            return Err("start_tx must be between min_tx and max_tx".to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "LbtCfgBuilder"),
+    builder(derive(serde::Deserialize))
+)]
 /// Configuration settings for Listen-Before-Talk. See [`Lbt`] for details.
 pub struct LbtCfg {
     /// Length of listening period
@@ -262,6 +553,22 @@ pub struct LbtCfg {
 
 impl Builder<LbtCfgBuilder> for LbtCfg {}
 
+deserialize_via_builder!(LbtCfg, LbtCfgBuilder, LbtCfgBuilderError);
+
+impl From<ffi::STUHFL_T_ST25RU3993_FreqLBT> for Lbt {
+    fn from(lbt: ffi::STUHFL_T_ST25RU3993_FreqLBT) -> Self {
+        if lbt.skipLBTcheck {
+            Lbt::Disable
+        } else {
+            Lbt::Enable(LbtCfg {
+                listening_time: lbt.listeningTime,
+                idle_time: lbt.idleTime,
+                rssi_log_threshold: lbt.rssiLogThreshold,
+            })
+        }
+    }
+}
+
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_FreqLBT> for Lbt {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_FreqLBT {
         match self {
@@ -282,7 +589,8 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_FreqLBT> for Lbt {
 /// Capacitance values of self-jamming PI-capacitor network. See
 /// ST25RU3993 self-jamming implementation details. See [`ChannelListCfg`]
 /// for details.
-#[derive(Copy, Clone)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub struct TuningCaps {
     /// IN capacitance of tuning network
     cin: u8,
@@ -310,6 +618,16 @@ impl TuningCaps {
     }
 }
 
+impl From<ffi::STUHFL_T_ST25RU3993_Caps> for TuningCaps {
+    fn from(caps: ffi::STUHFL_T_ST25RU3993_Caps) -> Self {
+        Self {
+            cin: caps.cin,
+            clen: caps.clen,
+            cout: caps.cout,
+        }
+    }
+}
+
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_Caps> for TuningCaps {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_Caps {
         ffi::STUHFL_T_ST25RU3993_Caps {
@@ -320,7 +638,8 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_Caps> for TuningCaps {
     }
 }
 
-#[derive(Copy, Clone)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
 /// A single frequency configuration in a [`ChannelListCfg`].
 pub struct ChannelItem {
     /// Frequency to be used for channel item (Hz)
@@ -344,6 +663,20 @@ impl ChannelItem {
     pub fn from(frequency: u32, caps: [TuningCaps; 2]) -> Self {
         Self { frequency, caps }
     }
+
+    /// Frequency of the channel item
+    pub fn frequency(&self) -> u32 {
+        self.frequency
+    }
+}
+
+impl From<ffi::STUHFL_T_ST25RU3993_ChannelItem> for ChannelItem {
+    fn from(item: ffi::STUHFL_T_ST25RU3993_ChannelItem) -> Self {
+        Self {
+            frequency: item.frequency,
+            caps: [item.caps[0].into(), item.caps[1].into()],
+        }
+    }
 }
 
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_ChannelItem> for ChannelItem {
@@ -356,7 +689,12 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_ChannelItem> for ChannelItem {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Vec<ChannelItem>", into = "Vec<ChannelItem>")
+)]
 /// Contains a list of [`ChannelItem`]. This represents all
 /// the frequencies the reader can try to use during transmission.
 pub struct ChannelListCfg {
@@ -380,6 +718,42 @@ impl ChannelListCfg {
     }
 }
 
+#[cfg(feature = "use-serde")]
+impl TryFrom<Vec<ChannelItem>> for ChannelListCfg {
+    type Error = String;
+
+    /// Checks that the list fits the firmware's channel table
+    fn try_from(item_list: Vec<ChannelItem>) -> core::result::Result<Self, Self::Error> {
+        if item_list.is_empty() || item_list.len() > ffi::STUHFL_D_MAX_FREQUENCY as usize {
+            return Err(format!(
+                "channel list must contain 1 to {} items",
+                ffi::STUHFL_D_MAX_FREQUENCY
+            ));
+        }
+
+        Ok(Self { item_list })
+    }
+}
+
+impl From<ChannelListCfg> for Vec<ChannelItem> {
+    fn from(list: ChannelListCfg) -> Self {
+        list.item_list
+    }
+}
+
+impl From<ffi::STUHFL_T_ST25RU3993_ChannelList> for ChannelListCfg {
+    fn from(list: ffi::STUHFL_T_ST25RU3993_ChannelList) -> Self {
+        // Never read past the array, whatever count the firmware reports
+        let len = (list.numFrequencies as usize).min(list.itemList.len());
+        Self {
+            item_list: list.itemList[..len]
+                .iter()
+                .map(|&item| item.into())
+                .collect(),
+        }
+    }
+}
+
 // CB 7/9/25: Changing 'itemList:' might be the answer
 // I need to use 'parse()' to change the caps list
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_ChannelList> for ChannelListCfg {
@@ -393,7 +767,13 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_ChannelList> for ChannelListCfg {
     }
 }
 
-#[derive(Builder, Copy, Clone)]
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "FreqHopCfgBuilder"),
+    builder(derive(serde::Deserialize))
+)]
 /// Frequency hopping configuration. See [`Self::builder()`] for more.
 pub struct FreqHopCfg {
     /// Max sending time before frequency hopping is performed. Minimum value: 40ms
@@ -409,6 +789,22 @@ pub struct FreqHopCfg {
 
 impl Builder<FreqHopCfgBuilder> for FreqHopCfg {}
 
+deserialize_via_builder!(FreqHopCfg, FreqHopCfgBuilder, FreqHopCfgBuilderError);
+
+impl TryFrom<ffi::STUHFL_T_ST25RU3993_FreqHop> for FreqHopCfg {
+    type Error = Error;
+
+    fn try_from(
+        freq_hop: ffi::STUHFL_T_ST25RU3993_FreqHop,
+    ) -> core::result::Result<Self, Self::Error> {
+        Ok(Self {
+            max_sending_time: freq_hop.maxSendingTime,
+            min_sending_time: freq_hop.minSendingTime,
+            mode: FreqHopMode::from_u8(freq_hop.mode).ok_or(Error::Proto)?,
+        })
+    }
+}
+
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_FreqHop> for FreqHopCfg {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_FreqHop {
         ffi::STUHFL_T_ST25RU3993_FreqHop {
@@ -420,6 +816,188 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_FreqHop> for FreqHopCfg {
     }
 }
 
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Antenna power (carrier) state. See [`BasicReader::set_antenna_power()`].
+pub struct AntennaPower {
+    /// Whether the carrier is switched on
+    pub on: bool,
+    /// Time in ms before the setting will be applied
+    pub timeout: u16,
+    /// Frequency of the carrier (kHz)
+    pub frequency: u32,
+}
+
+impl From<ffi::STUHFL_T_ST25RU3993_AntennaPower> for AntennaPower {
+    fn from(pwr: ffi::STUHFL_T_ST25RU3993_AntennaPower) -> Self {
+        Self {
+            on: pwr.mode == ffi::STUHFL_D_ANTENNA_POWER_MODE_ON,
+            timeout: pwr.timeout,
+            frequency: pwr.frequency,
+        }
+    }
+}
+
+impl AsFFI<ffi::STUHFL_T_ST25RU3993_AntennaPower> for AntennaPower {
+    fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_AntennaPower {
+        ffi::STUHFL_T_ST25RU3993_AntennaPower {
+            mode: if self.on {
+                ffi::STUHFL_D_ANTENNA_POWER_MODE_ON
+            } else {
+                ffi::STUHFL_D_ANTENNA_POWER_MODE_OFF
+            },
+            timeout: self.timeout,
+            frequency: self.frequency,
+        }
+    }
+}
+
+#[derive(Builder, Copy, Clone)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "ContinuousModulationCfgBuilder"),
+    builder(derive(serde::Deserialize))
+)]
+/// Continuous modulation settings, used for RF certification and emission
+/// tests. See [`BasicReader::start_continuous_modulation()`] for details.
+pub struct ContinuousModulationCfg {
+    /// Frequency of the carrier (kHz)
+    #[builder(default = "ffi::STUHFL_D_DEFAULT_FREQUENCY")]
+    frequency: u32,
+    /// Maximum modulation time in ms. If zero, the modulation
+    /// runs until it is stopped.
+    #[builder(default = "0")]
+    max_sending_time: u16,
+    /// Modulation mode
+    #[builder(default = "ContinuousModulationMode::Static")]
+    mode: ContinuousModulationMode,
+}
+
+impl Builder<ContinuousModulationCfgBuilder> for ContinuousModulationCfg {}
+
+deserialize_via_builder!(
+    ContinuousModulationCfg,
+    ContinuousModulationCfgBuilder,
+    ContinuousModulationCfgBuilderError
+);
+
+impl AsFFI<ffi::STUHFL_T_ST25RU3993_FreqContinuousModulation> for ContinuousModulationCfg {
+    fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_FreqContinuousModulation {
+        ffi::STUHFL_T_ST25RU3993_FreqContinuousModulation {
+            frequency: self.frequency,
+            enable: true,
+            maxSendingTime: self.max_sending_time,
+            mode: self.mode as u8,
+        }
+    }
+}
+
+/// Guard for a running continuous modulation. The carrier is
+/// switched off when this is dropped (or [`Self::stop()`] is called).
+/// See [`BasicReader::start_continuous_modulation()`].
+pub struct ContinuousModulation<'a, R: BasicReader> {
+    /// Reader that is modulating, borrowed until the carrier is off
+    reader: &'a mut R,
+    /// Settings the modulation was started with
+    cfg: ContinuousModulationCfg,
+    /// Whether the carrier was already switched off
+    stopped: bool,
+}
+
+impl<'a, R: BasicReader> ContinuousModulation<'a, R> {
+    /// Starts modulating, for use within the crate
+    pub(crate) fn start(reader: &'a mut R, cfg: &ContinuousModulationCfg) -> Result<Self> {
+        use crate::helpers::proc_err;
+
+        let mut cont_mod = cfg.as_ffi();
+        unsafe { proc_err(ffi::Set_FreqContinuousModulation(&mut cont_mod))? }
+
+        Ok(Self {
+            reader,
+            cfg: *cfg,
+            stopped: false,
+        })
+    }
+
+    /// Returns the reader that is modulating.
+    pub fn reader(&self) -> &R {
+        self.reader
+    }
+
+    /// Switches the carrier off, returning any errors that
+    /// occur while doing so.
+    pub fn stop(mut self) -> Result<()> {
+        self.stop_inner()
+    }
+
+    /// Switches the carrier off (only once)
+    fn stop_inner(&mut self) -> Result<()> {
+        use crate::helpers::proc_err;
+
+        if self.stopped {
+            return Ok(());
+        }
+        self.stopped = true;
+
+        let mut cont_mod = ffi::STUHFL_T_ST25RU3993_FreqContinuousModulation {
+            frequency: self.cfg.frequency,
+            enable: false,
+            maxSendingTime: 0,
+            mode: ffi::STUHFL_D_CONTINUOUS_MODULATION_MODE_OFF as u8,
+        };
+        unsafe { proc_err(ffi::Set_FreqContinuousModulation(&mut cont_mod)) }
+    }
+}
+
+impl<'a, R: BasicReader> Drop for ContinuousModulation<'a, R> {
+    fn drop(&mut self) {
+        if let Err(e) = self.stop_inner() {
+            log::error!(target: "stuhfl", "Error while stopping continuous modulation: {}", e);
+        }
+    }
+}
+
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Snapshot of the board-level reader configuration.
+/// See [`BasicReader::read_all_rwd_config()`].
+pub struct RwdConfig {
+    /// Power down mode
+    pub power_down_mode: PowerDownMode,
+    /// Whether an external VCO is used
+    pub external_vco: bool,
+    /// Whether an external power amplifier is used
+    pub external_power_amplifier: bool,
+    /// Receiver input (board specific)
+    pub input: u8,
+    /// Whether the antenna switch is enabled
+    pub antenna_switch: bool,
+    /// Tuner capacitors fitted (bitmask of Cin = 0x01, Clen = 0x02, Cout = 0x04)
+    pub tuner: u8,
+    /// Hardware the firmware is running on, `None` if unknown
+    pub hardware_id: Option<HardwareId>,
+}
+
+impl fmt::Display for RwdConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Power down mode: {:?}", self.power_down_mode)?;
+        writeln!(f, "External VCO: {}", self.external_vco)?;
+        writeln!(
+            f,
+            "External power amplifier: {}",
+            self.external_power_amplifier
+        )?;
+        writeln!(f, "Input: {}", self.input)?;
+        writeln!(f, "Antenna switch: {}", self.antenna_switch)?;
+        writeln!(f, "Tuner: 0x{:02X}", self.tuner)?;
+        match self.hardware_id {
+            Some(id) => write!(f, "Hardware ID: {:?}", id),
+            None => write!(f, "Hardware ID: unknown"),
+        }
+    }
+}
+
 #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Clone)]
 /// Container for hexadecimal-based ID values such as TID, XPC, and EPC.
@@ -459,6 +1037,7 @@ impl fmt::Display for HexID {
     }
 }
 
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, PartialEq)]
 /// Contains all data related to an RFID tag found during an inventory cycle.
 /// These values are populated automatically by the firmware.
@@ -532,6 +1111,7 @@ impl fmt::Display for InventoryTag {
     }
 }
 
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Copy, Clone, PartialEq, Debug)]
 /// Statistics related to an inventory run. These settings are
 /// generated by the firmware.
@@ -678,34 +1258,196 @@ impl InventoryStatistics {
     }
 }
 
+/// Baud rates the serial link can be opened with
+const SUPPORTED_BAUD_RATES: [u32; 15] = [
+    57600, 115200, 230400, 460800, 500000, 576000, 921600, 1000000, 1152000, 1500000, 2000000,
+    2500000, 3000000, 3500000, 4000000,
+];
+
+/// Time between two readiness probes while connecting
+const READY_PROBE_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Builder, Clone, PartialEq, Debug)]
+#[builder(build_fn(validate = "Self::validate"))]
+/// Serial link settings used when connecting to a reader. See [`Self::builder()`] for details.
+///
+/// # Example
+///
+/// ```no_run
+/// use libstuhfl::prelude::*;
+/// use std::time::Duration;
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+///
+/// // Slow link through a USB hub and a long cable
+/// let options = ConnectionOptions::builder()
+///     .read_timeout(Duration::from_secs(5))
+///     .ready_timeout(Duration::from_secs(10))
+///     .build()?;
+///
+/// let reader = Reader::connect_with_options("/dev/ttyUSB0", &options)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ConnectionOptions {
+    /// Baud rate of the serial link. The evaluation firmware runs at 3 MBd,
+    /// other rates only work with firmware built for them.
+    #[builder(default = "3000000")]
+    baud_rate: u32,
+    /// Time to wait for a reply from the reader
+    #[builder(default = "Duration::from_millis(2000)")]
+    read_timeout: Duration,
+    /// Time to wait for a command to be sent to the reader
+    #[builder(default = "Duration::from_millis(1000)")]
+    write_timeout: Duration,
+    /// Level of the DTR line once connected. The reader only talks while it is set.
+    #[builder(default = "true")]
+    dtr: bool,
+    /// Level of the RTS line once connected. Setting it holds the reader in reset.
+    #[builder(default = "false")]
+    rts: bool,
+    /// Time the reader may take to answer after the port was opened
+    #[builder(default = "Duration::from_secs(5)")]
+    ready_timeout: Duration,
+}
+
+impl Builder<ConnectionOptionsBuilder> for ConnectionOptions {}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        ConnectionOptionsBuilder::default().build().unwrap()
+    }
+}
+
+impl ConnectionOptionsBuilder {
+    /// Validates state by making sure the baud rate is supported
+    /// and the timeouts fit the C library.
+    fn validate(&self) -> core::result::Result<(), String> {
+        if let Some(baud_rate) = self.baud_rate {
+            if !SUPPORTED_BAUD_RATES.contains(&baud_rate) {
+                return Err(format!("baud_rate {} is not supported", baud_rate));
+            }
+        }
+
+        for timeout in [self.read_timeout, self.write_timeout].iter().flatten() {
+            if timeout.is_zero() || timeout.as_millis() > u32::MAX as u128 {
+                return Err("read/write timeouts must be within 1ms..u32::MAX ms".to_owned());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether a [`Connection`] is currently open. The C library only holds
+/// one connection, opening another port closes the current one.
+static CONNECTED: AtomicBool = AtomicBool::new(false);
+
 /// Manages a connection to an RFID reader
-pub struct Connection;
+pub struct Connection {
+    /// Port the reader is connected to
+    port: String,
+    /// Settings the connection was established with
+    options: ConnectionOptions,
+}
 
 impl Connection {
-    /// Establishes new connection to reader
+    /// Establishes new connection to reader with default settings
     pub fn new(port: &str) -> Result<Self> {
+        Self::with_options(port, &ConnectionOptions::default())
+    }
+
+    /// Establishes new connection to reader. The link is configured as
+    /// set in `options`, then the reader is probed until it answers or
+    /// [`ConnectionOptionsBuilder::ready_timeout()`] expires.
+    pub fn with_options(port: &str, options: &ConnectionOptions) -> Result<Self> {
         use crate::helpers::proc_err;
-        use std::{ffi::CString, thread, time::Duration};
+        use std::{ffi::CString, mem::zeroed, thread, time::Instant};
+
+        // Forward C traces from the start
+        #[cfg(feature = "logging")]
+        crate::logging::auto_enable();
 
         // Copy the port so that its "safe" from C
-        let port = CString::new(port).expect("Failed to convert string");
+        let c_port = CString::new(port).expect("Failed to convert string");
+
+        // The baud rate is kept by the C library between connections,
+        // so it has to be set every time before the port is opened
+        let mut baud_rate = options.baud_rate;
+        unsafe { set_connection_param(ffi::STUHFL_KEY_BR, &mut baud_rate)? }
 
         // Connect to board
-        unsafe { proc_err(ffi::Connect(port.as_ptr() as *mut _))? }
+        unsafe { proc_err(ffi::Connect(c_port.as_ptr() as *mut _))? }
+
+        // From here on the port is closed again when returning early
+        CONNECTED.store(true, Ordering::SeqCst);
+        let connection = Self {
+            port: port.to_owned(),
+            options: options.clone(),
+        };
+
+        // Timeouts and control lines can only be set on an open port
+        let mut read_timeout = options.read_timeout.as_millis() as u32;
+        let mut write_timeout = options.write_timeout.as_millis() as u32;
+        let mut dtr = options.dtr as u8;
+        let mut rts = options.rts as u8;
+        unsafe {
+            set_connection_param(ffi::STUHFL_KEY_RD_TIMEOUT_MS, &mut read_timeout)?;
+            set_connection_param(ffi::STUHFL_KEY_WR_TIMEOUT_MS, &mut write_timeout)?;
+            set_connection_param(ffi::STUHFL_KEY_DTR, &mut dtr)?;
+            set_connection_param(ffi::STUHFL_KEY_RTS, &mut rts)?;
+        }
+
+        // Wait until the board has come out of reset and answers
+        let deadline = Instant::now() + options.ready_timeout;
+        loop {
+            let mut sw_ver: ffi::STUHFL_T_Version = unsafe { zeroed() };
+            let mut hw_ver: ffi::STUHFL_T_Version = unsafe { zeroed() };
+
+            match unsafe { proc_err(ffi::Get_BoardVersion(&mut sw_ver, &mut hw_ver)) } {
+                Ok(()) => return Ok(connection),
+                Err(e) if Instant::now() >= deadline => return Err(e),
+                Err(_) => thread::sleep(READY_PROBE_INTERVAL),
+            }
+        }
+    }
+
+    /// Port the reader is connected to
+    pub fn port(&self) -> &str {
+        &self.port
+    }
 
-        // Wait so that board has time to connect
-        thread::sleep(Duration::from_micros(600000));
+    /// Settings the connection was established with
+    pub fn options(&self) -> &ConnectionOptions {
+        &self.options
+    }
 
-        Ok(Self {})
+    /// Whether a connection to any reader is currently open
+    #[cfg(feature = "port-scanning")]
+    pub(crate) fn is_open() -> bool {
+        CONNECTED.load(Ordering::SeqCst)
     }
 }
 
+/// Sets a parameter of the serial link
+///
+/// # Safety
+///
+/// `value` must have the type the C library expects for `key`.
+unsafe fn set_connection_param<T>(key: u32, value: &mut T) -> Result<()> {
+    crate::helpers::proc_err(ffi::STUHFL_F_SetParam(
+        ffi::STUHFL_PARAM_TYPE_CONNECTION | key,
+        value as *mut T as *mut _,
+    ))
+}
+
 impl Drop for Connection {
     fn drop(&mut self) {
         use crate::helpers::proc_err;
 
         if let Err(e) = unsafe { proc_err(ffi::Disconnect()) } {
-            eprintln!("Error while disconnecting from reader: {}", e);
+            log::error!(target: "stuhfl", "Error while disconnecting from reader: {}", e);
         }
+
+        CONNECTED.store(false, Ordering::SeqCst);
     }
 }
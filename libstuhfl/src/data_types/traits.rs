@@ -3,6 +3,9 @@ use crate::error::Result;
 use crate::gen2;
 use crate::helpers::proc_err;
 use crate::reader::Reader;
+#[cfg(feature = "advanced")]
+use crate::registers::*;
+use enum_primitive::FromPrimitive;
 use std::mem::zeroed;
 
 /// Allows various datatypes to be converted
@@ -126,36 +129,7 @@ pub unsafe trait BasicReader: Sized + ConnectionHolder {
     /// # }
     /// ```
     fn configure_gen2(self, configuration: &gen2::Gen2Cfg) -> Result<gen2::Gen2Reader> {
-        // Set up antenna configuration
-        let mut tx_rx_cfg = configuration.tx_rx_cfg.as_ffi();
-        unsafe { proc_err(ffi::Set_TxRxCfg(&mut tx_rx_cfg))? }
-
-        // Set up inventory configuration
-        let mut inv_cfg = configuration.inv_cfg.as_ffi();
-        unsafe { proc_err(ffi::Set_Gen2_InventoryCfg(&mut inv_cfg))? }
-
-        // Set up protocol configuration
-        let mut proto_cfg = configuration.proto_cfg.as_ffi();
-        unsafe { proc_err(ffi::Set_Gen2_ProtocolCfg(&mut proto_cfg))? }
-
-        // Set up lbt configuraiton
-        let mut lbt = configuration.lbt.as_ffi();
-        unsafe { proc_err(ffi::Set_FreqLBT(&mut lbt))? }
-
-        // Set up channel list configuration
-        let mut channel_list = configuration.channel_list.as_ffi();
-        unsafe { proc_err(ffi::Set_ChannelList(&mut channel_list))? }
-
-        // Set up frequency hopping configuration
-        let mut freq_hop = configuration.freq_hop.as_ffi();
-        unsafe { proc_err(ffi::Set_FreqHop(&mut freq_hop))? }
-
-        // Clear select configuration
-        let mut gen2_select = ffi::STUHFL_T_Gen2_Select {
-            mode: ffi::STUHFL_D_GEN2_SELECT_MODE_CLEAR_LIST as u8,
-            ..Default::default()
-        };
-        unsafe { proc_err(ffi::Gen2_Select(&mut gen2_select))? }
+        apply_gen2_cfg(configuration)?;
 
         Ok(gen2::Gen2Reader::new(self.steal_connection()))
     }
@@ -187,6 +161,42 @@ pub unsafe trait BasicReader: Sized + ConnectionHolder {
         Ok(ver.sw_ver >= LOWEST_SW_VER && ver.hw_ver >= LOWEST_HW_VER)
     }
 
+    /// # Rebooting reader
+    ///
+    /// Reboots the reader firmware and reconnects to it on the same port.
+    /// All configuration is lost, so the returned reader is unconfigured.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    ///
+    /// let reader = reader.reboot()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the last connection error if the reader doesn't come back
+    /// within [`Reader::REBOOT_TIMEOUT`].
+    fn reboot(self) -> Result<Reader> {
+        let connection = self.steal_connection();
+        let port = connection.port().to_owned();
+        let options = connection.options().clone();
+
+        unsafe { ffi::Reboot() }
+
+        // The old link is gone, close it before reconnecting
+        drop(connection);
+
+        Reader::reconnect(&port, &options, Reader::REBOOT_TIMEOUT)
+    }
+
     /// # Disconnecting from reader
     ///
     /// This function disconnects from the reader.
@@ -201,19 +211,330 @@ pub unsafe trait BasicReader: Sized + ConnectionHolder {
     }
 
     /// # Adjusting antenna output power
+    ///
+    /// Switches the carrier on or off at the given frequency (kHz). The
+    /// setting is applied after `timeout` ms.
     // CB 6/19/25: could this be used to increase range?
     fn set_antenna_power(&mut self, on: bool, timeout: u16, frequency: u32) -> Result<()> {
-        unsafe {
-            proc_err(ffi::Set_AntennaPower(
-                &mut ffi::STUHFL_T_ST25RU3993_AntennaPower {
-                    mode: on as u8,
-                    timeout,
-                    frequency,
-                },
-            ))
+        let mut antenna_power = AntennaPower {
+            on,
+            timeout,
+            frequency,
         }
+        .as_ffi();
+        unsafe { proc_err(ffi::Set_AntennaPower(&mut antenna_power)) }
+    }
+
+    /// # Reading antenna output power
+    ///
+    /// Returns the current carrier state. See [`AntennaPower`].
+    fn get_antenna_power(&self) -> Result<AntennaPower> {
+        let mut antenna_power = ffi::STUHFL_T_ST25RU3993_AntennaPower::default();
+        unsafe { proc_err(ffi::Get_AntennaPower(&mut antenna_power))? }
+
+        Ok(AntennaPower::from(antenna_power))
     }
-    
+
+    /// # Continuous modulation
+    ///
+    /// Starts transmitting a continuous (optionally modulated) carrier, as
+    /// needed for spectrum analyser measurements and emission tests. The
+    /// carrier is switched off when the returned guard is dropped. See
+    /// [`ContinuousModulationCfg`] for settings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let mut reader = Reader::autoconnect()?;
+    ///
+    /// let cfg = ContinuousModulationCfg::builder()
+    ///     .frequency(915250)
+    ///     .mode(ContinuousModulationMode::PseudoRandom)
+    ///     .build()?;
+    ///
+    /// let modulation = reader.start_continuous_modulation(&cfg)?;
+    ///
+    /// // ... take measurements ...
+    /// std::thread::sleep(std::time::Duration::from_secs(5));
+    ///
+    /// // carrier is switched off here
+    /// modulation.stop()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn start_continuous_modulation(
+        &mut self,
+        cfg: &ContinuousModulationCfg,
+    ) -> Result<ContinuousModulation<'_, Self>> {
+        ContinuousModulation::start(self, cfg)
+    }
+
+    /// # Reading a register
+    ///
+    /// Reads a single register of the ST25RU3993. Requires the `advanced` feature.
+    #[cfg(feature = "advanced")]
+    fn get_register(&self, register: Register) -> Result<u8> {
+        let mut reg = ffi::STUHFL_T_ST25RU3993_Register {
+            addr: register.address(),
+            data: 0,
+        };
+        unsafe { proc_err(ffi::Get_Register(&mut reg))? }
+
+        Ok(reg.data)
+    }
+
+    /// # Writing a register
+    ///
+    /// Writes a single register of the ST25RU3993. Requires the `advanced` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`](crate::error::Error::Param) if the register
+    /// is not writable (see [`Register::is_writable()`]).
+    #[cfg(feature = "advanced")]
+    fn set_register(&mut self, register: Register, value: u8) -> Result<()> {
+        self.set_registers(&[(register, value)])
+    }
+
+    /// # Reading several registers
+    ///
+    /// Reads several registers at once, returning their values in the same
+    /// order. Requires the `advanced` feature.
+    #[cfg(feature = "advanced")]
+    fn get_registers(&self, registers: &[Register]) -> Result<Vec<u8>> {
+        let mut values = Vec::with_capacity(registers.len());
+
+        for chunk in registers.chunks(ffi::STUHFL_D_MAX_REGISTERS as usize) {
+            let mut regs: Vec<ffi::STUHFL_T_ST25RU3993_Register> = chunk
+                .iter()
+                .map(|register| ffi::STUHFL_T_ST25RU3993_Register {
+                    addr: register.address(),
+                    data: 0,
+                })
+                .collect();
+
+            unsafe {
+                proc_err(ffi::Get_RegisterMultiple(
+                    regs.len() as u8,
+                    &mut regs.as_mut_ptr(),
+                ))?
+            }
+
+            values.extend(regs.iter().map(|reg| reg.data));
+        }
+
+        Ok(values)
+    }
+
+    /// # Writing several registers
+    ///
+    /// Writes several registers at once. Requires the `advanced` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`](crate::error::Error::Param) if any register
+    /// is not writable (nothing is written in this case).
+    #[cfg(feature = "advanced")]
+    fn set_registers(&mut self, values: &[(Register, u8)]) -> Result<()> {
+        if values.iter().any(|(register, _)| !register.is_writable()) {
+            return Err(crate::error::Error::Param);
+        }
+
+        for chunk in values.chunks(ffi::STUHFL_D_MAX_REGISTERS as usize) {
+            let mut regs: Vec<ffi::STUHFL_T_ST25RU3993_Register> = chunk
+                .iter()
+                .map(|(register, value)| ffi::STUHFL_T_ST25RU3993_Register {
+                    addr: register.address(),
+                    data: *value,
+                })
+                .collect();
+
+            unsafe {
+                proc_err(ffi::Set_RegisterMultiple(
+                    &mut regs.as_mut_ptr(),
+                    regs.len() as u8,
+                ))?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Reading register bitfields
+    ///
+    /// Reads a register as a typed set of bitfields. Requires the `advanced` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::registers::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let mut reader = Reader::autoconnect()?;
+    ///
+    /// let mut dsc: DeviceStatusControl = reader.get_register_bits()?;
+    /// println!("RF on: {}", dsc.rf_on());
+    ///
+    /// dsc.set_agc_on(true);
+    /// reader.set_register_bits(dsc)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "advanced")]
+    fn get_register_bits<T: RegisterBits>(&self) -> Result<T> {
+        Ok(T::from(self.get_register(T::REGISTER)?))
+    }
+
+    /// # Writing register bitfields
+    ///
+    /// Writes a typed set of bitfields to its register. Requires the `advanced` feature.
+    #[cfg(feature = "advanced")]
+    fn set_register_bits<T: RegisterBits>(&mut self, bits: T) -> Result<()> {
+        self.set_register(T::REGISTER, bits.into())
+    }
+
+    /// # Dumping the register file
+    ///
+    /// Reads every named register (except those with read side effects, see
+    /// [`Register::has_read_side_effects()`]) into a [`RegisterDump`]. This can be
+    /// restored later with [`BasicReader::restore_registers()`], or compared against
+    /// another board using [`RegisterDump::diff()`]. Requires the `advanced` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let mut reader = Reader::autoconnect()?;
+    ///
+    /// let known_good = reader.dump_registers()?;
+    ///
+    /// // ... later ...
+    ///
+    /// for diff in reader.dump_registers()?.diff(&known_good) {
+    ///     println!("{}", diff);
+    /// }
+    ///
+    /// reader.restore_registers(&known_good)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "advanced")]
+    fn dump_registers(&self) -> Result<RegisterDump> {
+        let registers: Vec<Register> = Register::ALL
+            .iter()
+            .copied()
+            .filter(|register| !register.has_read_side_effects())
+            .collect();
+
+        let values = self.get_registers(&registers)?;
+
+        let dump: Vec<(Register, u8)> = registers.into_iter().zip(values).collect();
+
+        Ok(RegisterDump::from(&dump))
+    }
+
+    /// # Restoring the register file
+    ///
+    /// Writes back every writable register (see [`Register::is_writable()`])
+    /// contained in the dump. Read-only registers are skipped. Requires the
+    /// `advanced` feature.
+    #[cfg(feature = "advanced")]
+    fn restore_registers(&mut self, dump: &RegisterDump) -> Result<()> {
+        let values: Vec<(Register, u8)> = dump
+            .iter()
+            .filter(|(register, _)| register.is_writable())
+            .collect();
+
+        self.set_registers(&values)
+    }
+
+    /// # Reading reader configuration
+    ///
+    /// Reads a single board-level configuration value. See [`RwdConfigKey`]
+    /// for the meaning of each value, or [`BasicReader::read_all_rwd_config()`]
+    /// for a typed snapshot.
+    fn get_rwd_config(&self, key: RwdConfigKey) -> Result<u8> {
+        let mut rwd_cfg = ffi::STUHFL_T_ST25RU3993_RwdConfig {
+            id: key as u8,
+            value: 0,
+        };
+        unsafe { proc_err(ffi::Get_RwdCfg(&mut rwd_cfg))? }
+
+        Ok(rwd_cfg.value)
+    }
+
+    /// # Changing reader configuration
+    ///
+    /// Writes a single board-level configuration value. See [`RwdConfigKey`]
+    /// for the meaning of each value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`](crate::error::Error::Param) for
+    /// [`RwdConfigKey::HardwareId`], which is read only.
+    fn set_rwd_config(&mut self, key: RwdConfigKey, value: u8) -> Result<()> {
+        if key == RwdConfigKey::HardwareId {
+            return Err(crate::error::Error::Param);
+        }
+
+        let mut rwd_cfg = ffi::STUHFL_T_ST25RU3993_RwdConfig {
+            id: key as u8,
+            value,
+        };
+        unsafe { proc_err(ffi::Set_RwdCfg(&mut rwd_cfg)) }
+    }
+
+    /// # Reading power down mode
+    fn get_power_down_mode(&self) -> Result<PowerDownMode> {
+        let value = self.get_rwd_config(RwdConfigKey::PowerDownMode)?;
+
+        PowerDownMode::from_u8(value).ok_or(crate::error::Error::Proto)
+    }
+
+    /// # Changing power down mode
+    fn set_power_down_mode(&mut self, mode: PowerDownMode) -> Result<()> {
+        self.set_rwd_config(RwdConfigKey::PowerDownMode, mode as u8)
+    }
+
+    /// # Reading all reader configuration
+    ///
+    /// Reads every [`RwdConfigKey`] into an [`RwdConfig`] snapshot,
+    /// e.g. to log the board setup at start-up.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    ///
+    /// println!("{}", reader.read_all_rwd_config()?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn read_all_rwd_config(&self) -> Result<RwdConfig> {
+        Ok(RwdConfig {
+            power_down_mode: self.get_power_down_mode()?,
+            external_vco: self.get_rwd_config(RwdConfigKey::ExternalVco)? != 0,
+            external_power_amplifier: self.get_rwd_config(RwdConfigKey::PowerAmplifier)? != 0,
+            input: self.get_rwd_config(RwdConfigKey::Input)?,
+            antenna_switch: self.get_rwd_config(RwdConfigKey::AntennaSwitch)? != 0,
+            tuner: self.get_rwd_config(RwdConfigKey::Tuner)?,
+            hardware_id: HardwareId::from_u8(self.get_rwd_config(RwdConfigKey::HardwareId)?),
+        })
+    }
+
     /// # Adjusting amplifier power configuration
     fn set_power_amplifier_cfg(&mut self, external: bool) -> Result<()> {
         unsafe {
@@ -224,17 +545,72 @@ pub unsafe trait BasicReader: Sized + ConnectionHolder {
     }
 }
 
+/// Applies every setting of `configuration` to the connected reader and
+/// clears the select list, see [`BasicReader::configure_gen2()`]
+pub(crate) fn apply_gen2_cfg(configuration: &gen2::Gen2Cfg) -> Result<()> {
+    // Set up antenna configuration
+    let mut tx_rx_cfg = configuration.tx_rx_cfg.as_ffi();
+    unsafe { proc_err(ffi::Set_TxRxCfg(&mut tx_rx_cfg))? }
+
+    // Set up inventory configuration
+    let mut inv_cfg = configuration.inv_cfg.as_ffi();
+    unsafe { proc_err(ffi::Set_Gen2_InventoryCfg(&mut inv_cfg))? }
+
+    // Set up protocol configuration
+    let mut proto_cfg = configuration.proto_cfg.as_ffi();
+    unsafe { proc_err(ffi::Set_Gen2_ProtocolCfg(&mut proto_cfg))? }
+
+    // Set up calibrated filters for the chosen BLF and coding
+    if let Some(rx_filter) = configuration.proto_cfg.rx_filter() {
+        let mut rx_filter = rx_filter.as_ffi();
+        unsafe { proc_err(ffi::Set_Gen2_RxFilter(&mut rx_filter))? }
+    }
+    if let Some(filter_calibration) = configuration.proto_cfg.filter_calibration() {
+        let mut filter_calibration = filter_calibration.as_ffi();
+        unsafe { proc_err(ffi::Set_Gen2_FilterCalibration(&mut filter_calibration))? }
+    }
+
+    // Set up link timing configuration
+    let mut timings = configuration.timings.as_ffi();
+    unsafe { proc_err(ffi::Set_Gen2_Timings(&mut timings))? }
+
+    // Set up lbt configuraiton
+    let mut lbt = configuration.lbt.as_ffi();
+    unsafe { proc_err(ffi::Set_FreqLBT(&mut lbt))? }
+
+    // Set up channel list configuration
+    let mut channel_list = configuration.channel_list.as_ffi();
+    unsafe { proc_err(ffi::Set_ChannelList(&mut channel_list))? }
+
+    // Set up frequency hopping configuration
+    let mut freq_hop = configuration.freq_hop.as_ffi();
+    unsafe { proc_err(ffi::Set_FreqHop(&mut freq_hop))? }
+
+    // Clear select configuration
+    let mut gen2_select = ffi::STUHFL_T_Gen2_Select {
+        mode: ffi::STUHFL_D_GEN2_SELECT_MODE_CLEAR_LIST as u8,
+        ..Default::default()
+    };
+    unsafe { proc_err(ffi::Gen2_Select(&mut gen2_select))? }
+
+    Ok(())
+}
+
 /// Protocol-Specific reader commands. These include any commands that require
-/// the reader to be configured to use an RFID protocol. See [`BasicReader`]
-/// for more.
+/// the reader to be configured to use an RFID protocol, see [`ProtocolCommands`]
+/// for the commands and [`BasicReader`] for more.
 ///
 /// # Safety
 ///
 /// This trait must only be implemented for types that manage reader instances
 /// (see [`BasicReader`]). Furthermore, all readers implementing this trait must
-/// be able to handle the full funcitonality defined in this trait.
-pub unsafe trait ProtocolReader: BasicReader {
+/// be able to handle the full funcitonality defined in [`ProtocolCommands`].
+pub unsafe trait ProtocolReader: BasicReader + ProtocolCommands {}
 
+/// Inventory and tag access commands of a [`ProtocolReader`]. Doesn't
+/// require a connection, so readers without one (like the `ReplayReader`
+/// playing recordings back) can offer the same commands.
+pub trait ProtocolCommands {
     /// # Tuning reader
     ///
     /// Tune the reader using the specified tuning algorithm.
@@ -260,10 +636,10 @@ pub unsafe trait ProtocolReader: BasicReader {
     /// # Inventorying tags
     ///
     /// There are two ways to inventory tags, using this command or
-    /// the [`ProtocolReader::inventory()`] command. This command will only
+    /// the [`ProtocolCommands::inventory()`] command. This command will only
     /// run a single round and return a vector of tags. Running this
     /// in a loop will cause a huge amount of unecessary memory allocations
-    /// and copies, so it is recommended to use [`ProtocolReader::inventory()`]
+    /// and copies, so it is recommended to use [`ProtocolCommands::inventory()`]
     /// if several rounds are needed.
     ///
     /// # Example
@@ -289,7 +665,7 @@ pub unsafe trait ProtocolReader: BasicReader {
     /// # Inventorying tags (threaded)
     ///
     /// There are two ways to inventory tags, using this command or
-    /// the [`ProtocolReader::inventory_once()`] command. This command will run `num_rounds`
+    /// the [`ProtocolCommands::inventory_once()`] command. This command will run `num_rounds`
     /// inventory rounds, and for each round where tags are discovered the
     /// `data_cb` will be called. Note: this is a blocking call.
     ///
@@ -234,3 +234,229 @@ impl From<Error> for String {
         format!["{}", e]
     }
 }
+
+#[cfg(feature = "use-serde")]
+#[derive(Debug)]
+/// Error raised while loading or saving configuration files.
+/// See [`Gen2Cfg::from_toml_file()`](crate::gen2::Gen2Cfg::from_toml_file).
+pub enum ConfigFileError {
+    /// The file could not be read
+    Io(std::io::Error),
+    /// The file is not valid TOML or contains invalid settings
+    Parse(toml::de::Error),
+    /// The configuration could not be serialized
+    Serialize(toml::ser::Error),
+}
+
+#[cfg(feature = "use-serde")]
+impl fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFileError::Io(e) => write!(f, "Configuration file IO Error: {}", e),
+            ConfigFileError::Parse(e) => write!(f, "Configuration file Parse Error: {}", e),
+            ConfigFileError::Serialize(e) => write!(f, "Configuration Serialize Error: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "use-serde")]
+impl std::error::Error for ConfigFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigFileError::Io(e) => Some(e),
+            ConfigFileError::Parse(e) => Some(e),
+            ConfigFileError::Serialize(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "use-serde")]
+impl From<std::io::Error> for ConfigFileError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigFileError::Io(e)
+    }
+}
+
+#[cfg(feature = "use-serde")]
+impl From<toml::de::Error> for ConfigFileError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigFileError::Parse(e)
+    }
+}
+
+#[cfg(feature = "use-serde")]
+impl From<toml::ser::Error> for ConfigFileError {
+    fn from(e: toml::ser::Error) -> Self {
+        ConfigFileError::Serialize(e)
+    }
+}
+
+#[cfg(feature = "use-serde")]
+#[derive(Debug)]
+/// Error raised while recording or loading inventory sessions.
+/// See [`Recording::open()`](crate::recording::Recording::open).
+pub enum RecordingError {
+    /// The file could not be read or written
+    Io(std::io::Error),
+    /// A line of the file is not a valid record (1-based line number)
+    Parse(usize, serde_json::Error),
+    /// The file was written in an unsupported format version
+    UnsupportedFormat(u32),
+    /// The reader returned an error
+    Reader(Error),
+}
+
+#[cfg(feature = "use-serde")]
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(e) => write!(f, "Recording IO Error: {}", e),
+            RecordingError::Parse(line, e) => {
+                write!(f, "Recording Parse Error on line {}: {}", line, e)
+            }
+            RecordingError::UnsupportedFormat(format) => {
+                write!(f, "Unsupported recording format: {}", format)
+            }
+            RecordingError::Reader(e) => write!(f, "Reader {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "use-serde")]
+impl std::error::Error for RecordingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecordingError::Io(e) => Some(e),
+            RecordingError::Parse(_, e) => Some(e),
+            RecordingError::UnsupportedFormat(_) => None,
+            RecordingError::Reader(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "use-serde")]
+impl From<std::io::Error> for RecordingError {
+    fn from(e: std::io::Error) -> Self {
+        RecordingError::Io(e)
+    }
+}
+
+#[cfg(feature = "use-serde")]
+impl From<Error> for RecordingError {
+    fn from(e: Error) -> Self {
+        RecordingError::Reader(e)
+    }
+}
+
+#[cfg(feature = "firmware")]
+#[derive(Debug)]
+/// Error raised during a firmware upgrade.
+/// See [`Reader::upgrade_firmware()`](crate::reader::Reader::upgrade_firmware).
+pub enum FirmwareError {
+    /// The firmware image could not be read, or the bootloader link failed
+    Io(std::io::Error),
+    /// The bootloader port could not be opened
+    Serial(serialport::Error),
+    /// The reader returned an error
+    Reader(Error),
+    /// The firmware image is not a valid image for the reader
+    InvalidImage(String),
+    /// The bootloader rejected a command or did not respond
+    Bootloader(String),
+    /// The flash contents differ from the image at the given address
+    VerifyFailed(u32),
+    /// The reader came back with a different firmware version
+    VersionMismatch {
+        /// Version requested in the upgrade options
+        expected: crate::data_types::VersionNum,
+        /// Version reported by the reader
+        found: crate::data_types::VersionNum,
+    },
+}
+
+#[cfg(feature = "firmware")]
+impl fmt::Display for FirmwareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FirmwareError::Io(e) => write!(f, "Firmware upgrade IO Error: {}", e),
+            FirmwareError::Serial(e) => write!(f, "Bootloader port Error: {}", e),
+            FirmwareError::Reader(e) => write!(f, "Reader {}", e),
+            FirmwareError::InvalidImage(e) => write!(f, "Invalid firmware image: {}", e),
+            FirmwareError::Bootloader(e) => write!(f, "Bootloader Error: {}", e),
+            FirmwareError::VerifyFailed(addr) => {
+                write!(f, "Firmware verification failed at 0x{:08X}", addr)
+            }
+            FirmwareError::VersionMismatch { expected, found } => write!(
+                f,
+                "Firmware version mismatch: expected {}, found {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "firmware")]
+impl std::error::Error for FirmwareError {}
+
+#[cfg(feature = "firmware")]
+impl From<std::io::Error> for FirmwareError {
+    fn from(e: std::io::Error) -> Self {
+        FirmwareError::Io(e)
+    }
+}
+
+#[cfg(feature = "firmware")]
+impl From<serialport::Error> for FirmwareError {
+    fn from(e: serialport::Error) -> Self {
+        FirmwareError::Serial(e)
+    }
+}
+
+#[cfg(feature = "firmware")]
+impl From<Error> for FirmwareError {
+    fn from(e: Error) -> Self {
+        FirmwareError::Reader(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Error raised while decoding or encoding EPCs according to the GS1
+/// Tag Data Standard. See [`Gs1Epc`](crate::tds::Gs1Epc).
+pub enum TdsError {
+    /// The EPC header doesn't belong to a supported scheme
+    UnknownHeader(u8),
+    /// The EPC is shorter than its scheme requires (in bits)
+    Length {
+        /// Number of bits the scheme requires
+        expected: usize,
+        /// Number of bits available
+        found: usize,
+    },
+    /// The partition value is out of range
+    Partition(u8),
+    /// A field is invalid or doesn't fit the scheme
+    Field(&'static str),
+    /// The EPC URI is malformed
+    Uri(String),
+    /// The EPC uses a feature which isn't supported
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for TdsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TdsError::UnknownHeader(header) => write!(f, "Unknown EPC header 0x{:02X}", header),
+            TdsError::Length { expected, found } => write!(
+                f,
+                "EPC too short: expected {} bits, found {}",
+                expected, found
+            ),
+            TdsError::Partition(partition) => write!(f, "Invalid partition value {}", partition),
+            TdsError::Field(field) => write!(f, "Invalid EPC field: {}", field),
+            TdsError::Uri(uri) => write!(f, "Invalid EPC URI: {}", uri),
+            TdsError::Unsupported(what) => write!(f, "Unsupported EPC feature: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for TdsError {}
@@ -0,0 +1,552 @@
+//! Firmware management for the reader board.
+//!
+//! The middleware's `STUHFL_F_Upgrade` is not implemented in the C library,
+//! so firmware images are flashed through the STM32 ROM bootloader instead:
+//! the reader is switched into its bootloader, the image is streamed over
+//! the same port using the bootloader UART protocol (ST AN3155), and the
+//! new firmware is started and reconnected to. See
+//! [`Reader::upgrade_firmware()`] to get started.
+
+use crate::data_types::*;
+use crate::error::FirmwareError;
+use crate::reader::Reader;
+use std::io::{ErrorKind, Read, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Result type used by firmware operations
+type Result<T> = core::result::Result<T, FirmwareError>;
+
+/// Time the board needs to restart into its bootloader
+const BOOTLOADER_STARTUP: Duration = Duration::from_millis(1000);
+
+#[derive(Clone, PartialEq, Debug)]
+/// Raw binary (`.bin`) firmware image for the STM32L4 MCU of the reader.
+/// Images are checked on load, see [`Self::from_bytes()`].
+pub struct FirmwareImage {
+    /// Image contents, padded to the flash write size
+    data: Vec<u8>,
+}
+
+impl FirmwareImage {
+    /// Flash address the image is written to
+    pub const FLASH_BASE: u32 = 0x0800_0000;
+    /// Flash size of the reader MCU in bytes
+    pub const FLASH_SIZE: usize = 1024 * 1024;
+    /// Flash page (erase unit) size in bytes
+    pub const PAGE_SIZE: usize = 2048;
+    /// SRAM range the initial stack pointer has to point into
+    const SRAM: RangeInclusive<u32> = 0x2000_0000..=0x2002_0000;
+    /// Flash write unit (double word) in bytes
+    const WRITE_SIZE: usize = 8;
+
+    /// # Loading firmware images
+    ///
+    /// Loads and checks a firmware image from a `.bin` file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::firmware::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let image = FirmwareImage::from_file("ST25RU3993_EVAL_FW.bin")?;
+    ///
+    /// println!("Image size: {} bytes", image.size());
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// Checks a firmware image given as raw bytes. The image must fit into
+    /// flash and start with a vector table whose initial stack pointer
+    /// points into SRAM and whose reset handler lies within the image.
+    /// Intel HEX and ELF files are rejected, convert them to a raw binary first.
+    pub fn from_bytes(mut data: Vec<u8>) -> Result<Self> {
+        if data.starts_with(b":") || data.starts_with(b"\x7fELF") {
+            return Err(FirmwareError::InvalidImage(
+                "expected a raw binary image, not Intel HEX or ELF".to_owned(),
+            ));
+        }
+
+        if data.len() < Self::WRITE_SIZE || data.len() > Self::FLASH_SIZE {
+            return Err(FirmwareError::InvalidImage(format!(
+                "image size of {} bytes must be within {}..={} bytes",
+                data.len(),
+                Self::WRITE_SIZE,
+                Self::FLASH_SIZE
+            )));
+        }
+
+        let stack_pointer = word(&data, 0);
+        if !Self::SRAM.contains(&stack_pointer) || stack_pointer & 0x3 != 0 {
+            return Err(FirmwareError::InvalidImage(format!(
+                "initial stack pointer 0x{:08X} is not in SRAM",
+                stack_pointer
+            )));
+        }
+
+        // Cortex-M vectors have the thumb bit set
+        let reset_handler = word(&data, 1);
+        let image_range = Self::FLASH_BASE..Self::FLASH_BASE + data.len() as u32;
+        if reset_handler & 1 == 0 || !image_range.contains(&(reset_handler & !1)) {
+            return Err(FirmwareError::InvalidImage(format!(
+                "reset handler 0x{:08X} is not within the image",
+                reset_handler
+            )));
+        }
+
+        // Fill up the last double word with erased flash
+        let padded = data.len().div_ceil(Self::WRITE_SIZE) * Self::WRITE_SIZE;
+        data.resize(padded, 0xFF);
+
+        Ok(Self { data })
+    }
+
+    /// Size of the image in bytes (including padding)
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Image contents
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Initial stack pointer from the vector table
+    pub fn initial_stack_pointer(&self) -> u32 {
+        word(&self.data, 0)
+    }
+
+    /// Reset handler address from the vector table
+    pub fn reset_handler(&self) -> u32 {
+        word(&self.data, 1)
+    }
+
+    /// Flash pages covered by the image
+    fn pages(&self) -> Vec<u16> {
+        let count = self.data.len().div_ceil(Self::PAGE_SIZE);
+
+        (0..count as u16).collect()
+    }
+}
+
+/// Reads the little endian word at the given word index
+fn word(data: &[u8], index: usize) -> u32 {
+    let i = index * 4;
+
+    u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]])
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Stage of a firmware upgrade, reported to the progress callback of
+/// [`Reader::upgrade_firmware()`].
+pub enum UpgradeProgress {
+    /// Switching the reader into its bootloader
+    EnteringBootloader,
+    /// Erasing flash pages
+    Erasing {
+        /// Pages erased so far
+        done: usize,
+        /// Pages to erase
+        total: usize,
+    },
+    /// Writing the image
+    Writing {
+        /// Bytes written so far
+        done: usize,
+        /// Bytes to write
+        total: usize,
+    },
+    /// Reading back the image
+    Verifying {
+        /// Bytes verified so far
+        done: usize,
+        /// Bytes to verify
+        total: usize,
+    },
+    /// Starting the new firmware
+    Rebooting,
+    /// Waiting for the reader to come back
+    Reconnecting,
+}
+
+#[derive(Builder, Clone, PartialEq, Debug)]
+/// Firmware upgrade settings. See [`Self::builder()`] for details.
+pub struct UpgradeOptions {
+    /// Firmware version the reader must report after the upgrade.
+    /// If not set, the version is not checked.
+    #[builder(default = "None", setter(strip_option))]
+    expected_version: Option<VersionNum>,
+    /// Read back and compare the image after writing it
+    #[builder(default = "true")]
+    verify: bool,
+    /// Baud rate of the bootloader link
+    #[builder(default = "115200")]
+    baud_rate: u32,
+    /// Time the new firmware may take to come up
+    #[builder(default = "Reader::REBOOT_TIMEOUT")]
+    reconnect_timeout: Duration,
+    /// Serial link settings used to reconnect to the new firmware
+    #[builder(default)]
+    connection_options: ConnectionOptions,
+}
+
+impl Builder<UpgradeOptionsBuilder> for UpgradeOptions {}
+
+impl Reader {
+    /// # Upgrading firmware
+    ///
+    /// Flashes a firmware image and reconnects to the reader once the new
+    /// firmware is running. The reader is switched into its STM32 ROM
+    /// bootloader, the flash pages covered by the image are erased, the
+    /// image is written (and read back, if enabled), and the new firmware
+    /// is started. Progress is reported through `progress`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::firmware::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let image = FirmwareImage::from_file("ST25RU3993_EVAL_FW.bin")?;
+    ///
+    /// let options = UpgradeOptions::builder()
+    ///     .expected_version(VersionNum {
+    ///         major: 3,
+    ///         minor: 1,
+    ///         micro: 0,
+    ///         nano: 0,
+    ///     })
+    ///     .build()?;
+    ///
+    /// let reader = Reader::autoconnect()?;
+    ///
+    /// let reader = reader.upgrade_firmware(&image, &options, |progress| {
+    ///     println!("{:?}", progress);
+    /// })?;
+    ///
+    /// println!("Running {}", reader.get_version()?);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Any error after the bootloader was entered leaves the reader in its
+    /// bootloader (or with a partially written image). The upgrade can
+    /// simply be repeated with [`upgrade_from_bootloader()`].
+    pub fn upgrade_firmware<F>(
+        self,
+        image: &FirmwareImage,
+        options: &UpgradeOptions,
+        mut progress: F,
+    ) -> Result<Reader>
+    where
+        F: FnMut(UpgradeProgress),
+    {
+        progress(UpgradeProgress::EnteringBootloader);
+
+        let connection = self.steal_connection();
+        let port = connection.port().to_owned();
+
+        unsafe { ffi::EnterBootloader() }
+
+        // The firmware is gone, release the port for the bootloader
+        drop(connection);
+        thread::sleep(BOOTLOADER_STARTUP);
+
+        upgrade_from_bootloader(&port, image, options, progress)
+    }
+}
+
+/// # Upgrading firmware
+///
+/// Same as [`Reader::upgrade_firmware()`], for a reader that is already
+/// running its bootloader, e.g. after a failed upgrade.
+///
+/// # Example
+///
+/// ```no_run
+/// use libstuhfl::prelude::*;
+/// use libstuhfl::firmware::*;
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+///
+/// let image = FirmwareImage::from_file("ST25RU3993_EVAL_FW.bin")?;
+/// let options = UpgradeOptions::builder().build()?;
+///
+/// let reader = upgrade_from_bootloader("/dev/ttyUSB0", &image, &options, |_| {})?;
+///
+/// # Ok(())
+/// # }
+/// ```
+pub fn upgrade_from_bootloader<F>(
+    port: &str,
+    image: &FirmwareImage,
+    options: &UpgradeOptions,
+    mut progress: F,
+) -> Result<Reader>
+where
+    F: FnMut(UpgradeProgress),
+{
+    // AN3155 uses 8 data bits with even parity
+    let link = serialport::new(port, options.baud_rate)
+        .data_bits(serialport::DataBits::Eight)
+        .parity(serialport::Parity::Even)
+        .stop_bits(serialport::StopBits::One)
+        .flow_control(serialport::FlowControl::None)
+        .timeout(Duration::from_millis(100))
+        .open()?;
+
+    let mut bootloader = Bootloader::new(link);
+    bootloader.sync()?;
+
+    flash(&mut bootloader, image, options.verify, &mut progress)?;
+
+    progress(UpgradeProgress::Rebooting);
+    bootloader.go(FirmwareImage::FLASH_BASE)?;
+    drop(bootloader);
+
+    progress(UpgradeProgress::Reconnecting);
+    let reader = Reader::reconnect(port, &options.connection_options, options.reconnect_timeout)?;
+
+    if let Some(expected) = options.expected_version {
+        let found = reader.get_version()?.sw_ver;
+
+        if found != expected {
+            return Err(FirmwareError::VersionMismatch { expected, found });
+        }
+    }
+
+    Ok(reader)
+}
+
+/// Erases, writes and optionally verifies an image through the bootloader
+pub(crate) fn flash<T: Read + Write>(
+    bootloader: &mut Bootloader<T>,
+    image: &FirmwareImage,
+    verify: bool,
+    progress: &mut dyn FnMut(UpgradeProgress),
+) -> Result<()> {
+    /// Pages erased per erase command, so progress can be reported
+    const ERASE_BATCH: usize = 16;
+
+    let pages = image.pages();
+    let total = pages.len();
+
+    for (i, batch) in pages.chunks(ERASE_BATCH).enumerate() {
+        bootloader.erase_pages(batch)?;
+
+        progress(UpgradeProgress::Erasing {
+            done: i * ERASE_BATCH + batch.len(),
+            total,
+        });
+    }
+
+    let total = image.size();
+    let chunk_size = Bootloader::<T>::MAX_CHUNK;
+
+    for (i, chunk) in image.data().chunks(chunk_size).enumerate() {
+        let offset = i * chunk_size;
+        bootloader.write_memory(FirmwareImage::FLASH_BASE + offset as u32, chunk)?;
+
+        progress(UpgradeProgress::Writing {
+            done: offset + chunk.len(),
+            total,
+        });
+    }
+
+    if verify {
+        for (i, chunk) in image.data().chunks(chunk_size).enumerate() {
+            let offset = i * chunk_size;
+            let address = FirmwareImage::FLASH_BASE + offset as u32;
+            let flashed = bootloader.read_memory(address, chunk.len())?;
+
+            if let Some(pos) = chunk.iter().zip(&flashed).position(|(a, b)| a != b) {
+                return Err(FirmwareError::VerifyFailed(address + pos as u32));
+            }
+
+            progress(UpgradeProgress::Verifying {
+                done: offset + chunk.len(),
+                total,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Client for the STM32 ROM bootloader UART protocol (ST AN3155)
+pub(crate) struct Bootloader<T: Read + Write> {
+    /// Link to the bootloader
+    link: T,
+}
+
+impl<T: Read + Write> Bootloader<T> {
+    /// Largest block for read and write commands
+    pub(crate) const MAX_CHUNK: usize = 256;
+    /// Synchronization byte sent after reset
+    const SYNC: u8 = 0x7F;
+    /// Positive acknowledge
+    const ACK: u8 = 0x79;
+    /// Negative acknowledge
+    const NACK: u8 = 0x1F;
+    /// Read Memory command
+    const CMD_READ_MEMORY: u8 = 0x11;
+    /// Go command
+    const CMD_GO: u8 = 0x21;
+    /// Write Memory command
+    const CMD_WRITE_MEMORY: u8 = 0x31;
+    /// Extended Erase command
+    const CMD_EXTENDED_ERASE: u8 = 0x44;
+    /// Time to wait for regular replies
+    const REPLY_TIMEOUT: Duration = Duration::from_secs(1);
+    /// Time to wait for an erase to complete
+    const ERASE_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Wraps a link to a bootloader
+    pub(crate) fn new(link: T) -> Self {
+        Self { link }
+    }
+
+    /// Returns the link to the bootloader
+    #[cfg(test)]
+    pub(crate) fn into_link(self) -> T {
+        self.link
+    }
+
+    /// Synchronizes with the bootloader (baud rate detection)
+    pub(crate) fn sync(&mut self) -> Result<()> {
+        let mut last_err = None;
+
+        // The bootloader may still be starting up
+        for _ in 0..3 {
+            self.send(&[Self::SYNC])?;
+
+            // A NACK means the bootloader is already synchronized
+            match self.reply(Self::REPLY_TIMEOUT) {
+                Ok(Self::ACK) | Ok(Self::NACK) => return Ok(()),
+                Ok(other) => {
+                    last_err = Some(FirmwareError::Bootloader(format!(
+                        "unexpected reply 0x{:02X} to sync",
+                        other
+                    )))
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        Err(last_err.unwrap())
+    }
+
+    /// Erases the given flash pages
+    pub(crate) fn erase_pages(&mut self, pages: &[u16]) -> Result<()> {
+        self.command(Self::CMD_EXTENDED_ERASE)?;
+
+        let mut frame = ((pages.len() - 1) as u16).to_be_bytes().to_vec();
+        for page in pages {
+            frame.extend_from_slice(&page.to_be_bytes());
+        }
+        frame.push(checksum(&frame));
+
+        self.send(&frame)?;
+        self.ack(Self::ERASE_TIMEOUT, "erase")
+    }
+
+    /// Writes up to [`Self::MAX_CHUNK`] bytes to memory
+    pub(crate) fn write_memory(&mut self, address: u32, data: &[u8]) -> Result<()> {
+        self.command(Self::CMD_WRITE_MEMORY)?;
+        self.address(address)?;
+
+        let mut frame = vec![(data.len() - 1) as u8];
+        frame.extend_from_slice(data);
+        frame.push(checksum(&frame));
+
+        self.send(&frame)?;
+        self.ack(Self::REPLY_TIMEOUT, "write memory")
+    }
+
+    /// Reads up to [`Self::MAX_CHUNK`] bytes from memory
+    pub(crate) fn read_memory(&mut self, address: u32, len: usize) -> Result<Vec<u8>> {
+        self.command(Self::CMD_READ_MEMORY)?;
+        self.address(address)?;
+
+        let n = (len - 1) as u8;
+        self.send(&[n, !n])?;
+        self.ack(Self::REPLY_TIMEOUT, "read memory")?;
+
+        (0..len).map(|_| self.reply(Self::REPLY_TIMEOUT)).collect()
+    }
+
+    /// Starts the application whose vector table is at `address`
+    pub(crate) fn go(&mut self, address: u32) -> Result<()> {
+        self.command(Self::CMD_GO)?;
+        self.address(address)
+    }
+
+    /// Sends a command byte and its complement
+    fn command(&mut self, cmd: u8) -> Result<()> {
+        self.send(&[cmd, !cmd])?;
+        self.ack(Self::REPLY_TIMEOUT, "command")
+    }
+
+    /// Sends a big endian address with its checksum
+    fn address(&mut self, address: u32) -> Result<()> {
+        let mut frame = address.to_be_bytes().to_vec();
+        frame.push(checksum(&frame));
+
+        self.send(&frame)?;
+        self.ack(Self::REPLY_TIMEOUT, "address")
+    }
+
+    /// Writes raw bytes to the link
+    fn send(&mut self, bytes: &[u8]) -> Result<()> {
+        self.link.write_all(bytes)?;
+        self.link.flush()?;
+
+        Ok(())
+    }
+
+    /// Waits for an ACK, reporting what was rejected otherwise
+    fn ack(&mut self, timeout: Duration, what: &str) -> Result<()> {
+        match self.reply(timeout)? {
+            Self::ACK => Ok(()),
+            Self::NACK => Err(FirmwareError::Bootloader(format!("{} rejected", what))),
+            other => Err(FirmwareError::Bootloader(format!(
+                "unexpected reply 0x{:02X} to {}",
+                other, what
+            ))),
+        }
+    }
+
+    /// Reads a single byte, waiting up to `timeout`
+    fn reply(&mut self, timeout: Duration) -> Result<u8> {
+        let deadline = Instant::now() + timeout;
+        let mut byte = [0u8];
+
+        loop {
+            match self.link.read(&mut byte) {
+                Ok(1) => return Ok(byte[0]),
+                Ok(_) => (),
+                Err(e) if e.kind() == ErrorKind::TimedOut => (),
+                Err(e) => return Err(e.into()),
+            }
+
+            if Instant::now() >= deadline {
+                return Err(FirmwareError::Bootloader("no response".to_owned()));
+            }
+        }
+    }
+}
+
+/// XOR checksum used by the bootloader frames
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |acc, b| acc ^ b)
+}
@@ -3,7 +3,8 @@ use crate::ffi;
 
 // Very similar to the Antenna enum in enums.rs
 enum_from_primitive! {
-    #[derive(Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// One of four Gen2 Sessions
     pub enum Gen2Session {
@@ -19,7 +20,8 @@ enum_from_primitive! {
 }
 
 enum_from_primitive! {
-    #[derive(Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// Query tags who's inventoried flag is A or B.
     ///
@@ -33,42 +35,79 @@ enum_from_primitive! {
 }
 
 enum_from_primitive! {
-    #[derive(Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// TARI values are the length of time to represent a
     /// binary 0 using the Gen 2 standard (in microseconds)
     pub enum Gen2Tari {
         /// 6.25 μs tari
+        #[cfg_attr(feature = "use-serde", serde(rename = "6.25us", alias = "Six"))]
         Six = ffi::STUHFL_D_GEN2_TARI_6_25 as u8,
         /// 12.5 μs tari
+        #[cfg_attr(feature = "use-serde", serde(rename = "12.5us", alias = "Twelve"))]
         Twelve = ffi::STUHFL_D_GEN2_TARI_12_50 as u8,
         /// 25 μs tari
+        #[cfg_attr(feature = "use-serde", serde(rename = "25us", alias = "TwentyFive"))]
         TwentyFive = ffi::STUHFL_D_GEN2_TARI_25_00 as u8,
     }
 }
 
+impl Gen2Tari {
+    /// Tari length in μs
+    pub fn micros(self) -> f32 {
+        match self {
+            Gen2Tari::Six => 6.25,
+            Gen2Tari::Twelve => 12.5,
+            Gen2Tari::TwentyFive => 25.0,
+        }
+    }
+}
+
 enum_from_primitive! {
-    #[derive(Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// BLF is the Backscatter Link Frequency of the transmission (in kHz)
     pub enum Gen2Blf {
         /// 40 kHz BLF
+        #[cfg_attr(feature = "use-serde", serde(rename = "40kHz", alias = "Forty"))]
         Forty = ffi::STUHFL_D_GEN2_BLF_40 as u8,
         /// 160 kHz BLF
+        #[cfg_attr(feature = "use-serde", serde(rename = "160kHz", alias = "OneHundredSixty"))]
         OneHundredSixty = ffi::STUHFL_D_GEN2_BLF_160 as u8,
         /// 213 kHz BLF
+        #[cfg_attr(feature = "use-serde", serde(rename = "213kHz", alias = "TwoHundredThirteen"))]
         TwoHundredThirteen = ffi::STUHFL_D_GEN2_BLF_213 as u8,
         /// 256 kHz BLF
+        #[cfg_attr(feature = "use-serde", serde(rename = "256kHz", alias = "TwoHundredFiftySix"))]
         TwoHundredFiftySix = ffi::STUHFL_D_GEN2_BLF_256 as u8,
         /// 320 kHz BLF
+        #[cfg_attr(feature = "use-serde", serde(rename = "320kHz", alias = "ThreeHundredTwenty"))]
         ThreeHundredTwenty = ffi::STUHFL_D_GEN2_BLF_320 as u8,
         /// 640 kHz BLF
+        #[cfg_attr(feature = "use-serde", serde(rename = "640kHz", alias = "SixHundredForty"))]
         SixHundredForty = ffi::STUHFL_D_GEN2_BLF_640 as u8,
     }
 }
 
+impl Gen2Blf {
+    /// Backscatter link frequency in kHz
+    pub fn khz(self) -> f32 {
+        match self {
+            Gen2Blf::Forty => 40.0,
+            Gen2Blf::OneHundredSixty => 160.0,
+            Gen2Blf::TwoHundredThirteen => 640.0 / 3.0,
+            Gen2Blf::TwoHundredFiftySix => 256.0,
+            Gen2Blf::ThreeHundredTwenty => 320.0,
+            Gen2Blf::SixHundredForty => 640.0,
+        }
+    }
+}
+
 enum_from_primitive! {
-    #[derive(Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Debug)]
     #[repr(u8)]
     /// Gen2 protocol coding scheme
     pub enum Gen2Coding {
@@ -83,7 +122,21 @@ enum_from_primitive! {
     }
 }
 
-#[derive(Copy, Clone)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Presets for the AdaptiveQ algorithm parameters.
+/// See [`Gen2AdaptiveQCfgBuilder::preset()`].
+pub enum Gen2AdaptiveQPreset {
+    /// Firmware defaults
+    Default,
+    /// Many tags in the field at once
+    DensePopulation,
+    /// Few tags in the field at once
+    SparsePopulation,
+}
+
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
 /// AdaptiveQ Configuration. The Q factor determines how many slots are made
 /// during a query event (e.g. inventorying). Each tag chooses a random number
 /// from 0..2^Q, which is used in access commands. Note that if this is too small,
@@ -94,3 +147,44 @@ pub enum Gen2AdaptiveQ {
     /// Set manual Q
     Disable(u8),
 }
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// How much of an EPC change made it onto the tag.
+/// See [`Gen2Reader::write_epc()`](super::Gen2Reader::write_epc).
+pub enum Gen2EpcWriteStatus {
+    /// The new EPC and PC were written and read back successfully
+    Complete,
+    /// Some words were written, but the tag doesn't hold the new EPC
+    Partial,
+    /// Nothing was written, the tag still holds its old EPC (words are
+    /// compared with the EPC read from the tag, not with `current`)
+    Unchanged,
+}
+
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// Lock action applied to a memory bank or password.
+/// See [`Gen2LockCfg`].
+pub enum Gen2LockAction {
+    /// Writable (readable for passwords) without the access password
+    Unlock,
+    /// Permanently writable, can never be locked again
+    PermaUnlock,
+    /// Only writable (readable for passwords) in the secured state,
+    /// i.e. after giving the access password
+    Lock,
+    /// Permanently unwritable (unreadable for passwords)
+    PermaLock,
+}
+
+impl Gen2LockAction {
+    /// Action bits as sent in the Lock command (pwd-write, permalock)
+    pub(crate) fn bits(self) -> u32 {
+        match self {
+            Gen2LockAction::Unlock => 0b00,
+            Gen2LockAction::PermaUnlock => 0b01,
+            Gen2LockAction::Lock => 0b10,
+            Gen2LockAction::PermaLock => 0b11,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::data_types::*;
+use crate::error::{Error, Result};
+
+/// Maximum EPC length the PC length field can describe, in words
+const MAX_EPC_WORDS: u8 = 0x1F;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// Protocol Control word, stored in front of the EPC (word 1 of the EPC
+/// memory bank) and backscattered during inventory.
+///
+/// # Example
+///
+/// ```
+/// use libstuhfl::gen2::*;
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+///
+/// let pc = ProtocolControl::from([0x30, 0x00]);
+/// assert_eq!(pc.epc_words, 6);
+///
+/// // PC for a 128 bit EPC
+/// let pc = pc.with_epc_len(16)?;
+/// assert_eq!(pc.to_bytes(), [0x40, 0x00]);
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct ProtocolControl {
+    /// Length of the EPC in words (bits 15:11)
+    pub epc_words: u8,
+    /// User memory indicator: the User bank contains data (bit 10)
+    pub umi: bool,
+    /// XPC_W1 indicator: the tag has a non-zero XPC (bit 9)
+    pub xi: bool,
+    /// Numbering system toggle: `true` if the EPC is an ISO identifier
+    /// and [`Self::afi`] holds its AFI (bit 8)
+    pub toggle: bool,
+    /// Application family identifier if [`Self::toggle`] is set, EPCglobal
+    /// attribute bits otherwise (bits 7:0)
+    pub afi: u8,
+}
+
+impl ProtocolControl {
+    /// Raw PC word
+    pub fn to_word(self) -> u16 {
+        (self.epc_words as u16) << 11
+            | (self.umi as u16) << 10
+            | (self.xi as u16) << 9
+            | (self.toggle as u16) << 8
+            | self.afi as u16
+    }
+
+    /// PC word as stored in tag memory
+    pub fn to_bytes(self) -> [u8; 2] {
+        self.to_word().to_be_bytes()
+    }
+
+    /// Numbering system identifier: toggle bit and AFI/attribute bits (9 bits)
+    pub fn nsi(self) -> u16 {
+        self.to_word() & 0x1FF
+    }
+
+    /// Length of the EPC in bytes
+    pub fn epc_len(self) -> usize {
+        self.epc_words as usize * 2
+    }
+
+    /// Returns this PC with the length field set for an EPC of `len` bytes,
+    /// keeping all other bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`] if `len` isn't a whole number of words or
+    /// is longer than 62 bytes.
+    pub fn with_epc_len(self, len: usize) -> Result<Self> {
+        if len & 1 != 0 || len / 2 > MAX_EPC_WORDS as usize {
+            return Err(Error::Param);
+        }
+
+        Ok(Self {
+            epc_words: (len / 2) as u8,
+            ..self
+        })
+    }
+}
+
+impl From<u16> for ProtocolControl {
+    fn from(word: u16) -> Self {
+        Self {
+            epc_words: (word >> 11) as u8,
+            umi: word & 0x0400 != 0,
+            xi: word & 0x0200 != 0,
+            toggle: word & 0x0100 != 0,
+            afi: word as u8,
+        }
+    }
+}
+
+impl From<[u8; 2]> for ProtocolControl {
+    fn from(bytes: [u8; 2]) -> Self {
+        Self::from(u16::from_be_bytes(bytes))
+    }
+}
+
+impl From<ProtocolControl> for u16 {
+    fn from(pc: ProtocolControl) -> u16 {
+        pc.to_word()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+/// Extended Protocol Control words (XPC_W1 and optional XPC_W2),
+/// stored at word 0x21 of the EPC memory bank.
+pub struct ExtendedPc {
+    /// XPC_W2 indicator: XPC_W2 follows (bit 15)
+    pub xeb: bool,
+    /// Sensor alarm (bit 11)
+    pub sensor_alarm: bool,
+    /// Simple sensor: the tag has a simple sensor (bit 10)
+    pub simple_sensor: bool,
+    /// File serving: the tag supports file serving (bit 9)
+    pub file_serving: bool,
+    /// Snapshot: the tag has a snapshot sensor (bit 8)
+    pub snapshot: bool,
+    /// Battery-assisted passive tag (bit 7)
+    pub battery_assisted: bool,
+    /// Computed response: the tag backscatters a response to
+    /// Authenticate during inventory (bit 6)
+    pub computed_response: bool,
+    /// Security localized indicator: the tag has an active security
+    /// mode (bit 5)
+    pub sli: bool,
+    /// Tag notification: the tag requests attention (bit 4)
+    pub tag_notification: bool,
+    /// Untraceable: the tag hides parts of its memory (bit 3)
+    pub untraceable: bool,
+    /// Killable: the tag can be killed (bit 2)
+    pub killable: bool,
+    /// Non-removable: the tag is part of the item (bit 1)
+    pub non_removable: bool,
+    /// Hazmat: the item is hazardous material (bit 0)
+    pub hazmat: bool,
+    /// Raw XPC_W2, if present
+    pub xpc_w2: Option<u16>,
+}
+
+impl ExtendedPc {
+    /// Decodes XPC words as reported by inventory. Returns `None` if
+    /// there are less than 2 bytes.
+    pub fn decode(xpc: &[u8]) -> Option<Self> {
+        let w1 = u16::from_be_bytes([*xpc.first()?, *xpc.get(1)?]);
+        let bit = |n: u16| w1 & (1 << n) != 0;
+
+        let xpc_w2 = match xpc.get(2..4) {
+            Some(w2) if bit(15) => Some(u16::from_be_bytes([w2[0], w2[1]])),
+            _ => None,
+        };
+
+        Some(Self {
+            xeb: bit(15),
+            sensor_alarm: bit(11),
+            simple_sensor: bit(10),
+            file_serving: bit(9),
+            snapshot: bit(8),
+            battery_assisted: bit(7),
+            computed_response: bit(6),
+            sli: bit(5),
+            tag_notification: bit(4),
+            untraceable: bit(3),
+            killable: bit(2),
+            non_removable: bit(1),
+            hazmat: bit(0),
+            xpc_w2,
+        })
+    }
+
+    /// Raw XPC_W1 word
+    pub fn w1(&self) -> u16 {
+        [
+            (self.xeb, 15),
+            (self.sensor_alarm, 11),
+            (self.simple_sensor, 10),
+            (self.file_serving, 9),
+            (self.snapshot, 8),
+            (self.battery_assisted, 7),
+            (self.computed_response, 6),
+            (self.sli, 5),
+            (self.tag_notification, 4),
+            (self.untraceable, 3),
+            (self.killable, 2),
+            (self.non_removable, 1),
+            (self.hazmat, 0),
+        ]
+        .into_iter()
+        .fold(0, |w1, (set, n)| w1 | (set as u16) << n)
+    }
+
+    /// XPC words as stored in tag memory. XPC_W2 is only included if
+    /// [`Self::xeb`] is set.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.w1().to_be_bytes().to_vec();
+
+        if let (true, Some(w2)) = (self.xeb, self.xpc_w2) {
+            bytes.extend_from_slice(&w2.to_be_bytes());
+        }
+
+        bytes
+    }
+}
+
+impl InventoryTag {
+    /// Decoded [`InventoryTag::pc`]
+    pub fn protocol_control(&self) -> ProtocolControl {
+        ProtocolControl::from(self.pc)
+    }
+
+    /// Decoded [`InventoryTag::xpc`], if the tag reported one
+    pub fn extended_pc(&self) -> Option<ExtendedPc> {
+        ExtendedPc::decode(self.xpc.get_id())
+    }
+}
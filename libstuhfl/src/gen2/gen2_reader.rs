@@ -15,6 +15,12 @@ pub struct Gen2Reader {
 }
 
 impl Gen2Reader {
+    /// Word address of the PC in the EPC memory bank
+    const PC_WORD: u32 = 0x01;
+
+    /// Word address of the EPC in the EPC memory bank
+    const EPC_WORD: u32 = 0x02;
+
     /// Creates an instance of self, must be private to
     /// ensure that this doesn't 'leak' out to the end
     /// user. Otherwise the state might not be valid.
@@ -25,6 +31,12 @@ impl Gen2Reader {
         }
     }
 
+    /// Connection to the reader
+    #[cfg(feature = "port-scanning")]
+    pub(crate) fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
     /// Workaround for firmware issues with read command.
     /// Uses custom command in background.
     pub fn read_alt(
@@ -222,6 +234,719 @@ impl Gen2Reader {
             &generic_cmd_struct.rcvData[..generic_cmd_struct.rcvDataLength as usize],
         ))
     }
+
+    /// # Reconfiguring reader
+    ///
+    /// Applies a new configuration, the same way as
+    /// [`BasicReader::configure_gen2()`] but keeping this reader. If a
+    /// setting is rejected, the connection stays open and the reader can
+    /// be configured again, e.g. with the last configuration that worked.
+    /// The reader must be tuned again in either case.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    ///
+    /// let good = Gen2Cfg::builder().build()?;
+    /// let mut reader = reader.configure_gen2(&good)?;
+    ///
+    /// let changed = Gen2Cfg::builder()
+    ///     .tx_rx_cfg(TxRxCfg::builder().tx_output_level(-6).build()?)
+    ///     .build()?;
+    ///
+    /// if let Err(e) = reader.reconfigure(&changed) {
+    ///     println!("Configuration rejected: {}", e);
+    ///     reader.reconfigure(&good)?;
+    /// }
+    ///
+    /// reader.tune(TuningAlgorithm::Exact)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reconfigure(&mut self, cfg: &Gen2Cfg) -> Result<()> {
+        self.is_tuned = false;
+
+        apply_gen2_cfg(cfg)
+    }
+
+    /// # Reading the live configuration
+    ///
+    /// Reads back every setting covered by [`Gen2Cfg`] from the firmware. The
+    /// filters are read for the BLF and coding currently in use. Compare with
+    /// the requested configuration using [`Gen2Cfg::diff()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Proto`] if the firmware reports a setting this
+    /// library doesn't know, e.g. an antenna, session or BLF added by a
+    /// newer firmware.
+    pub fn current_config(&self) -> Result<Gen2Cfg> {
+        let mut tx_rx_cfg = ffi::STUHFL_T_ST25RU3993_TxRxCfg::default();
+        unsafe { proc_err(ffi::Get_TxRxCfg(&mut tx_rx_cfg))? }
+
+        let mut inv_cfg = ffi::STUHFL_T_ST25RU3993_Gen2_InventoryCfg::default();
+        unsafe { proc_err(ffi::Get_Gen2_InventoryCfg(&mut inv_cfg))? }
+
+        let mut proto_cfg = ffi::STUHFL_T_ST25RU3993_Gen2_ProtocolCfg::default();
+        unsafe { proc_err(ffi::Get_Gen2_ProtocolCfg(&mut proto_cfg))? }
+
+        let mut lbt = ffi::STUHFL_T_ST25RU3993_FreqLBT::default();
+        unsafe { proc_err(ffi::Get_FreqLBT(&mut lbt))? }
+
+        let mut freq_hop = ffi::STUHFL_T_ST25RU3993_FreqHop::default();
+        unsafe { proc_err(ffi::Get_FreqHop(&mut freq_hop))? }
+
+        // Filters are stored per BLF and coding, read the ones in use
+        let mut proto_cfg = Gen2ProtocolCfg::try_from(proto_cfg)?;
+        let rx_filter = self.get_rx_filter(proto_cfg.blf, proto_cfg.coding)?;
+        let calibration = self.get_filter_calibration(proto_cfg.blf, proto_cfg.coding)?;
+        proto_cfg.filters = Some(Gen2FilterSet {
+            rx_filter: rx_filter.value,
+            high_pass: calibration.high_pass,
+            low_pass: calibration.low_pass,
+        });
+
+        Ok(Gen2Cfg {
+            tx_rx_cfg: tx_rx_cfg.try_into()?,
+            inv_cfg: inv_cfg.try_into()?,
+            proto_cfg,
+            lbt: lbt.into(),
+            channel_list: self.get_channel_list()?,
+            freq_hop: freq_hop.try_into()?,
+            timings: self.get_timings()?,
+        })
+    }
+
+    /// # Reading anticollision settings
+    ///
+    /// Reads the AdaptiveQ settings currently applied by the firmware.
+    pub fn get_adaptive_q(&self) -> Result<Gen2AdaptiveQ> {
+        let mut inv_cfg = ffi::STUHFL_T_ST25RU3993_Gen2_InventoryCfg::default();
+        unsafe { proc_err(ffi::Get_Gen2_InventoryCfg(&mut inv_cfg))? }
+
+        Ok(Gen2AdaptiveQ::from(inv_cfg.antiCollision))
+    }
+
+    /// # Reading the channel list
+    ///
+    /// Reads the channel list currently used by the firmware. After tuning,
+    /// this includes the tuning capacitor values found for every channel.
+    pub fn get_channel_list(&self) -> Result<ChannelListCfg> {
+        let mut channel_list = ffi::STUHFL_T_ST25RU3993_ChannelList::default();
+        unsafe { proc_err(ffi::Get_ChannelList(&mut channel_list))? }
+
+        Ok(channel_list.into())
+    }
+
+    /// Restores a channel list saved after tuning, so the reader is tuned
+    /// again without running the tuning algorithm.
+    #[cfg(feature = "port-scanning")]
+    pub(crate) fn restore_tuning(&mut self, channel_list: &ChannelListCfg) -> Result<()> {
+        let mut channel_list = channel_list.as_ffi();
+        unsafe { proc_err(ffi::Set_ChannelList(&mut channel_list))? }
+
+        self.is_tuned = true;
+
+        Ok(())
+    }
+
+    /// # Reading link timings
+    ///
+    /// Reads the Gen2 link timings currently used by the firmware.
+    pub fn get_timings(&self) -> Result<Gen2Timings> {
+        let mut timings = ffi::STUHFL_T_ST25RU3993_Gen2_Timings {
+            T4Min: ffi::STUHFL_D_GEN2_TIMING_USE_DEFAULT_T4MIN as u16,
+        };
+        unsafe { proc_err(ffi::Get_Gen2_Timings(&mut timings))? }
+
+        Ok(Gen2Timings::from(timings))
+    }
+
+    /// # Reading the RX filter
+    ///
+    /// Reads the RX filter value used for the given BLF and coding.
+    pub fn get_rx_filter(&self, blf: Gen2Blf, coding: Gen2Coding) -> Result<Gen2RxFilter> {
+        Ok(self.get_rx_filters(&[(blf, coding)])?[0])
+    }
+
+    /// # Reading several RX filters
+    ///
+    /// Reads the RX filter values for each BLF and coding combination,
+    /// in the same order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`] if more than
+    /// [`Gen2FilterCalibration::MAX_LIST_LEN`] entries are requested, and
+    /// [`Error::Proto`] if the firmware reports an unknown BLF or coding.
+    pub fn get_rx_filters(&self, keys: &[(Gen2Blf, Gen2Coding)]) -> Result<Vec<Gen2RxFilter>> {
+        if keys.len() > Gen2FilterCalibration::MAX_LIST_LEN {
+            return Err(Error::Param);
+        }
+
+        let mut filters: Vec<ffi::STUHFL_T_ST25RU3993_RxFilter> = keys
+            .iter()
+            .map(|&(blf, coding)| {
+                Gen2RxFilter {
+                    blf,
+                    coding,
+                    value: 0,
+                }
+                .as_ffi()
+            })
+            .collect();
+
+        unsafe {
+            proc_err(ffi::Get_Gen2_RxFilterMultiple(
+                filters.len() as u8,
+                &mut filters.as_mut_ptr(),
+            ))?
+        }
+
+        filters.into_iter().map(Gen2RxFilter::try_from).collect()
+    }
+
+    /// # Setting the RX filter
+    ///
+    /// Sets the RX filter value used for a BLF and coding combination.
+    /// The firmware picks the matching filter whenever the protocol
+    /// configuration changes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    /// let mut reader = reader.configure_gen2(&Gen2Cfg::builder().build()?)?;
+    ///
+    /// let mut filter = reader.get_rx_filter(Gen2Blf::ThreeHundredTwenty, Gen2Coding::Miller2)?;
+    /// filter.value = 0x24;
+    /// reader.set_rx_filter(&filter)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_rx_filter(&mut self, filter: &Gen2RxFilter) -> Result<()> {
+        self.set_rx_filters(&[*filter])
+    }
+
+    /// # Setting several RX filters
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`] if more than
+    /// [`Gen2FilterCalibration::MAX_LIST_LEN`] filters are given.
+    pub fn set_rx_filters(&mut self, filters: &[Gen2RxFilter]) -> Result<()> {
+        if filters.len() > Gen2FilterCalibration::MAX_LIST_LEN {
+            return Err(Error::Param);
+        }
+
+        let mut filters: Vec<ffi::STUHFL_T_ST25RU3993_RxFilter> =
+            filters.iter().map(|filter| filter.as_ffi()).collect();
+
+        unsafe {
+            proc_err(ffi::Set_Gen2_RxFilterMultiple(
+                filters.len() as u8,
+                &mut filters.as_mut_ptr(),
+            ))
+        }
+    }
+
+    /// # Reading the filter calibration
+    ///
+    /// Reads the high-pass and low-pass filter calibration used for
+    /// the given BLF and coding.
+    pub fn get_filter_calibration(
+        &self,
+        blf: Gen2Blf,
+        coding: Gen2Coding,
+    ) -> Result<Gen2FilterCalibration> {
+        Ok(self.get_filter_calibrations(&[(blf, coding)])?[0])
+    }
+
+    /// # Reading several filter calibrations
+    ///
+    /// Reads the filter calibration for each BLF and coding combination,
+    /// in the same order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`] if more than
+    /// [`Gen2FilterCalibration::MAX_LIST_LEN`] entries are requested, and
+    /// [`Error::Proto`] if the firmware reports an unknown BLF or coding.
+    pub fn get_filter_calibrations(
+        &self,
+        keys: &[(Gen2Blf, Gen2Coding)],
+    ) -> Result<Vec<Gen2FilterCalibration>> {
+        if keys.len() > Gen2FilterCalibration::MAX_LIST_LEN {
+            return Err(Error::Param);
+        }
+
+        let mut cals: Vec<ffi::STUHFL_T_ST25RU3993_FilterCalibration> = keys
+            .iter()
+            .map(|&(blf, coding)| {
+                Gen2FilterCalibration {
+                    blf,
+                    coding,
+                    high_pass: 0,
+                    low_pass: 0,
+                }
+                .as_ffi()
+            })
+            .collect();
+
+        unsafe {
+            proc_err(ffi::Get_Gen2_FilterCalibrationMultiple(
+                cals.len() as u8,
+                &mut cals.as_mut_ptr(),
+            ))?
+        }
+
+        cals.into_iter()
+            .map(Gen2FilterCalibration::try_from)
+            .collect()
+    }
+
+    /// # Setting the filter calibration
+    ///
+    /// Sets the high-pass and low-pass filter calibration used for
+    /// a BLF and coding combination.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`] if either value is above
+    /// [`Gen2FilterCalibration::MAX_VALUE`].
+    pub fn set_filter_calibration(&mut self, calibration: &Gen2FilterCalibration) -> Result<()> {
+        self.set_filter_calibrations(&[*calibration])
+    }
+
+    /// # Setting several filter calibrations
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`] if any value is above
+    /// [`Gen2FilterCalibration::MAX_VALUE`] or more than
+    /// [`Gen2FilterCalibration::MAX_LIST_LEN`] calibrations are given
+    /// (nothing is written in either case).
+    pub fn set_filter_calibrations(
+        &mut self,
+        calibrations: &[Gen2FilterCalibration],
+    ) -> Result<()> {
+        if calibrations.len() > Gen2FilterCalibration::MAX_LIST_LEN {
+            return Err(Error::Param);
+        }
+
+        if calibrations.iter().any(|cal| {
+            cal.high_pass > Gen2FilterCalibration::MAX_VALUE
+                || cal.low_pass > Gen2FilterCalibration::MAX_VALUE
+        }) {
+            return Err(Error::Param);
+        }
+
+        let mut cals: Vec<ffi::STUHFL_T_ST25RU3993_FilterCalibration> =
+            calibrations.iter().map(|cal| cal.as_ffi()).collect();
+
+        unsafe {
+            proc_err(ffi::Set_Gen2_FilterCalibrationMultiple(
+                cals.len() as u8,
+                &mut cals.as_mut_ptr(),
+            ))
+        }
+    }
+
+    /// # Changing a tag's EPC
+    ///
+    /// Replaces the EPC of the tag currently answering with `current` by
+    /// `new`. The tag's EPC is read first, then the EPC words are written
+    /// one by one (words already holding the new value are skipped), then
+    /// the length field of the PC is updated if the length changed.
+    /// Finally, the tag is selected by its new EPC and read back to verify
+    /// the change.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// use libstuhfl::tds::Gs1Epc;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    /// let mut reader = reader.configure_gen2(&Gen2Cfg::builder().build()?)?;
+    ///
+    /// reader.tune(TuningAlgorithm::Exact)?;
+    ///
+    /// let (_stats, tags) = reader.inventory_once()?;
+    ///
+    /// if tags.is_empty() { panic!("No tags found") }
+    ///
+    /// let new = Gs1Epc::from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.812345.6789")?.to_epc()?;
+    /// let result = reader.write_epc(&tags[0].epc, &new, None)?;
+    ///
+    /// if result.status != Gen2EpcWriteStatus::Complete {
+    ///     println!("EPC change failed: {:?}", result);
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`] if `new` isn't a whole number of words or
+    /// longer than the PC can describe, and [`Error::Generic`] if the reader
+    /// isn't tuned. Errors talking to the tag are reported in the returned
+    /// [`Gen2EpcWriteResult`] instead, together with how far the write got.
+    pub fn write_epc(
+        &mut self,
+        current: &Epc,
+        new: &Epc,
+        password: Option<Password>,
+    ) -> Result<Gen2EpcWriteResult> {
+        // Require tuning
+        if !self.is_tuned {
+            return Err(Error::Generic);
+        }
+
+        let new = new.get_id();
+
+        // Check the length before touching the tag
+        ProtocolControl::from(0).with_epc_len(new.len())?;
+
+        let mut result = Gen2EpcWriteResult {
+            status: Gen2EpcWriteStatus::Unchanged,
+            words_written: 0,
+            pc_updated: false,
+            error: None,
+        };
+
+        if let Err(e) = self.write_epc_words(current, new, password, &mut result) {
+            result.error = Some(e);
+        }
+
+        match self.verify_epc(new, password) {
+            Ok(true) => result.status = Gen2EpcWriteStatus::Complete,
+            Ok(false) | Err(_) if result.words_written == 0 && !result.pc_updated => (),
+            Ok(false) => result.status = Gen2EpcWriteStatus::Partial,
+            Err(e) => {
+                result.status = Gen2EpcWriteStatus::Partial;
+                result.error.get_or_insert(e);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Writes the EPC words and PC for [`Self::write_epc()`], keeping
+    /// track of the progress in `result`
+    fn write_epc_words(
+        &mut self,
+        current: &Epc,
+        new: &[u8],
+        password: Option<Password>,
+        result: &mut Gen2EpcWriteResult,
+    ) -> Result<()> {
+        self.select(current)?;
+
+        let pc = self.read(MemoryBank::Epc, Self::PC_WORD, 2, password)?;
+        let pc = ProtocolControl::from([pc[0], pc[1]]);
+
+        // EPC memory contents, used to skip words and as select mask
+        let mut on_tag = match pc.epc_len() {
+            0 => Vec::new(),
+            len => self.read(MemoryBank::Epc, Self::EPC_WORD, len as u8, password)?,
+        };
+
+        for (i, word) in new.chunks(2).enumerate() {
+            let offset = i * 2;
+
+            if on_tag.get(offset..offset + 2) == Some(word) {
+                continue;
+            }
+
+            self.write(
+                MemoryBank::Epc,
+                Self::EPC_WORD + i as u32,
+                [word[0], word[1]],
+                password,
+            )?;
+            result.words_written += 1;
+
+            // The tag answers to the partly written EPC from now on
+            if on_tag.len() < offset + 2 {
+                on_tag.resize(offset + 2, 0);
+            }
+            on_tag[offset..offset + 2].copy_from_slice(word);
+            self.select(&Epc::from_id(on_tag.clone()))?;
+        }
+
+        let new_pc = pc.with_epc_len(new.len())?;
+
+        if new_pc != pc {
+            self.write(MemoryBank::Epc, Self::PC_WORD, new_pc.to_bytes(), password)?;
+            result.pc_updated = true;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the tag with EPC `new` answers and has a matching PC
+    fn verify_epc(&mut self, new: &[u8], password: Option<Password>) -> Result<bool> {
+        self.select(&Epc::from_id(new.to_vec()))?;
+
+        let data = self.read(
+            MemoryBank::Epc,
+            Self::PC_WORD,
+            2 + new.len() as u8,
+            password,
+        )?;
+
+        Ok(data.len() == 2 + new.len()
+            && ProtocolControl::from([data[0], data[1]]).epc_len() == new.len()
+            && &data[2..] == new)
+    }
+
+    /// # Measuring RSSI
+    ///
+    /// Issues `count` Query commands on `frequency` (kHz) and measures the
+    /// RSSI of every reply (queries without a reply are left out). Use
+    /// [`ProtocolCommands::select()`] beforehand to profile a single tag.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    /// let mut reader = reader.configure_gen2(&Gen2Cfg::builder().build()?)?;
+    ///
+    /// reader.tune(TuningAlgorithm::Exact)?;
+    ///
+    /// let measurement = reader.measure_rssi(866_900, 32)?;
+    ///
+    /// if let Some(log_i) = measurement.log_i() {
+    ///     println!("log I: {:.1} +/- {:.1}", log_i.mean, log_i.std_dev);
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`] if `count` is zero and [`Error::Generic`] if
+    /// the reader isn't tuned.
+    pub fn measure_rssi(&mut self, frequency: u32, count: u8) -> Result<Gen2RssiMeasurement> {
+        // Require tuning
+        if !self.is_tuned {
+            return Err(Error::Generic);
+        }
+
+        if count == 0 {
+            return Err(Error::Param);
+        }
+
+        let mut tx_rx_cfg = ffi::STUHFL_T_ST25RU3993_TxRxCfg::default();
+        unsafe { proc_err(ffi::Get_TxRxCfg(&mut tx_rx_cfg))? }
+
+        let mut measure = ffi::STUHFL_T_Gen2_QueryMeasureRssi {
+            frequency,
+            measureCnt: count,
+            agc: [0; 256],
+            rssiLogI: [0; 256],
+            rssiLogQ: [0; 256],
+            rssiLinI: [0; 256],
+            rssiLinQ: [0; 256],
+        };
+
+        unsafe { proc_err(ffi::Gen2_QueryMeasureRssi(&mut measure))? }
+
+        Ok(Gen2RssiMeasurement::from_ffi(
+            &measure,
+            tx_rx_cfg.rxSensitivity,
+        ))
+    }
+
+    /// Runs [`Self::measure_rssi()`] on every channel of the active
+    /// [`ChannelListCfg`], giving the frequency response of the selected
+    /// tag(s).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// use libstuhfl::rssi::RssiModel;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    /// let mut reader = reader.configure_gen2(&Gen2Cfg::builder().build()?)?;
+    ///
+    /// reader.tune(TuningAlgorithm::Exact)?;
+    ///
+    /// let (_stats, tags) = reader.inventory_once()?;
+    ///
+    /// if tags.is_empty() { panic!("No tags found") }
+    ///
+    /// reader.select(&tags[0].epc)?;
+    ///
+    /// let model = RssiModel::default();
+    ///
+    /// for measurement in reader.measure_rssi_channels(16)? {
+    ///     if let Some(dbm) = measurement.to_dbm(&model) {
+    ///         println!("{} kHz: {:.1} dBm", measurement.frequency, dbm.mean);
+    ///     }
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn measure_rssi_channels(&mut self, count: u8) -> Result<Vec<Gen2RssiMeasurement>> {
+        let channels: Vec<ChannelItem> = self.get_channel_list()?.into();
+
+        channels
+            .iter()
+            .map(|channel| self.measure_rssi(channel.frequency(), count))
+            .collect()
+    }
+
+    /// # Locking a tag
+    ///
+    /// Changes the lock state of the memory banks and passwords selected in
+    /// `lock`. Locking requires the tag's access password to be set, and
+    /// permanent changes can't be undone. Select the tag first, or a random
+    /// tag in the field will be locked.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    /// let mut reader = reader.configure_gen2(&Gen2Cfg::builder().build()?)?;
+    ///
+    /// reader.tune(TuningAlgorithm::Exact)?;
+    ///
+    /// let (_stats, tags) = reader.inventory_once()?;
+    ///
+    /// if tags.is_empty() { panic!("No tags found") }
+    /// reader.select(&tags[0].epc)?;
+    ///
+    /// let lock = Gen2LockCfg::builder()
+    ///     .access_password(Gen2LockAction::Lock)
+    ///     .user(Gen2LockAction::Lock)
+    ///     .build()?;
+    ///
+    /// let password = Password::from([0x55, 0x55, 0x55, 0x55]);
+    /// reader.lock(&lock, Some(password))?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Generic`] if the reader isn't tuned.
+    pub fn lock(&mut self, lock: &Gen2LockCfg, password: Option<Password>) -> Result<()> {
+        // Require tuning
+        if !self.is_tuned {
+            return Err(Error::Generic);
+        }
+
+        let mut lock_struct = ffi::STUHFL_T_Gen2_Lock {
+            mask: lock.payload(),
+            pwd: if let Some(pwd) = password {
+                pwd.into_inner()
+            } else {
+                [0; 4]
+            },
+            tagReply: 0,
+        };
+
+        unsafe { proc_err(ffi::Gen2_Lock(&mut lock_struct))? }
+
+        Ok(())
+    }
+
+    /// # Killing a tag
+    ///
+    /// Permanently disables the tag, which can't be read or inventoried
+    /// afterwards. Tags with a kill password of zero can't be killed.
+    /// Select the tag first, or a random tag in the field will be killed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    /// let mut reader = reader.configure_gen2(&Gen2Cfg::builder().build()?)?;
+    ///
+    /// reader.tune(TuningAlgorithm::Exact)?;
+    ///
+    /// let (_stats, tags) = reader.inventory_once()?;
+    ///
+    /// if tags.is_empty() { panic!("No tags found") }
+    /// reader.select(&tags[0].epc)?;
+    ///
+    /// reader.kill(Password::from([0x12, 0x34, 0x56, 0x78]), None)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Generic`] if the reader isn't tuned.
+    pub fn kill(&mut self, kill_password: Password, password: Option<Password>) -> Result<()> {
+        // Require tuning
+        if !self.is_tuned {
+            return Err(Error::Generic);
+        }
+
+        let mut kill_struct = ffi::STUHFL_T_Gen2_Kill {
+            pwd: if let Some(pwd) = password {
+                pwd.into_inner()
+            } else {
+                [0; 4]
+            },
+            killPwd: kill_password.into_inner(),
+            recommission: 0,
+            tagReply: 0,
+        };
+
+        unsafe { proc_err(ffi::Gen2_Kill(&mut kill_struct))? }
+
+        Ok(())
+    }
+
+    /// Clears the select list set by [`ProtocolCommands::select()`], so the
+    /// following commands address any tag in the field again
+    pub fn clear_select(&mut self) -> Result<()> {
+        let mut sel = ffi::STUHFL_T_Gen2_Select {
+            mode: ffi::STUHFL_D_GEN2_SELECT_MODE_CLEAR_LIST as u8,
+            ..Default::default()
+        };
+
+        unsafe { proc_err(ffi::Gen2_Select(&mut sel))? }
+
+        Ok(())
+    }
 }
 
 lazy_static! {
@@ -240,8 +965,9 @@ impl ConnectionHolder for Gen2Reader {
 
 unsafe impl BasicReader for Gen2Reader {}
 
-unsafe impl ProtocolReader for Gen2Reader {
+unsafe impl ProtocolReader for Gen2Reader {}
 
+impl ProtocolCommands for Gen2Reader {
     fn tune(&mut self, algo: TuningAlgorithm) -> Result<()> {
         // None does nothing
         if algo == TuningAlgorithm::None {
@@ -313,7 +1039,7 @@ unsafe impl ProtocolReader for Gen2Reader {
         }
 
         if num_rounds == 0 {
-            eprintln!("Error: num_rounds = 0 not yet implemented!");
+            log::error!(target: "stuhfl", "num_rounds = 0 not yet implemented!");
             return Err(Error::None);
         }
 
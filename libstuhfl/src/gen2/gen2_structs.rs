@@ -1,16 +1,24 @@
 use super::gen2_enums::*;
 use crate::data_types::*;
 use crate::ffi;
+#[cfg(feature = "use-serde")]
+use crate::error::ConfigFileError;
+use crate::error::Error;
+use crate::rssi::RssiModel;
+use enum_primitive::FromPrimitive;
+use std::fmt;
 
-// CB 7/14/25: Similar to 'structs.rs', this file holds default settings that can be
-//             altered to change the Gen2Cfg configuration. For example, on line 290:
-//
-//             #[builder(default = "ChannelListCfg::from_profile(Profile::Custom)")]
-//
-//             'Custom' can be changed to the desired Cfg profile, which can be found in 'enums.rs'
-//             from lines 55 to 72
+// As in 'structs.rs', builder defaults only apply to settings missing from
+// a configuration file. The channel list, for example, is given there as
+// '[[channel_list]]' entries, see 'Gen2Cfg::from_toml_file()'.
 
-#[derive(Builder, Copy, Clone)]
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Gen2AdaptiveQCfgBuilder"),
+    builder(derive(serde::Deserialize))
+)]
 /// AdaptiveQ Algorithm configuration. This contains parameters for determining
 /// the Q value automatically. See [`Gen2AdaptiveQ`] for details.
 #[builder(build_fn(validate = "Self::validate"))]
@@ -42,11 +50,57 @@ pub struct Gen2AdaptiveQCfg {
     /// Q Algorithm option
     #[builder(default = "false")]
     reset_after_round: bool,
+
+    /// Weight subtracted from Q on an empty slot, one value per Q (0..=15)
+    #[builder(default = "Gen2AdaptiveQCfg::DEFAULT_C1")]
+    c1: [u8; ffi::STUHFL_D_NB_C_VALUES as usize],
+
+    /// Weight added to Q on a collision, one value per Q (0..=15)
+    #[builder(default = "Gen2AdaptiveQCfg::DEFAULT_C2")]
+    c2: [u8; ffi::STUHFL_D_NB_C_VALUES as usize],
 }
 
 //Is this anything?
 impl Builder<Gen2AdaptiveQCfgBuilder> for Gen2AdaptiveQCfg {}
 
+deserialize_via_builder!(
+    Gen2AdaptiveQCfg,
+    Gen2AdaptiveQCfgBuilder,
+    Gen2AdaptiveQCfgBuilderError
+);
+
+impl Gen2AdaptiveQCfg {
+    /// Firmware default C1 values
+    pub const DEFAULT_C1: [u8; ffi::STUHFL_D_NB_C_VALUES as usize] =
+        [5; ffi::STUHFL_D_NB_C_VALUES as usize];
+
+    /// Firmware default C2 values
+    pub const DEFAULT_C2: [u8; ffi::STUHFL_D_NB_C_VALUES as usize] =
+        [35; ffi::STUHFL_D_NB_C_VALUES as usize];
+}
+
+impl From<ffi::STUHFL_T_ST25RU3993_Gen2_Anticollision> for Gen2AdaptiveQ {
+    fn from(anticollision: ffi::STUHFL_T_ST25RU3993_Gen2_Anticollision) -> Self {
+        if !anticollision.adaptiveQ {
+            return Gen2AdaptiveQ::Disable(anticollision.startQ);
+        }
+
+        let options = anticollision.options as u32;
+
+        Gen2AdaptiveQ::Enable(Gen2AdaptiveQCfg {
+            start_q: anticollision.startQ,
+            min_q: anticollision.minQ,
+            max_q: anticollision.maxQ,
+            adjust_nic: options & ffi::STUHFL_D_USE_QUERY_ADJUST_NIC != 0,
+            single_adjust: options & ffi::STUHFL_D_SINGLE_ADJUST != 0,
+            use_ceil_floor: options & ffi::STUHFL_D_USE_CEIL_FLOOR != 0,
+            reset_after_round: options & ffi::STUHFL_D_RESET_Q_AFTER_ROUND != 0,
+            c1: anticollision.C1,
+            c2: anticollision.C2,
+        })
+    }
+}
+
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_Anticollision> for Gen2AdaptiveQ {
     // INPUTS: Self(Gen2AdaptiveQ)
     // OUTPUTS: ffi::STUHFL_T_ST25RU3993_Gen2_Anticollision
@@ -81,7 +135,8 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_Anticollision> for Gen2AdaptiveQ {
                     minQ: conf.min_q,
                     maxQ: conf.max_q,
                     options,
-                    ..Default::default()
+                    C1: conf.c1,
+                    C2: conf.c2,
                 }
             }
             //If in Disable Configuration
@@ -111,11 +166,72 @@ impl Gen2AdaptiveQCfgBuilder {
             }
         }
 
+        // A zero weight would stop Q from ever moving in that direction
+        if let Some(c1) = self.c1 {
+            if c1.contains(&0) {
+                return Err("c1 values must be greater than 0".to_owned());
+            }
+        }
+        if let Some(c2) = self.c2 {
+            if c2.contains(&0) {
+                return Err("c2 values must be greater than 0".to_owned());
+            }
+        }
+
         Ok(())
     }
+
+    /// Applies a preset for Q algorithm parameters. Individual values
+    /// can still be overridden afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let adaptive_q = Gen2AdaptiveQCfg::builder()
+    ///     .preset(Gen2AdaptiveQPreset::DensePopulation)
+    ///     .max_q(12)
+    ///     .build()?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn preset(&mut self, preset: Gen2AdaptiveQPreset) -> &mut Self {
+        /// Number of C1/C2 values
+        const N: usize = ffi::STUHFL_D_NB_C_VALUES as usize;
+
+        match preset {
+            Gen2AdaptiveQPreset::Default => self
+                .start_q(6)
+                .min_q(2)
+                .c1(Gen2AdaptiveQCfg::DEFAULT_C1)
+                .c2(Gen2AdaptiveQCfg::DEFAULT_C2),
+            // Start high and grow Q quickly on collisions
+            Gen2AdaptiveQPreset::DensePopulation => self
+                .start_q(8)
+                .min_q(4)
+                .c1(Gen2AdaptiveQCfg::DEFAULT_C1)
+                .c2([50; N]),
+            // Start low and shrink Q quickly on empty slots
+            Gen2AdaptiveQPreset::SparsePopulation => self
+                .start_q(2)
+                .min_q(0)
+                .c1([15; N])
+                .c2(Gen2AdaptiveQCfg::DEFAULT_C2),
+        }
+    }
 }
 
-#[derive(Builder, Copy, Clone)]
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Gen2InventoryOptionsBuilder"),
+    builder(derive(serde::Deserialize))
+)]
 /// Options regarding Gen2 inventory rounds. This is part of [`Gen2InventoryCfg`].
 pub struct Gen2InventoryOptions {
     /// Fast Inventory enabling. If set to false, normal inventory round will be performed.
@@ -133,6 +249,22 @@ pub struct Gen2InventoryOptions {
 
 impl Builder<Gen2InventoryOptionsBuilder> for Gen2InventoryOptions {}
 
+deserialize_via_builder!(
+    Gen2InventoryOptions,
+    Gen2InventoryOptionsBuilder,
+    Gen2InventoryOptionsBuilderError
+);
+
+impl From<ffi::STUHFL_T_ST25RU3993_Gen2_InventoryOption> for Gen2InventoryOptions {
+    fn from(options: ffi::STUHFL_T_ST25RU3993_Gen2_InventoryOption) -> Self {
+        Self {
+            fast: options.fast,
+            auto_ack: options.autoAck,
+            read_tid: options.readTID,
+        }
+    }
+}
+
 // Converts 'Gen2InventoryOptions' to 'ffi::STUHFL_T_ST25RU3993_Gen2_InventoryOption'
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_InventoryOption> for Gen2InventoryOptions {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_Gen2_InventoryOption {
@@ -144,7 +276,13 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_InventoryOption> for Gen2InventoryOptio
     }
 }
 
-#[derive(Builder, Copy, Clone)]
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Gen2QueryParamsBuilder"),
+    builder(derive(serde::Deserialize))
+)]
 /// Gen2 Query paremeter parameters. These are used by the firmware
 /// during Query events (all data exchanges between reader and tag
 /// require Query events).
@@ -175,6 +313,28 @@ pub struct Gen2QueryParams {
 
 impl Builder<Gen2QueryParamsBuilder> for Gen2QueryParams {}
 
+deserialize_via_builder!(
+    Gen2QueryParams,
+    Gen2QueryParamsBuilder,
+    Gen2QueryParamsBuilderError
+);
+
+impl TryFrom<ffi::STUHFL_T_ST25RU3993_Gen2_QueryParams> for Gen2QueryParams {
+    type Error = Error;
+
+    fn try_from(
+        params: ffi::STUHFL_T_ST25RU3993_Gen2_QueryParams,
+    ) -> core::result::Result<Self, Self::Error> {
+        Ok(Self {
+            sel: QuerySel::from_u8(params.sel).ok_or(Error::Proto)?,
+            session: Gen2Session::from_u8(params.session).ok_or(Error::Proto)?,
+            target: Gen2QueryTarget::from_u8(params.target).ok_or(Error::Proto)?,
+            toggle_target: params.toggleTarget,
+            target_depletion_mode: params.targetDepletionMode,
+        })
+    }
+}
+
 // Converts 'Gen2QueryParams' to 'ffi::STUHFL_T_ST25RU3993_Gen2_QueryParams'
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_QueryParams> for Gen2QueryParams {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_Gen2_QueryParams {
@@ -188,7 +348,13 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_QueryParams> for Gen2QueryParams {
     }
 }
 
-#[derive(Builder, Copy, Clone)]
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Gen2InventoryCfgBuilder"),
+    builder(derive(serde::Deserialize))
+)]
 /// Settings for Gen2 Inventorying. Note that [`Gen2InventoryOptions`] is a only subset of
 /// these settings. See [`Self::builder()`] for details.
 /// See also: [`Gen2Cfg`]
@@ -222,6 +388,29 @@ pub struct Gen2InventoryCfg {
 
 impl Builder<Gen2InventoryCfgBuilder> for Gen2InventoryCfg {}
 
+deserialize_via_builder!(
+    Gen2InventoryCfg,
+    Gen2InventoryCfgBuilder,
+    Gen2InventoryCfgBuilderError
+);
+
+impl TryFrom<ffi::STUHFL_T_ST25RU3993_Gen2_InventoryCfg> for Gen2InventoryCfg {
+    type Error = Error;
+
+    fn try_from(
+        cfg: ffi::STUHFL_T_ST25RU3993_Gen2_InventoryCfg,
+    ) -> core::result::Result<Self, Self::Error> {
+        Ok(Self {
+            options: Gen2InventoryOptions::from(cfg.inventoryOption),
+            adaptive_q: Gen2AdaptiveQ::from(cfg.antiCollision),
+            auto_tuning: AutoTuning::try_from(cfg.autoTuning)?,
+            query_params: Gen2QueryParams::try_from(cfg.queryParams)?,
+            auto_rx_sensitivity: AutoRxSensitivity::from(cfg.adaptiveSensitivity),
+            auto_tx_strength: AutoTxStrength::from(cfg.adaptiveOutputPower),
+        })
+    }
+}
+
 // Converts 'Gen2InventoryCfg' to 'ffi::STUHFL_T_ST25RU3993_Gen2_InventoryCfg'
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_InventoryCfg> for Gen2InventoryCfg {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_Gen2_InventoryCfg {
@@ -236,29 +425,102 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_InventoryCfg> for Gen2InventoryCfg {
     }
 }
 
-#[derive(Builder, Copy, Clone)]
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Gen2ProtocolCfgBuilder"),
+    builder(derive(serde::Deserialize))
+)]
 /// Gen2 protocol settings. These factors affect the transmission
 /// speed and reliability of the air protocol.
+#[builder(build_fn(validate = "Self::validate"))]
 pub struct Gen2ProtocolCfg {
     /// Tari setting
     #[builder(default = "Gen2Tari::Six")]
-    tari: Gen2Tari,
+    pub(crate) tari: Gen2Tari,
 
     /// Backscatter link frequency factor
     #[builder(default = "Gen2Blf::ThreeHundredTwenty")]
-    blf: Gen2Blf,
+    pub(crate) blf: Gen2Blf,
 
     /// Coding
     #[builder(default = "Gen2Coding::Miller2")]
-    coding: Gen2Coding,
+    pub(crate) coding: Gen2Coding,
 
     /// Short or long preamble (true means long)
     #[builder(default = "ffi::STUHFL_D_TREXT_ON != 0")]
-    trext: bool,
+    pub(crate) trext: bool,
+
+    /// Calibrated filters to apply for the chosen BLF and coding.
+    /// If not set, the firmware's current filters are kept.
+    #[builder(default = "None", setter(strip_option))]
+    pub(crate) filters: Option<Gen2FilterSet>,
 }
 
 impl Builder<Gen2ProtocolCfgBuilder> for Gen2ProtocolCfg {}
 
+deserialize_via_builder!(
+    Gen2ProtocolCfg,
+    Gen2ProtocolCfgBuilder,
+    Gen2ProtocolCfgBuilderError
+);
+
+impl TryFrom<ffi::STUHFL_T_ST25RU3993_Gen2_ProtocolCfg> for Gen2ProtocolCfg {
+    type Error = Error;
+
+    fn try_from(
+        cfg: ffi::STUHFL_T_ST25RU3993_Gen2_ProtocolCfg,
+    ) -> core::result::Result<Self, Self::Error> {
+        Ok(Self {
+            tari: Gen2Tari::from_u8(cfg.tari).ok_or(Error::Proto)?,
+            blf: Gen2Blf::from_u8(cfg.blf).ok_or(Error::Proto)?,
+            coding: Gen2Coding::from_u8(cfg.coding).ok_or(Error::Proto)?,
+            trext: cfg.trext,
+            filters: None,
+        })
+    }
+}
+
+impl Gen2ProtocolCfg {
+    /// RX filter to apply along with the protocol settings, if any
+    pub(crate) fn rx_filter(&self) -> Option<Gen2RxFilter> {
+        self.filters.map(|filters| Gen2RxFilter {
+            blf: self.blf,
+            coding: self.coding,
+            value: filters.rx_filter,
+        })
+    }
+
+    /// Filter calibration to apply along with the protocol settings, if any
+    pub(crate) fn filter_calibration(&self) -> Option<Gen2FilterCalibration> {
+        self.filters.map(|filters| Gen2FilterCalibration {
+            blf: self.blf,
+            coding: self.coding,
+            high_pass: filters.high_pass,
+            low_pass: filters.low_pass,
+        })
+    }
+}
+
+impl Gen2ProtocolCfgBuilder {
+    /// Ensures filter calibration values are within range
+    fn validate(&self) -> core::result::Result<(), String> {
+        if let Some(Some(filters)) = self.filters {
+            if filters.high_pass > Gen2FilterCalibration::MAX_VALUE
+                || filters.low_pass > Gen2FilterCalibration::MAX_VALUE
+            {
+                return Err(format!(
+                    "Filter calibration values must be at most {}",
+                    Gen2FilterCalibration::MAX_VALUE
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 // Converts 'Gen2ProtocolCfg' to 'ffi::STUHFL_T_ST25RU3993_Gen2_ProtocolCfg'
 impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_ProtocolCfg> for Gen2ProtocolCfg {
     fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_Gen2_ProtocolCfg {
@@ -271,8 +533,185 @@ impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_ProtocolCfg> for Gen2ProtocolCfg {
     }
 }
 
-#[derive(Builder, Clone)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// RX filter setting for one BLF and coding combination.
+/// See [`Gen2Reader::set_rx_filter()`](crate::gen2::Gen2Reader::set_rx_filter).
+pub struct Gen2RxFilter {
+    /// Backscatter link frequency the filter applies to
+    pub blf: Gen2Blf,
+    /// Coding the filter applies to
+    pub coding: Gen2Coding,
+    /// RX filter value
+    pub value: u8,
+}
+
+// Fails with 'Error::Proto' if the firmware reports an unknown BLF or coding
+impl TryFrom<ffi::STUHFL_T_ST25RU3993_RxFilter> for Gen2RxFilter {
+    type Error = Error;
+
+    fn try_from(
+        filter: ffi::STUHFL_T_ST25RU3993_RxFilter,
+    ) -> core::result::Result<Self, Self::Error> {
+        Ok(Self {
+            blf: Gen2Blf::from_u8(filter.blf).ok_or(Error::Proto)?,
+            coding: Gen2Coding::from_u8(filter.coding).ok_or(Error::Proto)?,
+            value: filter.value,
+        })
+    }
+}
+
+impl AsFFI<ffi::STUHFL_T_ST25RU3993_RxFilter> for Gen2RxFilter {
+    fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_RxFilter {
+        ffi::STUHFL_T_ST25RU3993_RxFilter {
+            blf: self.blf as u8,
+            coding: self.coding as u8,
+            value: self.value,
+        }
+    }
+}
+
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// High-pass and low-pass filter calibration for one BLF and coding
+/// combination. See [`Gen2Reader::set_filter_calibration()`](crate::gen2::Gen2Reader::set_filter_calibration).
+pub struct Gen2FilterCalibration {
+    /// Backscatter link frequency the calibration applies to
+    pub blf: Gen2Blf,
+    /// Coding the calibration applies to
+    pub coding: Gen2Coding,
+    /// High pass filter value (0..=15, 4% steps)
+    pub high_pass: u8,
+    /// Low pass filter value (0..=15, 4% steps)
+    pub low_pass: u8,
+}
+
+impl Gen2FilterCalibration {
+    /// Largest valid high/low pass value
+    pub const MAX_VALUE: u8 = 15;
+
+    /// Most filters or calibrations read or written at once. The C library
+    /// traces every entry of a list into a 1 KiB stack buffer without
+    /// checking its size. A calibration takes 17 characters
+    /// (`"[XX, XX, XX, XX] "`), so 60 of them fit (1020 bytes plus the
+    /// terminating NUL). Filters take 13 characters and fit as well.
+    pub const MAX_LIST_LEN: usize = 60;
+}
+
+// Fails with 'Error::Proto' if the firmware reports an unknown BLF or coding
+impl TryFrom<ffi::STUHFL_T_ST25RU3993_FilterCalibration> for Gen2FilterCalibration {
+    type Error = Error;
+
+    fn try_from(
+        cal: ffi::STUHFL_T_ST25RU3993_FilterCalibration,
+    ) -> core::result::Result<Self, Self::Error> {
+        Ok(Self {
+            blf: Gen2Blf::from_u8(cal.blf).ok_or(Error::Proto)?,
+            coding: Gen2Coding::from_u8(cal.coding).ok_or(Error::Proto)?,
+            high_pass: cal.highPass,
+            low_pass: cal.lowPass,
+        })
+    }
+}
+
+impl AsFFI<ffi::STUHFL_T_ST25RU3993_FilterCalibration> for Gen2FilterCalibration {
+    fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_FilterCalibration {
+        ffi::STUHFL_T_ST25RU3993_FilterCalibration {
+            blf: self.blf as u8,
+            coding: self.coding as u8,
+            highPass: self.high_pass,
+            lowPass: self.low_pass,
+        }
+    }
+}
+
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Calibrated filter values for the BLF and coding of a [`Gen2ProtocolCfg`].
+/// These are applied together with the protocol settings by
+/// [`BasicReader::configure_gen2()`].
+pub struct Gen2FilterSet {
+    /// RX filter value
+    pub rx_filter: u8,
+    /// High pass filter value (0..=15, 4% steps)
+    pub high_pass: u8,
+    /// Low pass filter value (0..=15, 4% steps)
+    pub low_pass: u8,
+}
+
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Gen2TimingsBuilder"),
+    builder(derive(serde::Deserialize))
+)]
+/// Gen2 link timings. See [`Self::builder()`] for details.
+pub struct Gen2Timings {
+    /// Minimum time between two reader commands (T4) in μs. When unset,
+    /// the firmware uses 2 * RTcal (75 μs at 12.5 μs Tari). Tags that
+    /// need more processing time between commands (e.g. sensor tags)
+    /// may require a longer T4. See [`Gen2Timings::min_t4()`] for the
+    /// lowest allowed value.
+    #[builder(default = "None", setter(strip_option))]
+    t4_min: Option<u16>,
+}
+
+impl Builder<Gen2TimingsBuilder> for Gen2Timings {}
+
+deserialize_via_builder!(Gen2Timings, Gen2TimingsBuilder, Gen2TimingsBuilderError);
+
+impl Gen2Timings {
+    /// Lowest T4 (in μs) allowed for the given Tari and BLF. This is
+    /// 2 * RTcal, as required by the Gen2 standard (the firmware uses
+    /// RTcal = 3 * Tari), but at least the nominal tag reply time T1
+    /// (max(RTcal, 10 * Tpri)) so a reply is never cut off by the next command.
+    pub fn min_t4(tari: Gen2Tari, blf: Gen2Blf) -> u16 {
+        let rtcal = 3.0 * tari.micros();
+        let tpri = 1000.0 / blf.khz();
+
+        // 2 * RTcal already covers the RTcal term of T1
+        let t4 = (2.0 * rtcal).max(10.0 * tpri);
+
+        t4.ceil() as u16
+    }
+
+    /// Minimum T4 in μs, `None` if the firmware default is used
+    pub fn t4_min(&self) -> Option<u16> {
+        self.t4_min
+    }
+}
+
+impl From<ffi::STUHFL_T_ST25RU3993_Gen2_Timings> for Gen2Timings {
+    fn from(timings: ffi::STUHFL_T_ST25RU3993_Gen2_Timings) -> Self {
+        Self {
+            t4_min: match timings.T4Min as u32 {
+                ffi::STUHFL_D_GEN2_TIMING_USE_DEFAULT_T4MIN => None,
+                t4_min => Some(t4_min as u16),
+            },
+        }
+    }
+}
+
+impl AsFFI<ffi::STUHFL_T_ST25RU3993_Gen2_Timings> for Gen2Timings {
+    fn as_ffi(&self) -> ffi::STUHFL_T_ST25RU3993_Gen2_Timings {
+        ffi::STUHFL_T_ST25RU3993_Gen2_Timings {
+            T4Min: self
+                .t4_min
+                .unwrap_or(ffi::STUHFL_D_GEN2_TIMING_USE_DEFAULT_T4MIN as u16),
+        }
+    }
+}
+
+#[derive(Builder, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Gen2CfgBuilder"),
+    builder(derive(serde::Deserialize))
+)]
 /// Gen2 Master configuration
+#[builder(build_fn(validate = "Self::validate"))]
 pub struct Gen2Cfg {
     /// Antenna configuration
     #[builder(default = "TxRxCfg::builder().build().unwrap()")]
@@ -298,10 +737,316 @@ pub struct Gen2Cfg {
     /// Frequency hopping configuration
     #[builder(default = "FreqHopCfg::builder().build().unwrap()")]
     pub(crate) freq_hop: FreqHopCfg,
+
+    /// Link timing configuration
+    #[builder(default = "Gen2Timings::builder().build().unwrap()")]
+    pub(crate) timings: Gen2Timings,
 }
 
 impl Builder<Gen2CfgBuilder> for Gen2Cfg {}
 
+deserialize_via_builder!(Gen2Cfg, Gen2CfgBuilder, Gen2CfgBuilderError);
+
+impl Gen2Cfg {
+    /// # Loading configuration files
+    ///
+    /// Loads a configuration from a TOML file. Settings missing from the
+    /// file keep their builder defaults, and every section is checked the
+    /// same way as when using the builders.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// // station.toml:
+    /// //
+    /// // [tx_rx_cfg]
+    /// // tx_output_level = -6
+    /// //
+    /// // [proto_cfg]
+    /// // tari = "25us"
+    /// // blf = "160kHz"
+    /// // coding = "Miller4"
+    /// let gen2_cfg = Gen2Cfg::from_toml_file("station.toml")?;
+    ///
+    /// let reader = Reader::autoconnect()?;
+    /// let reader = reader.configure_gen2(&gen2_cfg)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "use-serde")]
+    pub fn from_toml_file<P: AsRef<std::path::Path>>(
+        path: P,
+    ) -> core::result::Result<Self, ConfigFileError> {
+        let contents = std::fs::read_to_string(path)?;
+
+        Ok(toml::from_str(&contents)?)
+    }
+
+    /// # Saving configuration files
+    ///
+    /// Writes the full configuration as TOML, e.g. to store a known
+    /// good setup that can be loaded with [`Self::from_toml_file()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let gen2_cfg = Gen2Cfg::builder().build()?;
+    ///
+    /// let path = std::env::temp_dir().join("station.toml");
+    /// std::fs::write(&path, gen2_cfg.to_toml_string()?)?;
+    /// # assert_eq!(Gen2Cfg::from_toml_file(&path)?, gen2_cfg);
+    /// # std::fs::remove_file(&path)?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "use-serde")]
+    pub fn to_toml_string(&self) -> core::result::Result<String, ConfigFileError> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// # Comparing configurations
+    ///
+    /// Lists every setting that differs between two configurations, e.g.
+    /// to verify that the firmware accepted the requested configuration
+    /// (see [`Gen2Reader::current_config()`](crate::gen2::Gen2Reader::current_config)).
+    /// Optional settings (filters, power amplifier) are only compared if
+    /// both configurations specify them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    ///
+    /// let requested = Gen2Cfg::builder().build()?;
+    /// let reader = reader.configure_gen2(&requested)?;
+    ///
+    /// for diff in requested.diff(&reader.current_config()?) {
+    ///     println!("Not applied: {}", diff);
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff(&self, other: &Gen2Cfg) -> Vec<Gen2CfgDiff> {
+        let mut diffs = Vec::new();
+
+        push_diff(&mut diffs, "tx_rx_cfg", &self.tx_rx_cfg, &other.tx_rx_cfg);
+        push_diff(
+            &mut diffs,
+            "inv_cfg.options",
+            &self.inv_cfg.options,
+            &other.inv_cfg.options,
+        );
+        push_diff(
+            &mut diffs,
+            "inv_cfg.adaptive_q",
+            &self.inv_cfg.adaptive_q,
+            &other.inv_cfg.adaptive_q,
+        );
+        push_diff(
+            &mut diffs,
+            "inv_cfg.auto_tuning",
+            &self.inv_cfg.auto_tuning,
+            &other.inv_cfg.auto_tuning,
+        );
+        push_diff(
+            &mut diffs,
+            "inv_cfg.query_params",
+            &self.inv_cfg.query_params,
+            &other.inv_cfg.query_params,
+        );
+        push_diff(
+            &mut diffs,
+            "inv_cfg.auto_rx_sensitivity",
+            &self.inv_cfg.auto_rx_sensitivity,
+            &other.inv_cfg.auto_rx_sensitivity,
+        );
+        push_diff(
+            &mut diffs,
+            "inv_cfg.auto_tx_strength",
+            &self.inv_cfg.auto_tx_strength,
+            &other.inv_cfg.auto_tx_strength,
+        );
+
+        let mut this_proto_cfg = self.proto_cfg;
+        let mut other_proto_cfg = other.proto_cfg;
+        if this_proto_cfg.filters.is_none() || other_proto_cfg.filters.is_none() {
+            this_proto_cfg.filters = None;
+            other_proto_cfg.filters = None;
+        }
+        push_diff(&mut diffs, "proto_cfg", &this_proto_cfg, &other_proto_cfg);
+
+        push_diff(&mut diffs, "lbt", &self.lbt, &other.lbt);
+        push_diff(
+            &mut diffs,
+            "channel_list",
+            &self.channel_list,
+            &other.channel_list,
+        );
+        push_diff(&mut diffs, "freq_hop", &self.freq_hop, &other.freq_hop);
+        push_diff(&mut diffs, "timings", &self.timings, &other.timings);
+
+        diffs
+    }
+
+    /// Transmission output level (dB)
+    pub fn tx_output_level(&self) -> i8 {
+        self.tx_rx_cfg.tx_output_level
+    }
+
+    /// Antenna used for transmission
+    pub fn antenna(&self) -> Antenna {
+        self.tx_rx_cfg.antenna
+    }
+
+    /// Session used by QUERY commands
+    pub fn session(&self) -> Gen2Session {
+        self.inv_cfg.query_params.session
+    }
+
+    /// Target used by QUERY commands
+    pub fn target(&self) -> Gen2QueryTarget {
+        self.inv_cfg.query_params.target
+    }
+
+    /// # Adjusting configurations
+    ///
+    /// Returns this configuration with another transmission output level,
+    /// e.g. to change a single setting of a running reader with
+    /// [`BasicReader::configure_gen2()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let gen2_cfg = Gen2Cfg::builder().build()?;
+    ///
+    /// let gen2_cfg = gen2_cfg
+    ///     .with_tx_output_level(-6)?
+    ///     .with_session(Gen2Session::Session1)
+    ///     .with_target(Gen2QueryTarget::B)
+    ///     .with_antenna(Antenna::Antenna2);
+    ///
+    /// assert_eq!(gen2_cfg.tx_output_level(), -6);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`] if `level` is outside of [-19dB..0dB].
+    pub fn with_tx_output_level(&self, level: i8) -> core::result::Result<Self, Error> {
+        if !(-19..=0).contains(&level) {
+            return Err(Error::Param);
+        }
+
+        let mut cfg = self.clone();
+        cfg.tx_rx_cfg.tx_output_level = level;
+
+        Ok(cfg)
+    }
+
+    /// Returns this configuration with another antenna
+    pub fn with_antenna(&self, antenna: Antenna) -> Self {
+        let mut cfg = self.clone();
+        cfg.tx_rx_cfg.antenna = antenna;
+
+        cfg
+    }
+
+    /// Returns this configuration with another QUERY session
+    pub fn with_session(&self, session: Gen2Session) -> Self {
+        let mut cfg = self.clone();
+        cfg.inv_cfg.query_params.session = session;
+
+        cfg
+    }
+
+    /// Returns this configuration with another QUERY target
+    pub fn with_target(&self, target: Gen2QueryTarget) -> Self {
+        let mut cfg = self.clone();
+        cfg.inv_cfg.query_params.target = target;
+
+        cfg
+    }
+}
+
+/// Records a [`Gen2CfgDiff`] if the two values differ
+fn push_diff<T: PartialEq + fmt::Debug>(
+    diffs: &mut Vec<Gen2CfgDiff>,
+    setting: &'static str,
+    this: &T,
+    other: &T,
+) {
+    if this != other {
+        diffs.push(Gen2CfgDiff {
+            setting,
+            this: format!("{:?}", this),
+            other: format!("{:?}", other),
+        });
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// A setting that differs between two [`Gen2Cfg`]s. See [`Gen2Cfg::diff()`].
+pub struct Gen2CfgDiff {
+    /// Name of the setting that differs
+    pub setting: &'static str,
+    /// Value in the configuration [`Gen2Cfg::diff()`] was called on
+    pub this: String,
+    /// Value in the other configuration
+    pub other: String,
+}
+
+impl fmt::Display for Gen2CfgDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.setting, self.this, self.other)
+    }
+}
+
+impl Gen2CfgBuilder {
+    /// Validates the link timings against the selected Tari and BLF
+    fn validate(&self) -> core::result::Result<(), String> {
+        if let Some(Gen2Timings {
+            t4_min: Some(t4_min),
+        }) = self.timings
+        {
+            let proto_cfg = match self.proto_cfg {
+                Some(proto_cfg) => proto_cfg,
+                None => Gen2ProtocolCfg::builder().build().unwrap(),
+            };
+
+            let min_t4 = Gen2Timings::min_t4(proto_cfg.tari, proto_cfg.blf);
+            if t4_min < min_t4 {
+                return Err(format!(
+                    "t4_min too small for the selected Tari and BLF: must be at least {} us",
+                    min_t4
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Copy, Clone, PartialEq)]
 /// Contains settings used to issue custom commands to the RFID reader.
 /// Warning: expect_header **does nothing** when CRC is disabled.
@@ -348,3 +1093,230 @@ impl<'a> Gen2CustomCommandData<'a> {
         }
     }
 }
+
+#[derive(Clone, PartialEq, Debug)]
+/// Outcome of [`Gen2Reader::write_epc()`](super::Gen2Reader::write_epc)
+pub struct Gen2EpcWriteResult {
+    /// Whether the change was applied completely, partially or not at all
+    pub status: Gen2EpcWriteStatus,
+    /// Number of EPC words written (unchanged words are skipped)
+    pub words_written: usize,
+    /// Whether the length field of the PC was updated
+    pub pc_updated: bool,
+    /// Error that stopped the operation, if any
+    pub error: Option<Error>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+/// Single reply measured by
+/// [`Gen2Reader::measure_rssi()`](super::Gen2Reader::measure_rssi)
+pub struct Gen2RssiSample {
+    /// AGC (Automatic Gain Control) gain reduction
+    pub agc: u8,
+    /// I part of logarithmic RSSI
+    pub log_i: u8,
+    /// Q part of logarithmic RSSI
+    pub log_q: u8,
+    /// I part of linear RSSI
+    pub lin_i: i8,
+    /// Q part of linear RSSI
+    pub lin_q: i8,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+/// Summary statistics of a series of RSSI values
+pub struct Gen2RssiSummary {
+    /// Smallest value
+    pub min: f32,
+    /// Largest value
+    pub max: f32,
+    /// Arithmetic mean
+    pub mean: f32,
+    /// Population standard deviation
+    pub std_dev: f32,
+}
+
+impl Gen2RssiSummary {
+    /// Summarizes `values`. Returns `None` if there are no values.
+    pub fn from_values<I: IntoIterator<Item = f32>>(values: I) -> Option<Self> {
+        let values: Vec<f32> = values.into_iter().collect();
+
+        if values.is_empty() {
+            return None;
+        }
+
+        let n = values.len() as f32;
+        let mean = values.iter().sum::<f32>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n;
+
+        Some(Self {
+            min: values.iter().copied().fold(f32::INFINITY, f32::min),
+            max: values.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            mean,
+            std_dev: variance.sqrt(),
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+/// RSSI samples measured on a single channel by
+/// [`Gen2Reader::measure_rssi()`](super::Gen2Reader::measure_rssi)
+pub struct Gen2RssiMeasurement {
+    /// Frequency the samples were measured on (kHz)
+    pub frequency: u32,
+    /// Receiver sensitivity level during the measurement (dB)
+    pub sensitivity: i8,
+    /// Measured replies, in order. Queries without reply aren't included.
+    pub samples: Vec<Gen2RssiSample>,
+}
+
+impl Gen2RssiMeasurement {
+    /// Collects the samples of a finished measurement. `measureCnt` is
+    /// only an input, so slots without a reply are still in the arrays
+    /// (with zero AGC and log I/Q values) and are dropped here.
+    pub(crate) fn from_ffi(measure: &ffi::STUHFL_T_Gen2_QueryMeasureRssi, sensitivity: i8) -> Self {
+        let samples = (0..measure.measureCnt as usize)
+            .map(|i| Gen2RssiSample {
+                agc: measure.agc[i],
+                log_i: measure.rssiLogI[i],
+                log_q: measure.rssiLogQ[i],
+                lin_i: measure.rssiLinI[i],
+                lin_q: measure.rssiLinQ[i],
+            })
+            .filter(|sample| sample.agc != 0 || sample.log_i != 0 || sample.log_q != 0)
+            .collect();
+
+        Self {
+            frequency: measure.frequency,
+            sensitivity,
+            samples,
+        }
+    }
+
+    /// Summary of the logarithmic I channel
+    pub fn log_i(&self) -> Option<Gen2RssiSummary> {
+        Gen2RssiSummary::from_values(self.samples.iter().map(|s| s.log_i as f32))
+    }
+
+    /// Summary of the logarithmic Q channel
+    pub fn log_q(&self) -> Option<Gen2RssiSummary> {
+        Gen2RssiSummary::from_values(self.samples.iter().map(|s| s.log_q as f32))
+    }
+
+    /// Summary of the AGC gain reduction
+    pub fn agc(&self) -> Option<Gen2RssiSummary> {
+        Gen2RssiSummary::from_values(self.samples.iter().map(|s| s.agc as f32))
+    }
+
+    /// Summary of the received power (dBm), estimated with `model`
+    pub fn to_dbm(&self, model: &RssiModel) -> Option<Gen2RssiSummary> {
+        Gen2RssiSummary::from_values(
+            self.samples
+                .iter()
+                .map(|s| model.raw_to_dbm(s.log_i, s.log_q, s.agc, self.sensitivity)),
+        )
+    }
+}
+
+#[derive(Builder, Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Gen2LockCfgBuilder"),
+    builder(derive(serde::Deserialize))
+)]
+/// Lock settings for [`Gen2Reader::lock()`](super::Gen2Reader::lock).
+/// Fields left unset keep their current lock state on the tag.
+///
+/// # Example
+///
+/// ```
+/// use libstuhfl::prelude::*;
+/// use libstuhfl::gen2::*;
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+///
+/// let lock = Gen2LockCfg::builder()
+///     .access_password(Gen2LockAction::Lock)
+///     .epc(Gen2LockAction::Lock)
+///     .build()?;
+///
+/// assert_eq!(lock.payload(), [0x3C, 0x0A, 0x00]);
+///
+/// # Ok(())
+/// # }
+/// ```
+#[builder(build_fn(validate = "Self::validate"))]
+pub struct Gen2LockCfg {
+    /// Kill password
+    #[builder(default = "None", setter(strip_option))]
+    kill_password: Option<Gen2LockAction>,
+    /// Access password
+    #[builder(default = "None", setter(strip_option))]
+    access_password: Option<Gen2LockAction>,
+    /// EPC memory bank
+    #[builder(default = "None", setter(strip_option))]
+    epc: Option<Gen2LockAction>,
+    /// TID memory bank
+    #[builder(default = "None", setter(strip_option))]
+    tid: Option<Gen2LockAction>,
+    /// User memory bank
+    #[builder(default = "None", setter(strip_option))]
+    user: Option<Gen2LockAction>,
+}
+
+impl Builder<Gen2LockCfgBuilder> for Gen2LockCfg {}
+
+deserialize_via_builder!(Gen2LockCfg, Gen2LockCfgBuilder, Gen2LockCfgBuilderError);
+
+impl Gen2LockCfg {
+    /// The 20 bit mask and action payload of the Lock command, left
+    /// aligned in 3 bytes as the firmware expects it
+    pub fn payload(&self) -> [u8; 3] {
+        let fields = [
+            self.kill_password,
+            self.access_password,
+            self.epc,
+            self.tid,
+            self.user,
+        ];
+
+        let mut mask = 0u32;
+        let mut action = 0u32;
+
+        for field in fields {
+            mask <<= 2;
+            action <<= 2;
+
+            if let Some(field) = field {
+                mask |= 0b11;
+                action |= field.bits();
+            }
+        }
+
+        let payload = (mask << 10 | action) << 4;
+
+        [(payload >> 16) as u8, (payload >> 8) as u8, payload as u8]
+    }
+}
+
+impl Gen2LockCfgBuilder {
+    /// Makes sure something gets locked or unlocked
+    fn validate(&self) -> core::result::Result<(), String> {
+        let fields = [
+            self.kill_password,
+            self.access_password,
+            self.epc,
+            self.tid,
+            self.user,
+        ];
+
+        if !fields.iter().any(|field| matches!(field, Some(Some(_)))) {
+            return Err("no memory bank or password selected".to_owned());
+        }
+
+        Ok(())
+    }
+}
@@ -15,5 +15,8 @@ pub use gen2_reader::Gen2Reader;
 mod gen2_enums;
 /// Structs for gen2
 mod gen2_structs;
+/// Protocol control words
+mod gen2_pc;
 pub use gen2_enums::*;
+pub use gen2_pc::*;
 pub use gen2_structs::*;
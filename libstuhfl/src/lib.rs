@@ -18,13 +18,41 @@ extern crate lazy_static;
 
 extern crate ffi;
 
+/// Lets a builder-based configuration type deserialize through its
+/// builder, so that values loaded from files get the builder defaults
+/// for missing fields and go through the same validation.
+macro_rules! deserialize_via_builder {
+    ($ty:ident, $builder:ident, $error:ident) => {
+        #[cfg(feature = "use-serde")]
+        impl TryFrom<$builder> for $ty {
+            type Error = $error;
+
+            fn try_from(builder: $builder) -> core::result::Result<Self, Self::Error> {
+                builder.build()
+            }
+        }
+    };
+}
+
 /* General Submodules */
 pub mod data_types;
 pub mod error;
+#[cfg(feature = "firmware")]
+pub mod firmware;
 pub mod gen2;
 mod helpers;
+#[cfg(feature = "logging")]
+pub mod logging;
 pub mod prelude;
 pub mod reader;
+#[cfg(feature = "use-serde")]
+pub mod recording;
+#[cfg(feature = "advanced")]
+pub mod registers;
+pub mod rssi;
+#[cfg(feature = "port-scanning")]
+pub mod supervisor;
+pub mod tds;
 
 /* Unit Testing Submodules */
 #[cfg(test)]
@@ -0,0 +1,161 @@
+//! Forwards the trace output of the C library to the [`log`] crate.
+//!
+//! Every trace line of the middleware is logged with a target naming the
+//! layer it comes from, so each layer can be filtered on its own:
+//!
+//! | C log level     | Target             | Level   |
+//! |-----------------|--------------------|---------|
+//! | `INFO`          | `stuhfl`           | Info    |
+//! | `WARNING`       | `stuhfl`           | Warn    |
+//! | `DEBUG`         | `stuhfl`           | Debug   |
+//! | `ERROR`         | `stuhfl`           | Error   |
+//! | `TRACE_AL`      | `stuhfl::al`       | Trace   |
+//! | `TRACE_SL`      | `stuhfl::sl`       | Trace   |
+//! | `TRACE_DL`      | `stuhfl::dl`       | Trace   |
+//! | `TRACE_PL`      | `stuhfl::pl`       | Trace   |
+//! | `TRACE_EVAL_API`| `stuhfl::eval_api` | Trace   |
+//! | `TRACE_BL`      | `stuhfl::bl`       | Trace   |
+//!
+//! Forwarding is enabled when connecting to a reader. Only the layers
+//! enabled in the installed logger are traced by the C library, so the
+//! logger has to be set up first. Applications using `tracing` can
+//! receive these records through `tracing-log`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use libstuhfl::prelude::*;
+//! # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+//!
+//! // e.g. RUST_LOG=stuhfl::dl=trace to see data link frames
+//! env_logger::init();
+//!
+//! let reader = Reader::autoconnect()?;
+//!
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::helpers::proc_err;
+use log::Level;
+use std::os::raw::c_char;
+use std::sync::Once;
+
+/// Size of the C trace buffer of each log level
+const LOG_BUF_SIZE: usize = 4096;
+
+/// C log levels with the target and level they are forwarded as
+const LEVELS: [(u32, &str, Level); ffi::STUHFL_D_LOG_LEVEL_COUNT as usize] = [
+    (ffi::STUHFL_D_LOG_LEVEL_INFO, "stuhfl", Level::Info),
+    (ffi::STUHFL_D_LOG_LEVEL_WARNING, "stuhfl", Level::Warn),
+    (ffi::STUHFL_D_LOG_LEVEL_DEBUG, "stuhfl", Level::Debug),
+    (ffi::STUHFL_D_LOG_LEVEL_ERROR, "stuhfl", Level::Error),
+    (ffi::STUHFL_D_LOG_LEVEL_TRACE_AL, "stuhfl::al", Level::Trace),
+    (ffi::STUHFL_D_LOG_LEVEL_TRACE_SL, "stuhfl::sl", Level::Trace),
+    (ffi::STUHFL_D_LOG_LEVEL_TRACE_DL, "stuhfl::dl", Level::Trace),
+    (ffi::STUHFL_D_LOG_LEVEL_TRACE_PL, "stuhfl::pl", Level::Trace),
+    (
+        ffi::STUHFL_D_LOG_LEVEL_TRACE_EVAL_API,
+        "stuhfl::eval_api",
+        Level::Trace,
+    ),
+    (ffi::STUHFL_D_LOG_LEVEL_TRACE_BL, "stuhfl::bl", Level::Trace),
+];
+
+/// Pointers to the trace buffers handed to the C library
+struct LogBuffers([*mut c_char; ffi::STUHFL_D_LOG_LEVEL_COUNT as usize]);
+
+// The buffers are only ever written by the C library
+unsafe impl Send for LogBuffers {}
+unsafe impl Sync for LogBuffers {}
+
+lazy_static! {
+    /// Trace buffers, which the C library keeps for as long as logging is enabled
+    static ref LOG_BUFFERS: LogBuffers = {
+        let storage: &'static mut [c_char] =
+            Box::leak(vec![0; LOG_BUF_SIZE * LEVELS.len()].into_boxed_slice());
+
+        let mut buffers = [std::ptr::null_mut(); ffi::STUHFL_D_LOG_LEVEL_COUNT as usize];
+        for (buffer, chunk) in buffers.iter_mut().zip(storage.chunks_mut(LOG_BUF_SIZE)) {
+            *buffer = chunk.as_mut_ptr();
+        }
+
+        LogBuffers(buffers)
+    };
+}
+
+/// Makes sure logging is only enabled automatically once
+static AUTO_ENABLE: Once = Once::new();
+
+/// Finds the target and level a C log level is forwarded as
+pub(crate) fn forward_level(level: u32) -> Option<(&'static str, Level)> {
+    LEVELS
+        .iter()
+        .find(|(c_level, _, _)| *c_level == level)
+        .map(|&(_, target, level)| (target, level))
+}
+
+/// C log levels enabled in the installed logger
+fn enabled_levels() -> u32 {
+    LEVELS
+        .iter()
+        .filter(|&&(_, target, level)| log::log_enabled!(target: target, level))
+        .fold(0, |mask, &(c_level, _, _)| mask | c_level)
+}
+
+/// # Forwarding C traces
+///
+/// Registers the log callback with the C library. Only the layers enabled
+/// in the installed logger are traced; call this again after changing the
+/// log filter. This is done automatically when connecting to a reader.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+/// log::set_max_level(log::LevelFilter::Trace);
+///
+/// libstuhfl::logging::enable()?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn enable() -> Result<()> {
+    let option = ffi::STUHFL_T_LogOption {
+        generateLogTimestamp: false,
+        logLevels: enabled_levels(),
+        logBuf: LOG_BUFFERS.0,
+        logBufSize: [LOG_BUF_SIZE as u16; ffi::STUHFL_D_LOG_LEVEL_COUNT as usize],
+    };
+
+    unsafe { proc_err(ffi::STUHFL_F_EnableLog(option, Some(log_callback))) }
+}
+
+/// Stops forwarding C traces. See [`enable()`].
+pub fn disable() -> Result<()> {
+    unsafe { proc_err(ffi::STUHFL_F_DisableLog()) }
+}
+
+/// Enables forwarding the first time a reader is connected
+pub(crate) fn auto_enable() {
+    AUTO_ENABLE.call_once(|| {
+        if let Err(e) = enable() {
+            log::warn!(target: "stuhfl", "Failed to enable C library logging: {}", e);
+        }
+    });
+}
+
+/// Called by the C library for every complete trace line
+unsafe extern "C" fn log_callback(data: ffi::STUHFL_T_LogDataType) -> ffi::STUHFL_T_RET_CODE {
+    // Packed struct, copy it out before reading fields
+    let data = std::ptr::read_unaligned(data as *const ffi::STUHFL_T_LogData);
+
+    if let Some((target, level)) = forward_level(data.logLevel) {
+        let bytes = std::slice::from_raw_parts(data.logBuf as *const u8, data.logBufSize as usize);
+        let line = String::from_utf8_lossy(bytes);
+
+        log::log!(target: target, level, "{}", line.trim_end());
+    }
+
+    ffi::STUHFL_ERR_NONE
+}
@@ -10,6 +10,8 @@ use serialport as sp;
 // CB 7/14/25: Imported for 'parse()' to use
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
+use std::thread;
+use std::time::{Duration, Instant};
 
 // CB 7/7/25: Added text parse function. This function is used to convert the 50-line text
 //            file 'GUI_Tuning_Results.txt' into an array of tuples. This array holds the
@@ -139,22 +141,112 @@ impl Reader {
     /// more info.
     #[cfg(feature = "port-scanning")]
     pub fn autoconnect() -> Result<Self> {
-        let mut found_port: Option<String> = None;
-
-        if let Ok(ports) = sp::available_ports() {
-            for port in ports {
-                if let sp::SerialPortType::UsbPort(port_info) = port.port_type {
-                    if port_info.vid == 0x403
-                        && port_info.pid == 0x6015
-                        && sp::new(&port.port_name, 9600).open().is_ok()
-                    {
-                        found_port = Some(port.port_name);
+        Self::autoconnect_with_options(&ConnectionOptions::default())
+    }
+
+    /// # Connecting to reader
+    ///
+    /// Same as [`Reader::autoconnect()`], with custom serial link settings.
+    /// See [`Reader::connect_with_options()`].
+    #[cfg(feature = "port-scanning")]
+    pub fn autoconnect_with_options(options: &ConnectionOptions) -> Result<Self> {
+        let found_port = find_ports(Self::USB_VID, Self::USB_PID).pop();
+
+        // Try connecting to port
+        if let Some(found_port) = found_port {
+            Self::connect_with_options(&found_port, options)
+        } else {
+            Err(Error::GeneralIo)
+        }
+    }
+
+    /// # Discovering readers
+    ///
+    /// Lists every reader board attached over USB, with its USB descriptors.
+    /// Each board is connected to once to read its version. The C library
+    /// only holds one connection, so while any reader is connected no board
+    /// is probed and every [`ReaderCandidate::version`] is `None`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// for candidate in Reader::discover() {
+    ///     println!(
+    ///         "{}: serial {:?}, {:?}",
+    ///         candidate.port,
+    ///         candidate.serial_number,
+    ///         candidate.version.map(|v| v.sw_ver)
+    ///     );
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "port-scanning")]
+    pub fn discover() -> Vec<ReaderCandidate> {
+        // Connecting would break the open connection
+        let probe = !Connection::is_open();
+        if !probe {
+            log::warn!(target: "stuhfl", "Already connected, not probing readers");
+        }
+
+        usb_ports(Self::USB_VID, Self::USB_PID)
+            .into_iter()
+            .map(|(port, port_info)| {
+                // Also report boards with incompatible firmware, the
+                // connection is closed again when the reader is dropped
+                let version = match probe.then(|| Connection::new(&port)) {
+                    None => None,
+                    Some(Ok(connection)) => Self::new(connection).get_version().ok(),
+                    Some(Err(e)) => {
+                        log::warn!(target: "stuhfl", "No answer from reader on {}: {}", port, e);
+                        None
                     }
+                };
+
+                ReaderCandidate {
+                    port,
+                    serial_number: port_info.serial_number,
+                    manufacturer: port_info.manufacturer,
+                    product: port_info.product,
+                    version,
                 }
-            }
-        }
+            })
+            .collect()
+    }
+
+    /// # Connecting to reader
+    ///
+    /// Connects to the board with the given USB serial number (see
+    /// [`Reader::discover()`]). Unlike [`Reader::autoconnect()`], this always
+    /// picks the same physical board when several are attached.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::connect_by_serial("DK0AHAXF")?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::GeneralIo`] if no board with this serial number is
+    /// attached. See [`Reader::connect()`] for other errors.
+    #[cfg(feature = "port-scanning")]
+    pub fn connect_by_serial(serial_number: &str) -> Result<Self> {
+        let found_port = usb_ports(Self::USB_VID, Self::USB_PID)
+            .into_iter()
+            .find(|(_, port_info)| port_info.serial_number.as_deref() == Some(serial_number))
+            .map(|(port, _)| port);
 
-        // Try connecting to port
         if let Some(found_port) = found_port {
             Self::connect(&found_port)
         } else {
@@ -190,8 +282,39 @@ impl Reader {
     /// will return [`Error::None`] if the reader's firmware or hardware is incompatible
     /// with this library.
     pub fn connect(port: &str) -> Result<Self> {
+        Self::connect_with_options(port, &ConnectionOptions::default())
+    }
+
+    /// # Connecting to reader
+    ///
+    /// Same as [`Reader::connect()`], with custom serial link settings. This is
+    /// mostly needed for setups where the default timeouts are too short, like
+    /// readers behind USB hubs or on long cables. See [`ConnectionOptions`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use std::time::Duration;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let options = ConnectionOptions::builder()
+    ///     .read_timeout(Duration::from_secs(5))
+    ///     .write_timeout(Duration::from_secs(2))
+    ///     .build()?;
+    ///
+    /// let reader = Reader::connect_with_options("/dev/ttyUSB0", &options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Reader::connect()`]. If the reader doesn't answer within the
+    /// ready timeout, the last error of the readiness probe is returned.
+    pub fn connect_with_options(port: &str, options: &ConnectionOptions) -> Result<Self> {
         // Establish connection
-        let connection = Connection::new(port)?;
+        let connection = Connection::with_options(port, options)?;
 
         // Construct new instance
         let board = Self { connection };
@@ -200,11 +323,41 @@ impl Reader {
         if board.test_compatible()? {
             Ok(board)
         } else {
-            eprintln!("Warning: Incompatible Board or Library Version Detected. Please verify that your FW is up to date.");
+            log::warn!(target: "stuhfl", "Incompatible Board or Library Version Detected. Please verify that your FW is up to date.");
             Err(Error::None)
         }
     }
 
+    /// USB vendor ID of the serial bridge on the evaluation board (FTDI)
+    pub const USB_VID: u16 = 0x0403;
+
+    /// USB product ID of the serial bridge on the evaluation board (FT231X)
+    pub const USB_PID: u16 = 0x6015;
+
+    /// Time a reader may take to come back after a reboot
+    pub const REBOOT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Connects to a reader that is restarting, retrying until it
+    /// answers or the timeout expires.
+    pub(crate) fn reconnect(
+        port: &str,
+        options: &ConnectionOptions,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let deadline = Instant::now() + timeout;
+
+        // Give the firmware time to drop the old link first
+        thread::sleep(Duration::from_millis(500));
+
+        loop {
+            match Self::connect_with_options(port, options) {
+                Ok(reader) => return Ok(reader),
+                Err(e) if Instant::now() >= deadline => return Err(e),
+                Err(_) => thread::sleep(Duration::from_millis(500)),
+            }
+        }
+    }
+
     /// Used for constructing new instances during conversion between
     /// reader types.
     pub(crate) fn new(connection: Connection) -> Self {
@@ -212,6 +365,55 @@ impl Reader {
     }
 }
 
+/// Lists the USB serial ports with the given vendor & product ID
+/// which can currently be opened, with their USB descriptors.
+#[cfg(feature = "port-scanning")]
+fn usb_ports(vid: u16, pid: u16) -> Vec<(String, sp::UsbPortInfo)> {
+    let mut found_ports = Vec::new();
+
+    if let Ok(ports) = sp::available_ports() {
+        for port in ports {
+            if let sp::SerialPortType::UsbPort(port_info) = port.port_type {
+                if port_info.vid == vid
+                    && port_info.pid == pid
+                    && sp::new(&port.port_name, 9600).open().is_ok()
+                {
+                    found_ports.push((port.port_name, port_info));
+                }
+            }
+        }
+    }
+
+    found_ports
+}
+
+/// Lists the USB serial ports with the given vendor & product ID
+/// which can currently be opened.
+#[cfg(feature = "port-scanning")]
+pub(crate) fn find_ports(vid: u16, pid: u16) -> Vec<String> {
+    usb_ports(vid, pid)
+        .into_iter()
+        .map(|(port, _)| port)
+        .collect()
+}
+
+/// A reader board found by [`Reader::discover()`]
+#[cfg(feature = "port-scanning")]
+#[derive(Clone, Debug)]
+pub struct ReaderCandidate {
+    /// Port the board is attached to
+    pub port: String,
+    /// USB serial number of the board, unique per board
+    pub serial_number: Option<String>,
+    /// USB manufacturer string
+    pub manufacturer: Option<String>,
+    /// USB product string
+    pub product: Option<String>,
+    /// Firmware & hardware version, `None` if the board didn't answer or
+    /// wasn't probed because a reader was connected
+    pub version: Option<Version>,
+}
+
 /// A fake struct that behaves similarly to a [`ProtocolReader`],
 /// except none of its functions actually do anything. This is
 /// mostly used to help write documentation.
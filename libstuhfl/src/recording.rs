@@ -0,0 +1,645 @@
+//! Records inventory sessions and plays them back.
+//!
+//! A [`Recorder`] saves the reader version, the applied [`Gen2Cfg`] and
+//! every [`InventoryTag`] and [`InventoryStatistics`] of a session to a
+//! file. A [`ReplayReader`] plays the file back with the same commands as
+//! a [`Gen2Reader`], in real time or faster, so field problems can be
+//! reproduced and applications tested without the original tag setup.
+//!
+//! Recordings are JSON lines files. The first line holds the format
+//! version, reader version and configuration, each following line a
+//! [`Record`] with the time it was made (ms after the recording started):
+//!
+//! ```text
+//! {"format":1,"version":{...},"config":{...}}
+//! {"time_ms":112,"event":{"tag":{"slot_id":3,...}}}
+//! {"time_ms":115,"event":{"statistics":{"timestamp":...}}}
+//! ```
+//!
+//! Requires the `use-serde` feature.
+
+use crate::data_types::*;
+use crate::error::{Error, RecordingError, Result};
+use crate::gen2::*;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Version of the file format written by [`Recorder`]
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+/// First line of a recording
+struct Header {
+    /// File format version, see [`FORMAT_VERSION`]
+    format: u32,
+    /// Version of the recorded reader
+    version: Version,
+    /// Configuration applied when the recording started
+    config: Gen2Cfg,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+/// Something that happened during a recorded session
+pub enum Event {
+    /// A tag was found
+    Tag(InventoryTag),
+    /// An inventory round (or a threaded inventory) finished. The tags
+    /// recorded since the previous statistics belong to it.
+    Statistics(InventoryStatistics),
+    /// The reader was configured again
+    Config(Gen2Cfg),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq)]
+/// A recorded [`Event`]
+pub struct Record {
+    /// Time of the event (ms after the recording started)
+    pub time_ms: u64,
+    /// What happened
+    pub event: Event,
+}
+
+/// Writes a recording, see the [module](self) docs.
+///
+/// # Example
+///
+/// ```no_run
+/// use libstuhfl::prelude::*;
+/// use libstuhfl::gen2::*;
+/// use libstuhfl::recording::Recorder;
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+///
+/// let reader = Reader::autoconnect()?;
+/// let mut reader = reader.configure_gen2(&Gen2Cfg::builder().build()?)?;
+/// reader.tune(TuningAlgorithm::Fast)?;
+///
+/// let mut recorder = Recorder::create("session.jsonl", &reader)?;
+///
+/// for _ in 0..100 {
+///     let (statistics, tags) = reader.inventory_once()?;
+///     recorder.round(&statistics, &tags)?;
+/// }
+///
+/// # Ok(())
+/// # }
+/// ```
+///
+/// To record a threaded [`ProtocolCommands::inventory()`], share the
+/// recorder with the callback:
+///
+/// ```no_run
+/// use std::sync::{Arc, Mutex};
+/// use libstuhfl::prelude::*;
+/// use libstuhfl::recording::Recorder;
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+/// # let reader = Reader::autoconnect()?;
+/// # let mut reader = reader.configure_gen2(&libstuhfl::gen2::Gen2Cfg::builder().build()?)?;
+///
+/// let recorder = Arc::new(Mutex::new(Recorder::create("session.jsonl", &reader)?));
+/// let tag_recorder = Arc::clone(&recorder);
+///
+/// let statistics = reader.inventory(
+///     50,
+///     Box::new(move |tag| {
+///         if let Err(e) = tag_recorder.lock().unwrap().tag(&tag) {
+///             eprintln!("Failed to record tag: {}", e);
+///         }
+///     }),
+/// )?;
+///
+/// recorder.lock().unwrap().statistics(&statistics)?;
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct Recorder<W: Write> {
+    /// Destination of the records
+    writer: W,
+    /// Time the recording started
+    started: Instant,
+}
+
+impl Recorder<BufWriter<File>> {
+    /// Creates a recording file for a session of `reader`, saving its
+    /// version and current configuration
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        reader: &Gen2Reader,
+    ) -> core::result::Result<Self, RecordingError> {
+        let version = reader.get_version()?;
+        let config = reader.current_config()?;
+
+        Self::new(BufWriter::new(File::create(path)?), &version, &config)
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// Starts a recording written to `writer`
+    pub fn new(
+        writer: W,
+        version: &Version,
+        config: &Gen2Cfg,
+    ) -> core::result::Result<Self, RecordingError> {
+        let mut recorder = Self {
+            writer,
+            started: Instant::now(),
+        };
+
+        recorder.write_line(&Header {
+            format: FORMAT_VERSION,
+            version: version.clone(),
+            config: config.clone(),
+        })?;
+
+        Ok(recorder)
+    }
+
+    /// Writes a value as a single line
+    fn write_line<T: serde::Serialize>(&mut self, value: &T) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, value).map_err(io::Error::from)?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// Records an event at the current time
+    fn record(&mut self, event: Event) -> core::result::Result<(), RecordingError> {
+        let record = Record {
+            time_ms: self.started.elapsed().as_millis() as u64,
+            event,
+        };
+
+        Ok(self.write_line(&record)?)
+    }
+
+    /// Records a tag found
+    pub fn tag(&mut self, tag: &InventoryTag) -> core::result::Result<(), RecordingError> {
+        self.record(Event::Tag(tag.clone()))
+    }
+
+    /// Records the statistics of a finished round, or threaded inventory
+    pub fn statistics(
+        &mut self,
+        statistics: &InventoryStatistics,
+    ) -> core::result::Result<(), RecordingError> {
+        self.record(Event::Statistics(*statistics))
+    }
+
+    /// Records a round of [`ProtocolCommands::inventory_once()`]
+    pub fn round(
+        &mut self,
+        statistics: &InventoryStatistics,
+        tags: &[InventoryTag],
+    ) -> core::result::Result<(), RecordingError> {
+        for tag in tags {
+            self.tag(tag)?;
+        }
+
+        self.statistics(statistics)
+    }
+
+    /// Records a configuration change
+    pub fn config(&mut self, config: &Gen2Cfg) -> core::result::Result<(), RecordingError> {
+        self.record(Event::Config(config.clone()))
+    }
+
+    /// Writes buffered records
+    pub fn flush(&mut self) -> core::result::Result<(), RecordingError> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[derive(Clone)]
+/// A recording loaded from a file
+pub struct Recording {
+    /// Version of the recorded reader
+    pub version: Version,
+    /// Configuration applied when the recording started
+    pub config: Gen2Cfg,
+    /// Recorded events, in order
+    pub records: Vec<Record>,
+}
+
+impl Recording {
+    /// Loads a recording file
+    pub fn open<P: AsRef<Path>>(path: P) -> core::result::Result<Self, RecordingError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Loads a recording from `reader`
+    pub fn read<R: BufRead>(reader: R) -> core::result::Result<Self, RecordingError> {
+        let mut lines = reader.lines();
+
+        let first = lines.next().transpose()?.unwrap_or_default();
+        let header: Header =
+            serde_json::from_str(&first).map_err(|e| RecordingError::Parse(1, e))?;
+
+        if header.format != FORMAT_VERSION {
+            return Err(RecordingError::UnsupportedFormat(header.format));
+        }
+
+        let mut records = Vec::new();
+
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+
+            // Tolerate a trailing empty line
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            records.push(serde_json::from_str(&line).map_err(|e| RecordingError::Parse(i + 2, e))?);
+        }
+
+        Ok(Self {
+            version: header.version,
+            config: header.config,
+            records,
+        })
+    }
+
+    /// Number of rounds (statistics events) recorded
+    pub fn rounds(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|record| matches!(record.event, Event::Statistics(_)))
+            .count()
+    }
+
+    /// Time between the first and the last event
+    pub fn duration(&self) -> Duration {
+        match (self.records.first(), self.records.last()) {
+            (Some(first), Some(last)) => {
+                Duration::from_millis(last.time_ms.saturating_sub(first.time_ms))
+            }
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+/// Position of a [`ReplayReader`] in its recording
+struct Playback {
+    /// Index of the next record
+    next: usize,
+    /// Time of the last record played (ms)
+    time_ms: u64,
+    /// Configuration at this position
+    config: Gen2Cfg,
+}
+
+/// Plays a [`Recording`] back, with the same commands as a [`Gen2Reader`].
+///
+/// Inventories return the recorded tags and statistics in order, taking
+/// as long as they did when recorded (divided by the speed set with
+/// [`Self::set_speed()`]). Each [`Self::inventory_once()`] returns the
+/// tags up to the next statistics event. At the end of the recording,
+/// inventories fail with [`Error::NoMsg`] unless looping is enabled.
+///
+/// Recordings don't contain tag memory, so only the EPC bank (from the
+/// PC word on) and the TID recorded for the selected tag can be read,
+/// and nothing can be written.
+///
+/// Without a connection to a real reader it isn't a [`ProtocolReader`],
+/// but it offers the same [`ProtocolCommands`], so code generic over them
+/// runs on recordings as well.
+///
+/// # Example
+///
+/// ```no_run
+/// use libstuhfl::prelude::*;
+/// use libstuhfl::recording::ReplayReader;
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+///
+/// let mut reader = ReplayReader::open("session.jsonl")?;
+/// reader.set_speed(10.0)?;
+/// reader.tune(TuningAlgorithm::Fast)?;
+///
+/// println!("Recorded with {}", reader.get_version()?);
+///
+/// while let Ok((statistics, tags)) = reader.inventory_once() {
+///     println!("{} tags at {} kHz", tags.len(), statistics.frequency);
+/// }
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReplayReader {
+    /// Recording played back
+    recording: Recording,
+    /// Playback speed, 1.0 being real time
+    speed: f64,
+    /// Whether playback restarts at the end of the recording
+    looping: bool,
+    /// Whether the reader was tuned
+    is_tuned: bool,
+    /// EPC of the selected tag
+    selected: Option<Epc>,
+    /// Current position
+    playback: RefCell<Playback>,
+}
+
+impl ReplayReader {
+    /// Plays `recording` back in real time
+    pub fn new(recording: Recording) -> Self {
+        let playback = RefCell::new(Self::start(&recording));
+
+        Self {
+            recording,
+            speed: 1.0,
+            looping: false,
+            is_tuned: false,
+            selected: None,
+            playback,
+        }
+    }
+
+    /// Plays a recording file back in real time
+    pub fn open<P: AsRef<Path>>(path: P) -> core::result::Result<Self, RecordingError> {
+        Ok(Self::new(Recording::open(path)?))
+    }
+
+    /// Position at the start of a recording
+    fn start(recording: &Recording) -> Playback {
+        Playback {
+            next: 0,
+            time_ms: recording.records.first().map_or(0, |record| record.time_ms),
+            config: recording.config.clone(),
+        }
+    }
+
+    /// Sets the playback speed: 1.0 for real time, 10.0 for ten times
+    /// faster, [`f64::INFINITY`] to not wait at all.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Param`] if `speed` isn't positive.
+    pub fn set_speed(&mut self, speed: f64) -> Result<()> {
+        if speed.is_nan() || speed <= 0.0 {
+            return Err(Error::Param);
+        }
+
+        self.speed = speed;
+
+        Ok(())
+    }
+
+    /// Sets whether playback restarts at the end of the recording
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Goes back to the start of the recording
+    pub fn rewind(&mut self) {
+        *self.playback.get_mut() = Self::start(&self.recording);
+    }
+
+    /// Whether the whole recording was played
+    pub fn is_finished(&self) -> bool {
+        self.playback.borrow().next == self.recording.records.len()
+    }
+
+    /// The recording played back
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Returns the recorded reader version
+    pub fn get_version(&self) -> Result<Version> {
+        Ok(self.recording.version.clone())
+    }
+
+    /// Returns the configuration recorded at the current position
+    pub fn current_config(&self) -> Result<Gen2Cfg> {
+        Ok(self.playback.borrow().config.clone())
+    }
+
+    /// Waits until `elapsed_ms` of recording time are over, at the
+    /// playback speed, since `started`
+    fn wait(&self, started: Instant, elapsed_ms: u64) {
+        if self.speed.is_infinite() {
+            return;
+        }
+
+        let deadline = started + Duration::from_secs_f64(elapsed_ms as f64 / 1000.0 / self.speed);
+        let now = Instant::now();
+
+        if deadline > now {
+            thread::sleep(deadline - now);
+        }
+    }
+
+    /// Plays the next round, passing its tags to `tag_cb`. `elapsed_ms`
+    /// is the recording time played since `started`.
+    fn play_round(
+        &self,
+        started: Instant,
+        elapsed_ms: &mut u64,
+        tag_cb: &mut dyn FnMut(InventoryTag),
+    ) -> Result<InventoryStatistics> {
+        let records = &self.recording.records;
+        let mut playback = self.playback.borrow_mut();
+
+        loop {
+            if playback.next == records.len() {
+                // Looping only ends if there is a round to return
+                if !self.looping || self.recording.rounds() == 0 {
+                    return Err(Error::NoMsg);
+                }
+
+                *playback = Self::start(&self.recording);
+            }
+
+            let record = &records[playback.next];
+            playback.next += 1;
+
+            *elapsed_ms += record.time_ms.saturating_sub(playback.time_ms);
+            playback.time_ms = record.time_ms;
+            self.wait(started, *elapsed_ms);
+
+            match &record.event {
+                Event::Tag(tag) => {
+                    if self.selected.as_ref().is_none_or(|epc| *epc == tag.epc) {
+                        tag_cb(tag.clone());
+                    }
+                }
+                Event::Statistics(statistics) => return Ok(*statistics),
+                Event::Config(config) => playback.config = config.clone(),
+            }
+        }
+    }
+
+    /// Plays back all tags again, see [`Gen2Reader::clear_select()`]
+    pub fn clear_select(&mut self) -> Result<()> {
+        self.selected = None;
+
+        Ok(())
+    }
+}
+
+impl ProtocolCommands for ReplayReader {
+    /// Marks the reader as tuned, with any algorithm
+    fn tune(&mut self, _algo: TuningAlgorithm) -> Result<()> {
+        self.is_tuned = true;
+
+        Ok(())
+    }
+
+    /// Plays the next round
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Generic`] if the reader isn't tuned, or
+    /// [`Error::NoMsg`] at the end of the recording.
+    fn inventory_once(&self) -> Result<(InventoryStatistics, Vec<InventoryTag>)> {
+        if !self.is_tuned {
+            return Err(Error::Generic);
+        }
+
+        let mut tags = Vec::new();
+        let statistics = self.play_round(Instant::now(), &mut 0, &mut |tag| tags.push(tag))?;
+
+        Ok((statistics, tags))
+    }
+
+    /// Plays `num_rounds` rounds, calling `cb` for each tag, and returns
+    /// the statistics of the last one. Stops early at the end of the
+    /// recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Generic`] if the reader isn't tuned or `cb`
+    /// panicked, [`Error::None`] if `num_rounds` is 0, or
+    /// [`Error::NoMsg`] if the recording was already over.
+    fn inventory(&mut self, num_rounds: u32, cb: Box<CallbackFn>) -> Result<InventoryStatistics> {
+        if !self.is_tuned {
+            return Err(Error::Generic);
+        }
+
+        if num_rounds == 0 {
+            log::error!(target: "stuhfl", "num_rounds = 0 not yet implemented!");
+            return Err(Error::None);
+        }
+
+        let started = Instant::now();
+        let mut elapsed_ms = 0;
+        let mut statistics = None;
+        let mut panicked = false;
+
+        // Like the threaded inventory of a real reader, panics in the callback are caught
+        let mut tag_cb = |tag: InventoryTag| {
+            panicked |= panic::catch_unwind(AssertUnwindSafe(|| cb(tag))).is_err()
+        };
+
+        for _ in 0..num_rounds {
+            match self.play_round(started, &mut elapsed_ms, &mut tag_cb) {
+                Ok(round) => statistics = Some(round),
+                Err(_) if statistics.is_some() => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        if panicked {
+            return Err(Error::Generic);
+        }
+
+        statistics.ok_or(Error::NoMsg)
+    }
+
+    /// Only plays back the tags with `epc`, and makes it the tag read.
+    fn select(&mut self, epc: &Epc) -> Result<()> {
+        if !self.is_tuned {
+            return Err(Error::Generic);
+        }
+
+        self.selected = Some(epc.clone());
+
+        Ok(())
+    }
+
+    /// Reads recorded memory of the selected tag: the EPC bank from the
+    /// PC word on, or its TID.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ChipNoResp`] if no recorded tag is selected,
+    /// [`Error::Gen2NotSupported`] for other memory banks, and
+    /// [`Error::Gen2MemOverRun`] past the recorded memory.
+    fn read(
+        &mut self,
+        bank: MemoryBank,
+        word_address: u32,
+        num_bytes: u8,
+        _password: Option<Password>,
+    ) -> Result<Vec<u8>> {
+        if !self.is_tuned {
+            return Err(Error::Generic);
+        }
+
+        let selected = self.selected.as_ref().ok_or(Error::ChipNoResp)?;
+        let mut tags = self
+            .recording
+            .records
+            .iter()
+            .filter_map(|record| match &record.event {
+                Event::Tag(tag) if tag.epc == *selected => Some(tag),
+                _ => None,
+            });
+
+        // Memory as recorded, and the word address it starts at
+        let (memory, first_word) = match bank {
+            MemoryBank::Epc => {
+                let tag = tags.next().ok_or(Error::ChipNoResp)?;
+                ([&tag.pc[..], tag.epc.get_id()].concat(), 1)
+            }
+            MemoryBank::Tid => {
+                let tag = tags
+                    .find(|tag| !tag.tid.get_id().is_empty())
+                    .ok_or(Error::Gen2NotSupported)?;
+                (tag.tid.get_id().to_vec(), 0)
+            }
+            _ => return Err(Error::Gen2NotSupported),
+        };
+
+        let start = word_address
+            .checked_sub(first_word)
+            .ok_or(Error::Gen2NotSupported)?;
+        // Addresses past the end of usize are past the recorded memory too
+        let start = usize::try_from(start)
+            .ok()
+            .and_then(|word| word.checked_mul(2))
+            .ok_or(Error::Gen2MemOverRun)?;
+        let end = start
+            .checked_add(num_bytes as usize)
+            .ok_or(Error::Gen2MemOverRun)?;
+
+        memory
+            .get(start..end)
+            .map(|data| data.to_vec())
+            .ok_or(Error::Gen2MemOverRun)
+    }
+
+    /// Always fails with [`Error::Gen2NotSupported`] (or [`Error::Generic`]
+    /// if the reader isn't tuned), as recordings can't be changed.
+    fn write(
+        &mut self,
+        _bank: MemoryBank,
+        _word_adddress: u32,
+        _data: [u8; 2],
+        _password: Option<Password>,
+    ) -> Result<()> {
+        if !self.is_tuned {
+            return Err(Error::Generic);
+        }
+
+        Err(Error::Gen2NotSupported)
+    }
+}
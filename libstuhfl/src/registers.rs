@@ -0,0 +1,466 @@
+//! Module containing raw access to the ST25RU3993 register file.
+//!
+//! Requires the `advanced` feature. Writing registers directly bypasses
+//! the firmware's own bookkeeping, so settings made here may be overwritten
+//! by later configuration commands (and vice versa). See
+//! [`BasicReader::dump_registers()`] to take a snapshot of the chip's state.
+//!
+//! [`BasicReader::dump_registers()`]: crate::data_types::BasicReader::dump_registers
+
+use crate::gen2::{Gen2Blf, Gen2Coding, Gen2Tari};
+use enum_primitive::FromPrimitive;
+use std::fmt;
+
+enum_from_primitive! {
+    #[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+    #[repr(u8)]
+    /// Named registers of the ST25RU3993. See the ST25RU3993 datasheet
+    /// for the meaning of each register.
+    pub enum Register {
+        /// Device status control (see [`DeviceStatusControl`])
+        DeviceStatusControl = 0x00,
+        /// Protocol selection
+        ProtocolSelection = 0x01,
+        /// TX options (see [`TxOptions`])
+        TxOptions = 0x02,
+        /// RX options (see [`RxOptions`])
+        RxOptions = 0x03,
+        /// TRcal high and miscellaneous
+        TrcalHighMisc = 0x04,
+        /// TRcal low
+        TrcalLow = 0x05,
+        /// AutoACK timer
+        AutoAckTimer = 0x06,
+        /// RX no response wait time
+        RxNoResponseWaitTime = 0x07,
+        /// RX wait time
+        RxWaitTime = 0x08,
+        /// RX filter settings
+        RxFilter = 0x09,
+        /// RX mixer and gain
+        RxMixerGain = 0x0A,
+        /// Regulator and I/O control
+        RegulatorIoControl = 0x0B,
+        /// RF output and LO control
+        RfOutputLoControl = 0x0C,
+        /// Miscellaneous 1
+        Miscellaneous1 = 0x0D,
+        /// Miscellaneous 2
+        Miscellaneous2 = 0x0E,
+        /// Measurement control
+        MeasurementControl = 0x0F,
+        /// VCO control
+        VcoControl = 0x10,
+        /// Charge pump control
+        ChargePumpControl = 0x11,
+        /// Modulator control 1
+        ModulatorControl1 = 0x12,
+        /// Modulator control 2
+        ModulatorControl2 = 0x13,
+        /// Modulator control 3 (TX output level)
+        ModulatorControl3 = 0x14,
+        /// Modulator control 4
+        ModulatorControl4 = 0x15,
+        /// PLL main 1
+        PllMain1 = 0x16,
+        /// PLL main 2
+        PllMain2 = 0x17,
+        /// PLL main 3
+        PllMain3 = 0x18,
+        /// PLL auxiliary 1
+        PllAuxiliary1 = 0x19,
+        /// PLL auxiliary 2
+        PllAuxiliary2 = 0x1A,
+        /// PLL auxiliary 3
+        PllAuxiliary3 = 0x1B,
+        /// ICD
+        Icd = 0x1C,
+        /// Mixer IQ level
+        MixerIqLevel = 0x1D,
+        /// Emitter coupled mixer options
+        EmitterCoupledMixerOptions = 0x22,
+        /// Status page
+        StatusPage = 0x29,
+        /// AGC and internal status
+        AgcInternalStatus = 0x2A,
+        /// RSSI levels
+        RssiLevels = 0x2B,
+        /// AGL status
+        AglStatus = 0x2C,
+        /// ADC
+        Adc = 0x2D,
+        /// Command status
+        CommandStatus = 0x2E,
+        /// Device version
+        DeviceVersion = 0x33,
+        /// Interrupt mask 1
+        InterruptMask1 = 0x35,
+        /// Interrupt mask 2
+        InterruptMask2 = 0x36,
+        /// Interrupt 1 (cleared on read)
+        Interrupt1 = 0x37,
+        /// Interrupt 2 (cleared on read)
+        Interrupt2 = 0x38,
+        /// FIFO status
+        FifoStatus = 0x39,
+        /// RX length 1
+        RxLength1 = 0x3A,
+        /// RX length 2
+        RxLength2 = 0x3B,
+        /// TX settings
+        TxSettings = 0x3C,
+        /// TX length 1
+        TxLength1 = 0x3D,
+        /// TX length 2
+        TxLength2 = 0x3E,
+        /// FIFO I/O (read pops the FIFO)
+        FifoIo = 0x3F,
+    }
+}
+
+impl Register {
+    /// Every named register, in address order.
+    pub const ALL: [Register; 49] = [
+        Register::DeviceStatusControl,
+        Register::ProtocolSelection,
+        Register::TxOptions,
+        Register::RxOptions,
+        Register::TrcalHighMisc,
+        Register::TrcalLow,
+        Register::AutoAckTimer,
+        Register::RxNoResponseWaitTime,
+        Register::RxWaitTime,
+        Register::RxFilter,
+        Register::RxMixerGain,
+        Register::RegulatorIoControl,
+        Register::RfOutputLoControl,
+        Register::Miscellaneous1,
+        Register::Miscellaneous2,
+        Register::MeasurementControl,
+        Register::VcoControl,
+        Register::ChargePumpControl,
+        Register::ModulatorControl1,
+        Register::ModulatorControl2,
+        Register::ModulatorControl3,
+        Register::ModulatorControl4,
+        Register::PllMain1,
+        Register::PllMain2,
+        Register::PllMain3,
+        Register::PllAuxiliary1,
+        Register::PllAuxiliary2,
+        Register::PllAuxiliary3,
+        Register::Icd,
+        Register::MixerIqLevel,
+        Register::EmitterCoupledMixerOptions,
+        Register::StatusPage,
+        Register::AgcInternalStatus,
+        Register::RssiLevels,
+        Register::AglStatus,
+        Register::Adc,
+        Register::CommandStatus,
+        Register::DeviceVersion,
+        Register::InterruptMask1,
+        Register::InterruptMask2,
+        Register::Interrupt1,
+        Register::Interrupt2,
+        Register::FifoStatus,
+        Register::RxLength1,
+        Register::RxLength2,
+        Register::TxSettings,
+        Register::TxLength1,
+        Register::TxLength2,
+        Register::FifoIo,
+    ];
+
+    /// Register address
+    pub fn address(self) -> u8 {
+        self as u8
+    }
+
+    /// Whether the register holds configuration that can be written
+    /// (and restored from a [`RegisterDump`]).
+    pub fn is_writable(self) -> bool {
+        self <= Register::EmitterCoupledMixerOptions
+            || self == Register::InterruptMask1
+            || self == Register::InterruptMask2
+    }
+
+    /// Whether reading the register has side effects (clearing
+    /// interrupts or popping the FIFO). These are never dumped.
+    pub fn has_read_side_effects(self) -> bool {
+        matches!(
+            self,
+            Register::Interrupt1 | Register::Interrupt2 | Register::FifoIo
+        )
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:02X} {:?}", self.address(), self)
+    }
+}
+
+/// Typed view of a single register's bitfields.
+/// See [`BasicReader::get_register_bits()`].
+///
+/// [`BasicReader::get_register_bits()`]: crate::data_types::BasicReader::get_register_bits
+pub trait RegisterBits: From<u8> + Into<u8> + Copy {
+    /// Register the bitfields belong to
+    const REGISTER: Register;
+}
+
+/// Returns whether the given bit is set
+fn get_bit(value: u8, bit: u8) -> bool {
+    value & (1 << bit) != 0
+}
+
+/// Sets or clears the given bit
+fn set_bit(value: &mut u8, bit: u8, on: bool) {
+    if on {
+        *value |= 1 << bit;
+    } else {
+        *value &= !(1 << bit);
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Bitfields of [`Register::DeviceStatusControl`].
+pub struct DeviceStatusControl(u8);
+
+impl DeviceStatusControl {
+    /// RF output enabled
+    pub fn rf_on(&self) -> bool {
+        get_bit(self.0, 0)
+    }
+
+    /// Enable or disable the RF output
+    pub fn set_rf_on(&mut self, on: bool) {
+        set_bit(&mut self.0, 0, on)
+    }
+
+    /// Receiver enabled
+    pub fn rec_on(&self) -> bool {
+        get_bit(self.0, 1)
+    }
+
+    /// Enable or disable the receiver
+    pub fn set_rec_on(&mut self, on: bool) {
+        set_bit(&mut self.0, 1, on)
+    }
+
+    /// Automatic gain control enabled
+    pub fn agc_on(&self) -> bool {
+        get_bit(self.0, 2)
+    }
+
+    /// Enable or disable automatic gain control
+    pub fn set_agc_on(&mut self, on: bool) {
+        set_bit(&mut self.0, 2, on)
+    }
+
+    /// Direct mode enabled
+    pub fn direct(&self) -> bool {
+        get_bit(self.0, 6)
+    }
+
+    /// Enable or disable direct mode
+    pub fn set_direct(&mut self, on: bool) {
+        set_bit(&mut self.0, 6, on)
+    }
+
+    /// Standby mode enabled
+    pub fn standby(&self) -> bool {
+        get_bit(self.0, 7)
+    }
+
+    /// Enable or disable standby mode
+    pub fn set_standby(&mut self, on: bool) {
+        set_bit(&mut self.0, 7, on)
+    }
+}
+
+impl From<u8> for DeviceStatusControl {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<DeviceStatusControl> for u8 {
+    fn from(bits: DeviceStatusControl) -> u8 {
+        bits.0
+    }
+}
+
+impl RegisterBits for DeviceStatusControl {
+    const REGISTER: Register = Register::DeviceStatusControl;
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Bitfields of [`Register::TxOptions`].
+pub struct TxOptions(u8);
+
+impl TxOptions {
+    /// Tari setting (bits 2:0), `None` if the value is reserved
+    pub fn tari(&self) -> Option<Gen2Tari> {
+        Gen2Tari::from_u8(self.0 & 0b0000_0111)
+    }
+
+    /// Set the Tari setting
+    pub fn set_tari(&mut self, tari: Gen2Tari) {
+        self.0 = (self.0 & !0b0000_0111) | tari as u8;
+    }
+}
+
+impl From<u8> for TxOptions {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<TxOptions> for u8 {
+    fn from(bits: TxOptions) -> u8 {
+        bits.0
+    }
+}
+
+impl RegisterBits for TxOptions {
+    const REGISTER: Register = Register::TxOptions;
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// Bitfields of [`Register::RxOptions`].
+pub struct RxOptions(u8);
+
+impl RxOptions {
+    /// Backscatter link frequency (bits 7:4), `None` if the value is reserved
+    pub fn blf(&self) -> Option<Gen2Blf> {
+        Gen2Blf::from_u8(self.0 >> 4)
+    }
+
+    /// Set the backscatter link frequency
+    pub fn set_blf(&mut self, blf: Gen2Blf) {
+        self.0 = (self.0 & 0b0000_1111) | ((blf as u8) << 4);
+    }
+
+    /// Coding (bits 1:0)
+    pub fn coding(&self) -> Gen2Coding {
+        Gen2Coding::from_u8(self.0 & 0b0000_0011).unwrap()
+    }
+
+    /// Set the coding
+    pub fn set_coding(&mut self, coding: Gen2Coding) {
+        self.0 = (self.0 & !0b0000_0011) | coding as u8;
+    }
+}
+
+impl From<u8> for RxOptions {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<RxOptions> for u8 {
+    fn from(bits: RxOptions) -> u8 {
+        bits.0
+    }
+}
+
+impl RegisterBits for RxOptions {
+    const REGISTER: Register = Register::RxOptions;
+}
+
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+/// Snapshot of the register file. See [`BasicReader::dump_registers()`].
+///
+/// [`BasicReader::dump_registers()`]: crate::data_types::BasicReader::dump_registers
+pub struct RegisterDump {
+    /// Register values, in address order
+    values: Vec<(Register, u8)>,
+}
+
+impl RegisterDump {
+    /// Creates a dump from register values.
+    pub fn from(values: &[(Register, u8)]) -> Self {
+        let mut values = Vec::from(values);
+        values.sort_by_key(|(reg, _)| *reg);
+        values.dedup_by_key(|(reg, _)| *reg);
+
+        Self { values }
+    }
+
+    /// Value of a register, if it is part of the dump.
+    pub fn get(&self, register: Register) -> Option<u8> {
+        self.values
+            .binary_search_by_key(&register, |(reg, _)| *reg)
+            .ok()
+            .map(|i| self.values[i].1)
+    }
+
+    /// Iterates over all registers in the dump, in address order.
+    pub fn iter(&self) -> impl Iterator<Item = (Register, u8)> + '_ {
+        self.values.iter().copied()
+    }
+
+    /// Lists every register whose value differs between the two dumps
+    /// (including registers only present in one of them).
+    pub fn diff(&self, other: &RegisterDump) -> Vec<RegisterDiff> {
+        Register::ALL
+            .iter()
+            .filter_map(|&register| {
+                let this = self.get(register);
+                let other = other.get(register);
+
+                if this != other {
+                    Some(RegisterDiff {
+                        register,
+                        this,
+                        other,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (register, value) in &self.values {
+            writeln!(f, "{}: 0x{:02X}", register, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// A register that differs between two [`RegisterDump`]s.
+pub struct RegisterDiff {
+    /// Register that differs
+    pub register: Register,
+    /// Value in the dump [`RegisterDump::diff()`] was called on
+    pub this: Option<u8>,
+    /// Value in the other dump
+    pub other: Option<u8>,
+}
+
+impl fmt::Display for RegisterDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Formats a register value, or dashes if it is missing
+        fn value(v: Option<u8>) -> String {
+            v.map(|v| format!("0x{:02X}", v))
+                .unwrap_or_else(|| "----".to_owned())
+        }
+
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.register,
+            value(self.this),
+            value(self.other)
+        )
+    }
+}
@@ -0,0 +1,385 @@
+//! Converts RSSI readings into received power (dBm).
+//!
+//! The reader reports tag signal strength as logarithmic I and Q channel
+//! values ([`InventoryTag::rssi_log_i`], [`InventoryTag::rssi_log_q`]) measured
+//! with the current receiver sensitivity and AGC gain reduction. An
+//! [`RssiModel`] combines these into an estimate of the power at the
+//! receiver input:
+//!
+//! ```text
+//! dBm = offset + 10 * log10(10^(I * step / 10) + 10^(Q * step / 10))
+//!              + sensitivity + AGC steps * AGC step
+//! ```
+//!
+//! The default model uses nominal values. As the offset depends on the
+//! board and its antenna path, it should be calibrated per board with
+//! [`RssiModel::calibrate()`] and stored in an [`RssiCalibration`] file.
+
+use crate::data_types::*;
+#[cfg(feature = "use-serde")]
+use crate::error::ConfigFileError;
+use crate::error::{Error, Result};
+use crate::gen2::*;
+use std::collections::BTreeMap;
+use std::f32::consts::PI;
+
+/// Speed of light (m/s)
+const SPEED_OF_LIGHT: f32 = 299_792_458.0;
+
+/// Bits of the AGC value holding the gain reduction steps
+const AGC_STEPS_MASK: u8 = 0x07;
+
+#[derive(Builder, Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "use-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RssiModelBuilder"),
+    builder(derive(serde::Deserialize))
+)]
+#[builder(build_fn(validate = "Self::validate"))]
+/// Conversion of RSSI readings to dBm. See the [module](self) docs and
+/// [`Self::builder()`] for details.
+///
+/// # Example
+///
+/// ```
+/// use libstuhfl::prelude::*;
+/// use libstuhfl::rssi::*;
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+///
+/// let model = RssiModel::builder().offset_dbm(-80.0).build()?;
+///
+/// // I = Q = 5 at sensitivity 0 and no AGC: 10 dB + 3 dB for two equal channels
+/// let dbm = model.raw_to_dbm(5, 5, 0, 0);
+/// assert!((dbm - -66.99).abs() < 0.01);
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct RssiModel {
+    /// Received power (dBm) for a reading of 0 on both channels, at
+    /// sensitivity level 0 without AGC gain reduction. This is the value
+    /// found by calibration.
+    #[builder(default = "-90.0")]
+    offset_dbm: f32,
+    /// Power step of the logarithmic RSSI values (dB)
+    #[builder(default = "2.0")]
+    log_step_db: f32,
+    /// Gain reduction per AGC step (dB)
+    #[builder(default = "3.0")]
+    agc_step_db: f32,
+}
+
+impl Builder<RssiModelBuilder> for RssiModel {}
+
+deserialize_via_builder!(RssiModel, RssiModelBuilder, RssiModelBuilderError);
+
+impl Default for RssiModel {
+    fn default() -> Self {
+        RssiModelBuilder::default().build().unwrap()
+    }
+}
+
+impl RssiModelBuilder {
+    /// Validates state by making sure all values are finite and the
+    /// step sizes are positive.
+    fn validate(&self) -> core::result::Result<(), String> {
+        if let Some(offset) = self.offset_dbm {
+            if !offset.is_finite() {
+                return Err("offset_dbm must be finite".to_owned());
+            }
+        }
+
+        for (name, step) in [
+            ("log_step_db", self.log_step_db),
+            ("agc_step_db", self.agc_step_db),
+        ] {
+            if let Some(step) = step {
+                if !step.is_finite() || step < 0.0 {
+                    return Err(format!("{} must not be negative", name));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl RssiModel {
+    /// Calibrated offset (dBm)
+    pub fn offset_dbm(&self) -> f32 {
+        self.offset_dbm
+    }
+
+    /// # Converting readings
+    ///
+    /// Estimates the received power of a raw reading. `sensitivity` is the
+    /// receiver sensitivity level at the time of the reading (dB), as
+    /// reported in [`InventoryStatistics::sensitivity`].
+    pub fn raw_to_dbm(&self, log_i: u8, log_q: u8, agc: u8, sensitivity: i8) -> f32 {
+        let channel = |log: u8| 10f32.powf(log as f32 * self.log_step_db / 10.0);
+        let combined = 10.0 * (channel(log_i) + channel(log_q)).log10();
+
+        self.offset_dbm
+            + combined
+            + sensitivity as f32
+            + (agc & AGC_STEPS_MASK) as f32 * self.agc_step_db
+    }
+
+    /// Estimates the received power of a tag found during the inventory
+    /// round `statistics` belong to.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// use libstuhfl::rssi::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    /// let mut reader = reader.configure_gen2(&Gen2Cfg::builder().build()?)?;
+    /// reader.tune(TuningAlgorithm::Exact)?;
+    ///
+    /// let model = RssiModel::default();
+    /// let (stats, tags) = reader.inventory_once()?;
+    ///
+    /// for tag in tags {
+    ///     println!("{}: {:.1} dBm", tag.epc, model.to_dbm(&tag, &stats));
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_dbm(&self, tag: &InventoryTag, statistics: &InventoryStatistics) -> f32 {
+        self.raw_to_dbm(
+            tag.rssi_log_i,
+            tag.rssi_log_q,
+            tag.agc,
+            statistics.sensitivity,
+        )
+    }
+
+    /// Estimates the mean received power of the tags found so far, from
+    /// [`InventoryStatistics::rssi_log_mean`]. The mean is kept for a
+    /// single channel, so both channels are assumed to be equal and AGC
+    /// isn't accounted for.
+    pub fn mean_to_dbm(&self, statistics: &InventoryStatistics) -> f32 {
+        let mean = statistics.rssi_log_mean;
+
+        self.raw_to_dbm(mean, mean, 0, statistics.sensitivity)
+    }
+
+    /// Sets the offset so that `readings` (log I, log Q, AGC, sensitivity)
+    /// match `expected_dbm` on average. Returns the new offset, or `None`
+    /// if there are no readings.
+    pub fn fit_offset(&mut self, readings: &[(u8, u8, u8, i8)], expected_dbm: f32) -> Option<f32> {
+        if readings.is_empty() {
+            return None;
+        }
+
+        let uncalibrated = Self {
+            offset_dbm: 0.0,
+            ..*self
+        };
+
+        let mean = readings
+            .iter()
+            .map(|&(i, q, agc, sensitivity)| uncalibrated.raw_to_dbm(i, q, agc, sensitivity))
+            .sum::<f32>()
+            / readings.len() as f32;
+
+        self.offset_dbm = expected_dbm - mean;
+
+        Some(self.offset_dbm)
+    }
+
+    /// # Calibrating
+    ///
+    /// Runs `rounds` inventory rounds with a reference tag placed at a known
+    /// distance from the antenna, and sets the offset so that the readings
+    /// of the reference tag match its expected received power (see
+    /// [`ReferenceTag::expected_dbm()`]). Returns the new offset.
+    ///
+    /// The reader must be tuned, and should be configured with the same
+    /// output power as given in `reference`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// use libstuhfl::rssi::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let reader = Reader::autoconnect()?;
+    /// let mut reader = reader.configure_gen2(&Gen2Cfg::builder().build()?)?;
+    /// reader.tune(TuningAlgorithm::Exact)?;
+    ///
+    /// let reference = ReferenceTag::builder()
+    ///     .epc(Epc::from_id(vec![0xE2, 0x80, 0x11, 0x05, 0x20, 0x00, 0x74, 0x13, 0x12, 0x34, 0x56, 0x78]))
+    ///     .distance_m(1.0)
+    ///     .tx_power_dbm(20.0)
+    ///     .build()?;
+    ///
+    /// let mut model = RssiModel::default();
+    /// model.calibrate(&mut reader, &reference, 50)?;
+    ///
+    /// let mut calibration = RssiCalibration::default();
+    /// calibration.set("FT1ABCDE", model);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ChipNoResp`] if the reference tag wasn't found in
+    /// any round, or the error of the inventory.
+    pub fn calibrate(
+        &mut self,
+        reader: &mut Gen2Reader,
+        reference: &ReferenceTag,
+        rounds: u32,
+    ) -> Result<f32> {
+        let mut readings = Vec::new();
+        let mut expected = 0.0;
+
+        for _ in 0..rounds {
+            let (statistics, tags) = reader.inventory_once()?;
+
+            for tag in tags.iter().filter(|tag| tag.epc == reference.epc) {
+                readings.push((
+                    tag.rssi_log_i,
+                    tag.rssi_log_q,
+                    tag.agc,
+                    statistics.sensitivity,
+                ));
+                expected += reference.expected_dbm(statistics.frequency);
+            }
+        }
+
+        if readings.is_empty() {
+            return Err(Error::ChipNoResp);
+        }
+
+        // Rounds may run on different channels
+        let expected = expected / readings.len() as f32;
+
+        let offset = self.fit_offset(&readings, expected).unwrap();
+
+        log::info!(
+            target: "stuhfl",
+            "RSSI calibrated with {} readings: offset {:.1} dBm",
+            readings.len(),
+            offset
+        );
+
+        Ok(offset)
+    }
+}
+
+#[derive(Builder, Clone, PartialEq, Debug)]
+#[builder(build_fn(validate = "Self::validate"))]
+/// Tag used to calibrate an [`RssiModel`], and the link it is read over.
+/// See [`Self::builder()`] for details.
+pub struct ReferenceTag {
+    /// EPC of the reference tag
+    epc: Epc,
+    /// Distance between reader antenna and tag (m)
+    distance_m: f32,
+    /// Reader output power at the antenna port (dBm)
+    tx_power_dbm: f32,
+    /// Reader antenna gain (dBi)
+    #[builder(default = "6.0")]
+    antenna_gain_dbi: f32,
+    /// Tag antenna gain (dBi)
+    #[builder(default = "2.0")]
+    tag_gain_dbi: f32,
+    /// Loss of the tag's backscatter modulation and cable losses (dB)
+    #[builder(default = "6.0")]
+    backscatter_loss_db: f32,
+}
+
+impl Builder<ReferenceTagBuilder> for ReferenceTag {}
+
+impl ReferenceTagBuilder {
+    /// Validates state by making sure the distance is positive.
+    fn validate(&self) -> core::result::Result<(), String> {
+        if let Some(distance) = self.distance_m {
+            if !distance.is_finite() || distance <= 0.0 {
+                return Err("distance_m must be finite and positive".to_owned());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ReferenceTag {
+    /// Expected received power (dBm) on the given frequency (kHz), from
+    /// the free space path loss of the way to the tag and back.
+    pub fn expected_dbm(&self, frequency: u32) -> f32 {
+        let wavelength = SPEED_OF_LIGHT / (frequency as f32 * 1000.0);
+        let path_loss = 20.0 * (4.0 * PI * self.distance_m / wavelength).log10();
+
+        self.tx_power_dbm + 2.0 * (self.antenna_gain_dbi + self.tag_gain_dbi)
+            - 2.0 * path_loss
+            - self.backscatter_loss_db
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "use-serde", derive(serde::Serialize, serde::Deserialize))]
+/// Calibrated [`RssiModel`]s of several boards, keyed by an identifier
+/// such as the USB serial number
+/// (see [`ReaderCandidate`](crate::reader::ReaderCandidate)).
+pub struct RssiCalibration {
+    /// Model of each board
+    boards: BTreeMap<String, RssiModel>,
+}
+
+impl RssiCalibration {
+    /// Model of a board, or the uncalibrated default if it is unknown
+    pub fn model(&self, board: &str) -> RssiModel {
+        self.boards.get(board).copied().unwrap_or_default()
+    }
+
+    /// Stores the model of a board
+    pub fn set(&mut self, board: &str, model: RssiModel) {
+        self.boards.insert(board.to_owned(), model);
+    }
+
+    /// Whether a board has been calibrated
+    pub fn contains(&self, board: &str) -> bool {
+        self.boards.contains_key(board)
+    }
+
+    /// # Loading calibration files
+    ///
+    /// Reads calibrations from a TOML file, with a table per board:
+    ///
+    /// ```toml
+    /// [boards.FT1ABCDE]
+    /// offset_dbm = -87.5
+    /// ```
+    ///
+    /// Missing values get their defaults.
+    #[cfg(feature = "use-serde")]
+    pub fn from_toml_file<P: AsRef<std::path::Path>>(
+        path: P,
+    ) -> core::result::Result<Self, ConfigFileError> {
+        let contents = std::fs::read_to_string(path)?;
+
+        Ok(toml::from_str(&contents)?)
+    }
+
+    /// # Saving calibration files
+    ///
+    /// Writes all calibrations as TOML, to be loaded with
+    /// [`Self::from_toml_file()`].
+    #[cfg(feature = "use-serde")]
+    pub fn to_toml_string(&self) -> core::result::Result<String, ConfigFileError> {
+        Ok(toml::to_string(self)?)
+    }
+}
@@ -0,0 +1,392 @@
+//! Keeps a Gen2 session running across link losses.
+//!
+//! Unplugging the evaluation board (or a board reset) makes every call fail
+//! with [`Error::GeneralIo`] or [`Error::Timeout`], and the [`Gen2Reader`] can't
+//! be used anymore. A [`SupervisedReader`] notices this, searches for the
+//! board again by its USB vendor & product ID, reconnects, reapplies the
+//! last [`Gen2Cfg`] and tuning, and then continues with the interrupted
+//! operation. The application is told about each step through
+//! [`SupervisorEvent`]s.
+
+use crate::data_types::*;
+use crate::error::{Error, Result};
+use crate::gen2::*;
+use crate::reader::{find_ports, Reader};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Callback receiving supervisor events
+pub type EventFn = dyn FnMut(&SupervisorEvent) + Send;
+
+#[derive(Clone, PartialEq, Debug)]
+/// State changes of a supervised link, see [`SupervisedReader::on_event()`].
+pub enum SupervisorEvent {
+    /// The reader stopped answering
+    LinkLost {
+        /// Error the failed operation returned
+        error: Error,
+    },
+    /// The reader was found again and the session was restored
+    Reconnected {
+        /// Port the reader is now connected to
+        port: String,
+        /// Connection attempts needed
+        attempts: u32,
+        /// Time the link was down
+        downtime: Duration,
+    },
+    /// The reader didn't come back in time. The next operation
+    /// starts searching for it again.
+    GaveUp {
+        /// Last connection error
+        error: Error,
+    },
+}
+
+#[derive(Builder, Clone, PartialEq, Debug)]
+/// Reconnection settings. See [`Self::builder()`] for details.
+pub struct SupervisorOptions {
+    /// Serial link settings used for every connection
+    #[builder(default)]
+    connection: ConnectionOptions,
+    /// USB vendor ID of the reader's serial bridge
+    #[builder(default = "Reader::USB_VID")]
+    vendor_id: u16,
+    /// USB product ID of the reader's serial bridge
+    #[builder(default = "Reader::USB_PID")]
+    product_id: u16,
+    /// Time to keep searching for the reader before giving up
+    #[builder(default = "Duration::from_secs(60)")]
+    reconnect_timeout: Duration,
+    /// Time between two connection attempts
+    #[builder(default = "Duration::from_secs(1)")]
+    retry_interval: Duration,
+}
+
+impl Builder<SupervisorOptionsBuilder> for SupervisorOptions {}
+
+impl Default for SupervisorOptions {
+    fn default() -> Self {
+        SupervisorOptionsBuilder::default().build().unwrap()
+    }
+}
+
+/// Whether an error means that the reader can't be reached anymore
+/// (as opposed to e.g. a tag not answering).
+pub(crate) fn is_link_error(error: &Error) -> bool {
+    matches!(error, Error::GeneralIo | Error::Timeout)
+}
+
+/// A [`Gen2Reader`] that reconnects by itself. See the [module](self) docs.
+///
+/// # Example
+///
+/// ```no_run
+/// use libstuhfl::prelude::*;
+/// use libstuhfl::gen2::*;
+/// use libstuhfl::supervisor::*;
+/// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+///
+/// let cfg = Gen2Cfg::builder().build()?;
+///
+/// let mut reader = SupervisedReader::connect(&cfg, SupervisorOptions::default())?;
+///
+/// reader.on_event(|event| println!("{:?}", event));
+///
+/// reader.tune(TuningAlgorithm::Exact)?;
+///
+/// // Keeps going when the board is unplugged and plugged back in
+/// reader.inventory(u32::MAX, |tag| println!("{}", tag.epc))?;
+///
+/// # Ok(())
+/// # }
+/// ```
+pub struct SupervisedReader {
+    /// Configured reader, `None` while the link is down
+    reader: Option<Gen2Reader>,
+    /// Port of the last connection
+    port: String,
+    /// Configuration reapplied after reconnecting
+    cfg: Gen2Cfg,
+    /// Channel list with tuned capacitor values, if tuned
+    tuning: Option<ChannelListCfg>,
+    /// Algorithm the reader was tuned with, used to tune again after
+    /// reconnecting when no tuned channel list is saved
+    tuning_algo: Option<TuningAlgorithm>,
+    /// Reconnection settings
+    options: SupervisorOptions,
+    /// Application callback for events
+    on_event: Option<Box<EventFn>>,
+}
+
+impl SupervisedReader {
+    /// # Connecting to reader
+    ///
+    /// Searches for a reader the same way as [`Reader::autoconnect()`] and
+    /// configures it with `cfg`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::GeneralIo`] if no reader is found, or the error of
+    /// connecting to or configuring the reader.
+    pub fn connect(cfg: &Gen2Cfg, options: SupervisorOptions) -> Result<Self> {
+        let mut supervised = Self {
+            reader: None,
+            port: String::new(),
+            cfg: cfg.clone(),
+            tuning: None,
+            tuning_algo: None,
+            options,
+            on_event: None,
+        };
+
+        supervised.restore()?;
+
+        Ok(supervised)
+    }
+
+    /// Supervises an already configured reader. `cfg` must be the
+    /// configuration the reader was set up with, and `tuning` the
+    /// algorithm it was tuned with (if any), so the reader is tuned
+    /// again after reconnecting.
+    pub fn from_reader(
+        reader: Gen2Reader,
+        cfg: &Gen2Cfg,
+        tuning: Option<TuningAlgorithm>,
+        options: SupervisorOptions,
+    ) -> Self {
+        let port = reader.connection().port().to_owned();
+
+        Self {
+            reader: Some(reader),
+            port,
+            cfg: cfg.clone(),
+            tuning: None,
+            tuning_algo: tuning,
+            options,
+            on_event: None,
+        }
+    }
+
+    /// Sets the callback receiving [`SupervisorEvent`]s. It is called from
+    /// the thread running the interrupted operation.
+    pub fn on_event<F>(&mut self, callback: F)
+    where
+        F: FnMut(&SupervisorEvent) + Send + 'static,
+    {
+        self.on_event = Some(Box::new(callback));
+    }
+
+    /// Whether the reader is currently connected
+    pub fn is_connected(&self) -> bool {
+        self.reader.is_some()
+    }
+
+    /// Port of the current (or last) connection
+    pub fn port(&self) -> &str {
+        &self.port
+    }
+
+    /// Configuration applied after each reconnect
+    pub fn config(&self) -> &Gen2Cfg {
+        &self.cfg
+    }
+
+    /// # Running operations
+    ///
+    /// Runs `operation` on the reader. If the link is lost, the reader is
+    /// reconnected and `operation` is run once more.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use libstuhfl::prelude::*;
+    /// use libstuhfl::gen2::*;
+    /// use libstuhfl::supervisor::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let cfg = Gen2Cfg::builder().build()?;
+    /// let mut reader = SupervisedReader::connect(&cfg, SupervisorOptions::default())?;
+    /// reader.tune(TuningAlgorithm::Exact)?;
+    ///
+    /// let (_stats, tags) = reader.run(|reader| reader.inventory_once())?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `operation`, or the connection error if the
+    /// reader couldn't be reconnected within the reconnect timeout.
+    pub fn run<T, F>(&mut self, mut operation: F) -> Result<T>
+    where
+        F: FnMut(&mut Gen2Reader) -> Result<T>,
+    {
+        match operation(self.reader()?) {
+            Err(e) if is_link_error(&e) => {
+                self.recover(e)?;
+                operation(self.reader()?)
+            }
+            result => result,
+        }
+    }
+
+    /// # Tuning reader
+    ///
+    /// Same as [`ProtocolCommands::tune()`]. The resulting tuning is saved and
+    /// restored after reconnecting, without tuning again.
+    pub fn tune(&mut self, algo: TuningAlgorithm) -> Result<()> {
+        let tuning = self.run(|reader| {
+            reader.tune(algo)?;
+            reader.get_channel_list()
+        })?;
+
+        self.tuning = Some(tuning);
+        self.tuning_algo = Some(algo);
+
+        Ok(())
+    }
+
+    /// # Reconfiguring reader
+    ///
+    /// Applies a new configuration, which is also used from now on when
+    /// reconnecting. The saved tuning is discarded, as it may not match
+    /// the new channel list.
+    pub fn configure(&mut self, cfg: &Gen2Cfg) -> Result<()> {
+        if let Some(reader) = self.reader.take() {
+            self.reader = Some(reader.disconfigure().configure_gen2(cfg)?);
+        }
+
+        self.cfg = cfg.clone();
+        self.tuning = None;
+        self.tuning_algo = None;
+
+        Ok(())
+    }
+
+    /// # Running inventory
+    ///
+    /// Same as [`ProtocolCommands::inventory()`]. When the link is lost, the
+    /// inventory is started again (with all `num_rounds`) as soon as the
+    /// reader is back, until it completes or the reader can't be reconnected.
+    pub fn inventory<F>(&mut self, num_rounds: u32, callback: F) -> Result<InventoryStatistics>
+    where
+        F: Fn(InventoryTag) + Send + Sync + 'static,
+    {
+        let callback = Arc::new(callback);
+
+        loop {
+            let cb = Arc::clone(&callback);
+
+            let result = self
+                .reader()?
+                .inventory(num_rounds, Box::new(move |tag| cb(tag)));
+
+            match result {
+                Err(e) if is_link_error(&e) => self.recover(e)?,
+                result => return result,
+            }
+        }
+    }
+
+    /// Stops supervising, returning the reader if it is connected.
+    pub fn into_inner(self) -> Option<Gen2Reader> {
+        self.reader
+    }
+
+    /// Returns the connected reader, reconnecting first if the link is down
+    fn reader(&mut self) -> Result<&mut Gen2Reader> {
+        if self.reader.is_none() {
+            self.reconnect()?;
+        }
+
+        Ok(self.reader.as_mut().unwrap())
+    }
+
+    /// Handles a lost link by reconnecting
+    fn recover(&mut self, error: Error) -> Result<()> {
+        log::warn!(target: "stuhfl", "Lost link to reader on {}: {}", self.port, error);
+        self.emit(SupervisorEvent::LinkLost { error });
+
+        self.reconnect()
+    }
+
+    /// Searches for the reader until it is restored or the timeout expires
+    fn reconnect(&mut self) -> Result<()> {
+        // The C library only holds a single link, close the dead one first
+        self.reader = None;
+
+        let start = Instant::now();
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            match self.restore() {
+                Ok(()) => {
+                    log::info!(target: "stuhfl", "Reconnected to reader on {}", self.port);
+                    self.emit(SupervisorEvent::Reconnected {
+                        port: self.port.clone(),
+                        attempts,
+                        downtime: start.elapsed(),
+                    });
+                    return Ok(());
+                }
+                Err(error) if start.elapsed() >= self.options.reconnect_timeout => {
+                    log::error!(target: "stuhfl", "Giving up reconnecting to reader: {}", error);
+                    self.emit(SupervisorEvent::GaveUp { error });
+                    return Err(error);
+                }
+                Err(_) => thread::sleep(self.options.retry_interval),
+            }
+        }
+    }
+
+    /// Connects to the first reader found and restores the session on it
+    fn restore(&mut self) -> Result<()> {
+        let mut ports = find_ports(self.options.vendor_id, self.options.product_id);
+
+        // The OS may rename the port, but prefer the old one if it's back
+        if let Some(i) = ports.iter().position(|port| *port == self.port) {
+            ports.swap(0, i);
+        }
+
+        let mut error = Error::GeneralIo;
+
+        for port in ports {
+            match self.restore_on(&port) {
+                Ok(reader) => {
+                    self.reader = Some(reader);
+                    self.port = port;
+                    return Ok(());
+                }
+                Err(e) => error = e,
+            }
+        }
+
+        Err(error)
+    }
+
+    /// Connects to the reader on `port` and reapplies configuration & tuning
+    fn restore_on(&self, port: &str) -> Result<Gen2Reader> {
+        let reader = Reader::connect_with_options(port, &self.options.connection)?;
+        let mut reader = reader.configure_gen2(&self.cfg)?;
+
+        if let Some(tuning) = &self.tuning {
+            reader.restore_tuning(tuning)?;
+        } else if let Some(algo) = self.tuning_algo {
+            reader.tune(algo)?;
+        }
+
+        Ok(reader)
+    }
+
+    /// Passes an event to the application
+    fn emit(&mut self, event: SupervisorEvent) {
+        if let Some(on_event) = self.on_event.as_mut() {
+            on_event(&event);
+        }
+    }
+}
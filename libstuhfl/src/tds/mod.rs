@@ -0,0 +1,52 @@
+//! Module decoding and encoding EPCs according to the GS1 Tag Data Standard.
+//!
+//! Supports SGTIN-96/198, SSCC-96, SGLN-96/195, GRAI-96/170, GIAI-96/202,
+//! GID-96 and the TDS 2.0 SGTIN+ and DSGTIN+ schemes. TIDs are decoded
+//! with [`TidInfo`].
+//!
+//! # Example
+//!
+//! ```
+//! use libstuhfl::tds::*;
+//! # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+//!
+//! let bytes = [0x30, 0x74, 0x25, 0x7B, 0xF7, 0x19, 0x4E, 0x40, 0x00, 0x00, 0x1A, 0x85];
+//! let epc = Gs1Epc::decode(&bytes)?;
+//!
+//! assert_eq!(epc.scheme, EpcScheme::Sgtin96);
+//! assert_eq!(epc.filter, 3);
+//! assert_eq!(epc.partition(), Some(5));
+//! assert_eq!(epc.pure_identity_uri()?, "urn:epc:id:sgtin:0614141.812345.6789");
+//!
+//! if let Gs1Identity::Sgtin(sgtin) = &epc.identity {
+//!     assert_eq!(sgtin.gtin, "80614141123458");
+//!     assert_eq!(sgtin.serial, "6789");
+//! }
+//!
+//! // Encode a new identity, e.g. to write it with `Gen2Reader::write()`
+//! let epc = Gs1Epc::from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.812345.6790")?;
+//! let bytes = epc.encode()?;
+//!
+//! # Ok(())
+//! # }
+//! ```
+
+/// Bit fields, GS1 strings and check digits
+mod tds_bits;
+/// Registry of tag chips
+mod tds_chips;
+/// TDS 2.0 `+` schemes
+mod tds_plus;
+/// Binary schemes with a company prefix partition, and GID-96
+mod tds_schemes;
+/// TID decoding
+mod tds_tid;
+/// Pure identity and tag URIs
+mod tds_uri;
+
+/// Enums for tds
+mod tds_enums;
+/// Structs for tds
+mod tds_structs;
+pub use tds_enums::*;
+pub use tds_structs::*;
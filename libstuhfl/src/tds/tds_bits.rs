@@ -0,0 +1,251 @@
+use crate::error::TdsError;
+
+/// Reads big-endian bit fields from EPC memory
+pub(crate) struct BitReader<'a> {
+    /// EPC bytes
+    data: &'a [u8],
+    /// Index of the next bit to read
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Starts reading at the first bit of `data`
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Number of bits left
+    pub(crate) fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.pos
+    }
+
+    /// Makes sure `bits` more bits can be read
+    pub(crate) fn require(&self, bits: usize) -> Result<(), TdsError> {
+        if self.remaining() < bits {
+            Err(TdsError::Length {
+                expected: self.pos + bits,
+                found: self.data.len() * 8,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reads an unsigned integer of up to 128 bits
+    pub(crate) fn read(&mut self, bits: usize) -> Result<u128, TdsError> {
+        debug_assert!(bits <= 128);
+        self.require(bits)?;
+
+        let mut value = 0;
+        for _ in 0..bits {
+            let bit = (self.data[self.pos / 8] >> (7 - self.pos % 8)) & 1;
+            value = (value << 1) | bit as u128;
+            self.pos += 1;
+        }
+
+        Ok(value)
+    }
+
+    /// Reads a field of up to 64 bits
+    pub(crate) fn read_u64(&mut self, bits: usize) -> Result<u64, TdsError> {
+        debug_assert!(bits <= 64);
+        Ok(self.read(bits)? as u64)
+    }
+
+    /// Reads a zero padded field of 7 bit characters (TDS string encoding)
+    pub(crate) fn read_string(
+        &mut self,
+        bits: usize,
+        field: &'static str,
+    ) -> Result<String, TdsError> {
+        self.require(bits)?;
+
+        let mut value = String::new();
+        let mut padding = false;
+
+        for _ in 0..bits / 7 {
+            let c = self.read(7)? as u8;
+
+            if c == 0 {
+                padding = true;
+            } else if padding {
+                // Characters after the padding
+                return Err(TdsError::Field(field));
+            } else if is_string_char(c as char) {
+                value.push(c as char);
+            } else {
+                return Err(TdsError::Field(field));
+            }
+        }
+
+        // Unused bits at the end of the field
+        self.read(bits % 7)?;
+
+        Ok(value)
+    }
+}
+
+/// Writes big-endian bit fields into EPC memory
+pub(crate) struct BitWriter {
+    /// EPC bytes
+    data: Vec<u8>,
+    /// Number of bits written
+    len: usize,
+}
+
+impl BitWriter {
+    /// Starts an empty EPC
+    pub(crate) fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Writes the lowest `bits` bits of `value`
+    pub(crate) fn write(&mut self, value: u128, bits: usize) {
+        for i in (0..bits).rev() {
+            if self.len == self.data.len() * 8 {
+                self.data.push(0);
+            }
+
+            let bit = ((value >> i) & 1) as u8;
+            *self.data.last_mut().unwrap() |= bit << (7 - self.len % 8);
+            self.len += 1;
+        }
+    }
+
+    /// Writes a string as 7 bit characters, zero padded to `bits`
+    pub(crate) fn write_string(&mut self, value: &str, bits: usize) {
+        for c in value.chars() {
+            self.write(c as u128, 7);
+        }
+
+        self.write(0, bits - value.len() * 7);
+    }
+
+    /// Pads to a whole number of words and returns the EPC bytes
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        let padding = (16 - self.len % 16) % 16;
+        self.write(0, padding);
+        self.data
+    }
+}
+
+/// Whether a character may be used in TDS strings (GS1 AI encodable character set 82)
+pub(crate) fn is_string_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!\"%&'()*+,-./:;<=>?_".contains(c)
+}
+
+/// Characters which must be escaped in EPC URIs
+const URI_ESCAPED: &str = "\"%&/<>?";
+
+/// Escapes a TDS string for use in an EPC URI
+pub(crate) fn uri_escape(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if URI_ESCAPED.contains(c) {
+                format!("%{:02X}", c as u8)
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Reverts [`uri_escape()`]
+pub(crate) fn uri_unescape(value: &str, field: &'static str) -> Result<String, TdsError> {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            let hex: String = chars.by_ref().take(2).collect();
+            let c = u8::from_str_radix(&hex, 16).map_err(|_| TdsError::Field(field))? as char;
+
+            if !URI_ESCAPED.contains(c) {
+                return Err(TdsError::Field(field));
+            }
+            unescaped.push(c);
+        } else if URI_ESCAPED.contains(c) || !is_string_char(c) {
+            return Err(TdsError::Field(field));
+        } else {
+            unescaped.push(c);
+        }
+    }
+
+    Ok(unescaped)
+}
+
+/// GS1 check digit of a digit string
+pub(crate) fn check_digit(digits: &str) -> char {
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| (d - b'0') as u32 * if i % 2 == 0 { 3 } else { 1 })
+        .sum();
+
+    char::from(b'0' + ((10 - sum % 10) % 10) as u8)
+}
+
+/// Makes sure a GS1 key has the right length and check digit
+pub(crate) fn check_key(key: &str, len: usize, field: &'static str) -> Result<(), TdsError> {
+    if key.len() != len
+        || !is_digits(key)
+        || check_digit(&key[..len - 1]) != key.as_bytes()[len - 1] as char
+    {
+        Err(TdsError::Field(field))
+    } else {
+        Ok(())
+    }
+}
+
+/// Whether a string only consists of decimal digits
+pub(crate) fn is_digits(value: &str) -> bool {
+    value.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Parses a digit string of the given length into an integer
+pub(crate) fn parse_digits(value: &str, len: usize, field: &'static str) -> Result<u128, TdsError> {
+    if value.len() != len || !is_digits(value) {
+        return Err(TdsError::Field(field));
+    }
+
+    Ok(value.parse().unwrap_or(0))
+}
+
+/// Formats an integer as a zero padded digit string of the given length
+pub(crate) fn format_digits(
+    value: u128,
+    len: usize,
+    field: &'static str,
+) -> Result<String, TdsError> {
+    if len < 39 && value >= 10u128.pow(len as u32) {
+        return Err(TdsError::Field(field));
+    }
+
+    Ok(if len == 0 {
+        String::new()
+    } else {
+        format!("{:0len$}", value, len = len)
+    })
+}
+
+/// Parses a serial encoded as an integer, which must not have leading zeros
+pub(crate) fn parse_integer(
+    value: &str,
+    bits: usize,
+    field: &'static str,
+) -> Result<u128, TdsError> {
+    let valid = !value.is_empty()
+        && value.len() <= 39
+        && is_digits(value)
+        && (value == "0" || !value.starts_with('0'));
+
+    match value.parse::<u128>() {
+        Ok(parsed) if valid && parsed >> bits == 0 => Ok(parsed),
+        _ => Err(TdsError::Field(field)),
+    }
+}
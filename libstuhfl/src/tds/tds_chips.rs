@@ -0,0 +1,114 @@
+use super::tds_structs::ChipInfo;
+
+/// Mask designer IDs assigned by GS1
+const MANUFACTURERS: &[(u16, &str)] = &[
+    (0x001, "Impinj"),
+    (0x002, "Texas Instruments"),
+    (0x003, "Alien Technology"),
+    (0x004, "Intelleflex"),
+    (0x005, "Atmel"),
+    (0x006, "NXP Semiconductors"),
+    (0x007, "STMicroelectronics"),
+    (0x008, "EP Microelectronics"),
+    (0x009, "Motorola"),
+    (0x00A, "Sentech Snd Bhd"),
+    (0x00B, "EM Microelectronic"),
+    (0x00C, "Renesas Technology"),
+    (0x00D, "Mstar"),
+    (0x00E, "Tyco International"),
+    (0x00F, "Quanray Electronics"),
+    (0x010, "Fujitsu"),
+    (0x011, "LSIS"),
+    (0x012, "CAEN RFID"),
+    (0x013, "Productivity Engineering"),
+    (0x014, "Federal Electric"),
+    (0x015, "ON Semiconductor"),
+    (0x016, "Ramtron"),
+    (0x017, "Tego"),
+    (0x018, "Ceitec"),
+    (0x019, "CPA Wernher von Braun"),
+    (0x01A, "TransCore"),
+    (0x01B, "Nationz"),
+    (0x01C, "Invengo"),
+    (0x01D, "Kiloway"),
+];
+
+/// Shorthand for registry entries
+const fn chip(
+    mask_designer_id: u16,
+    model_number: u16,
+    model: &'static str,
+    epc_bits: u16,
+    user_memory_bits: u16,
+    block_write: bool,
+) -> ChipInfo {
+    let mut i = 0;
+    let mut manufacturer = "";
+
+    while i < MANUFACTURERS.len() {
+        if MANUFACTURERS[i].0 == mask_designer_id {
+            manufacturer = MANUFACTURERS[i].1;
+        }
+        i += 1;
+    }
+
+    ChipInfo {
+        mask_designer_id,
+        model_number,
+        manufacturer,
+        model,
+        epc_bits,
+        user_memory_bits,
+        block_write,
+    }
+}
+
+/// Chips known to the registry, with memory sizes from their datasheets
+const CHIPS: &[ChipInfo] = &[
+    // Impinj
+    chip(0x001, 0x100, "Monza 4D", 128, 32, true),
+    chip(0x001, 0x104, "Monza 4U", 128, 512, true),
+    chip(0x001, 0x105, "Monza 4QT", 128, 512, true),
+    chip(0x001, 0x10C, "Monza 4E", 496, 128, true),
+    chip(0x001, 0x130, "Monza 5", 128, 32, true),
+    chip(0x001, 0x160, "Monza R6", 96, 0, false),
+    chip(0x001, 0x170, "Monza R6-P", 128, 32, false),
+    chip(0x001, 0x190, "M750", 128, 32, false),
+    chip(0x001, 0x191, "M730", 128, 0, false),
+    // Alien
+    chip(0x003, 0x412, "Higgs 3", 480, 512, true),
+    chip(0x003, 0x414, "Higgs 4", 128, 128, true),
+    chip(0x003, 0x811, "Higgs 9", 496, 688, true),
+    // NXP
+    chip(0x006, 0x003, "UCODE G2XM", 240, 512, false),
+    chip(0x006, 0x004, "UCODE G2XL", 240, 0, false),
+    chip(0x006, 0x890, "UCODE 7", 128, 0, false),
+    chip(0x006, 0x894, "UCODE 8", 128, 0, false),
+    chip(0x006, 0x994, "UCODE 8m", 128, 32, false),
+    chip(0x006, 0x915, "UCODE 9", 96, 0, false),
+    // EM Microelectronic
+    chip(0x00B, 0x040, "EM4325", 352, 3072, true),
+];
+
+impl ChipInfo {
+    /// Looks up a chip by mask designer ID (without indicator bits)
+    /// and model number. See [`TidInfo::chip()`](super::TidInfo::chip).
+    pub fn lookup(mask_designer_id: u16, model_number: u16) -> Option<&'static ChipInfo> {
+        CHIPS.iter().find(|chip| {
+            chip.mask_designer_id == mask_designer_id && chip.model_number == model_number
+        })
+    }
+
+    /// Name of a mask designer (without indicator bits)
+    pub fn manufacturer(mask_designer_id: u16) -> Option<&'static str> {
+        MANUFACTURERS
+            .iter()
+            .find(|(id, _)| *id == mask_designer_id)
+            .map(|(_, name)| *name)
+    }
+
+    /// All chips in the registry
+    pub fn all() -> &'static [ChipInfo] {
+        CHIPS
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+
+enum_from_primitive! {
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    #[repr(u8)]
+    /// EPC binary encoding schemes, identified by the EPC header.
+    ///
+    /// These values are from the GS1 Tag Data Standard.
+    pub enum EpcScheme {
+        /// Serialised GTIN, 38 bit numeric serial
+        Sgtin96 = 0x30,
+        /// Serial Shipping Container Code
+        Sscc96 = 0x31,
+        /// Global Location Number with 41 bit numeric extension
+        Sgln96 = 0x32,
+        /// Global Returnable Asset Identifier, 38 bit numeric serial
+        Grai96 = 0x33,
+        /// Global Individual Asset Identifier, numeric asset reference
+        Giai96 = 0x34,
+        /// General Identifier
+        Gid96 = 0x35,
+        /// Serialised GTIN, alphanumeric serial (up to 20 characters)
+        Sgtin198 = 0x36,
+        /// Global Returnable Asset Identifier, alphanumeric serial (up to 16 characters)
+        Grai170 = 0x37,
+        /// Global Individual Asset Identifier, alphanumeric asset reference
+        Giai202 = 0x38,
+        /// Global Location Number with alphanumeric extension (up to 20 characters)
+        Sgln195 = 0x39,
+        /// Serialised GTIN without company prefix partition (TDS 2.0)
+        SgtinPlus = 0xF7,
+        /// Serialised GTIN with a prioritised date (TDS 2.0)
+        DsgtinPlus = 0xFB,
+    }
+}
+
+impl EpcScheme {
+    /// Name used in EPC tag URIs, e.g. `sgtin-96`
+    pub fn name(self) -> &'static str {
+        match self {
+            EpcScheme::Sgtin96 => "sgtin-96",
+            EpcScheme::Sscc96 => "sscc-96",
+            EpcScheme::Sgln96 => "sgln-96",
+            EpcScheme::Grai96 => "grai-96",
+            EpcScheme::Giai96 => "giai-96",
+            EpcScheme::Gid96 => "gid-96",
+            EpcScheme::Sgtin198 => "sgtin-198",
+            EpcScheme::Grai170 => "grai-170",
+            EpcScheme::Giai202 => "giai-202",
+            EpcScheme::Sgln195 => "sgln-195",
+            EpcScheme::SgtinPlus => "sgtin+",
+            EpcScheme::DsgtinPlus => "dsgtin+",
+        }
+    }
+
+    /// Finds a scheme by its tag URI name
+    pub fn from_name(name: &str) -> Option<Self> {
+        use EpcScheme::*;
+
+        [
+            Sgtin96, Sscc96, Sgln96, Grai96, Giai96, Gid96, Sgtin198, Grai170, Giai202, Sgln195,
+            SgtinPlus, DsgtinPlus,
+        ]
+        .into_iter()
+        .find(|scheme| scheme.name() == name)
+    }
+
+    /// Whether this is one of the TDS 2.0 `+` schemes, which
+    /// don't encode the length of the GS1 company prefix
+    pub fn is_plus(self) -> bool {
+        matches!(self, EpcScheme::SgtinPlus | EpcScheme::DsgtinPlus)
+    }
+}
+
+impl fmt::Display for EpcScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+enum_from_primitive! {
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    #[repr(u8)]
+    /// Kind of date encoded in a DSGTIN+
+    pub enum DateType {
+        /// Production date, AI (11)
+        Production = 0,
+        /// Packaging date, AI (13)
+        Packaging = 1,
+        /// Best before date, AI (15)
+        BestBefore = 2,
+        /// Sell by date, AI (16)
+        SellBy = 3,
+        /// Expiration date, AI (17)
+        Expiration = 4,
+        /// First freeze date, AI (7006)
+        FirstFreeze = 5,
+        /// Harvest date, AI (7007)
+        Harvest = 6,
+    }
+}
+
+impl DateType {
+    /// GS1 application identifier of the date
+    pub fn ai(self) -> &'static str {
+        match self {
+            DateType::Production => "11",
+            DateType::Packaging => "13",
+            DateType::BestBefore => "15",
+            DateType::SellBy => "16",
+            DateType::Expiration => "17",
+            DateType::FirstFreeze => "7006",
+            DateType::Harvest => "7007",
+        }
+    }
+}
+
+enum_from_primitive! {
+    #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+    #[repr(u8)]
+    /// Allocation class of a TID, identified by its first byte
+    pub enum AllocationClass {
+        /// ISO/IEC 7816-6 registered IC manufacturer, 64 bit unique ID
+        Iso7816 = 0xE0,
+        /// ISO/IEC 14816 (vehicle identification)
+        Iso14816 = 0xE1,
+        /// GS1 EPCglobal, mask designer ID and model number
+        Gs1 = 0xE2,
+        /// ISO/IEC 7816-6 registered IC manufacturer, 80 bit unique ID
+        Iso7816Extended = 0xE3,
+    }
+}
@@ -0,0 +1,260 @@
+use super::tds_bits::*;
+use super::tds_enums::*;
+use super::tds_structs::*;
+use crate::error::TdsError;
+use enum_primitive::FromPrimitive;
+
+/// Characters of the URI & file safe base 64 serial encoding
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Maximum length of a serial number, AI (21)
+const MAX_SERIAL_LEN: usize = 20;
+
+/// Encoding indicator of the variable length alphanumeric serial
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum SerialEncoding {
+    /// Digits only, stored as one integer
+    Numeric = 0,
+    /// `0-9A-F`, 4 bits per character
+    UpperHex = 1,
+    /// `0-9a-f`, 4 bits per character
+    LowerHex = 2,
+    /// URI & file safe base 64, 6 bits per character
+    Base64 = 3,
+    /// 7 bit ASCII
+    Ascii = 4,
+}
+
+impl SerialEncoding {
+    /// Number of bits used to store `len` characters
+    fn bits(self, len: usize) -> usize {
+        match self {
+            // Smallest number of bits holding every value with `len` digits
+            SerialEncoding::Numeric => (len as f64 * 10f64.log2()).ceil() as usize,
+            SerialEncoding::UpperHex | SerialEncoding::LowerHex => len * 4,
+            SerialEncoding::Base64 => len * 6,
+            SerialEncoding::Ascii => len * 7,
+        }
+    }
+
+    /// Whether `serial` can be stored with this encoding
+    fn fits(self, serial: &str) -> bool {
+        match self {
+            SerialEncoding::Numeric => is_digits(serial) && serial.len() <= 38,
+            SerialEncoding::UpperHex => serial
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'A'..=b'F').contains(&b)),
+            SerialEncoding::LowerHex => serial
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)),
+            SerialEncoding::Base64 => serial.bytes().all(|b| BASE64_CHARS.contains(&b)),
+            SerialEncoding::Ascii => serial.chars().all(is_string_char),
+        }
+    }
+}
+
+/// Decodes SGTIN+ and DSGTIN+ (header already read)
+pub(crate) fn decode(scheme: EpcScheme, reader: &mut BitReader) -> Result<Gs1Epc, TdsError> {
+    let has_aidc_data = reader.read(1)? == 1;
+    let filter = reader.read(3)? as u8;
+
+    let date = if scheme == EpcScheme::DsgtinPlus {
+        let date_type = reader.read(4)? as u8;
+        let date_type = DateType::from_u8(date_type).ok_or(TdsError::Field("date type"))?;
+
+        let year = reader.read(7)?;
+        let month = reader.read(4)?;
+        let day = reader.read(5)?;
+
+        if year > 99 || !(1..=12).contains(&month) {
+            return Err(TdsError::Field("date"));
+        }
+
+        Some(PrioritisedDate {
+            date_type,
+            date: format!("{:02}{:02}{:02}", year, month, day),
+        })
+    } else {
+        None
+    };
+
+    let mut gtin = String::new();
+    for _ in 0..14 {
+        let digit = reader.read(4)? as u8;
+
+        if digit > 9 {
+            return Err(TdsError::Field("gtin"));
+        }
+        gtin.push((b'0' + digit) as char);
+    }
+    check_key(&gtin, 14, "gtin")?;
+
+    let serial = decode_serial(reader)?;
+
+    Ok(Gs1Epc {
+        scheme,
+        filter,
+        identity: Gs1Identity::Sgtin(Sgtin {
+            gtin,
+            company_prefix_len: None,
+            serial,
+        }),
+        date,
+        has_aidc_data,
+    })
+}
+
+/// Encodes SGTIN+ and DSGTIN+ (header already written)
+pub(crate) fn encode(epc: &Gs1Epc, writer: &mut BitWriter) -> Result<(), TdsError> {
+    if epc.has_aidc_data {
+        return Err(TdsError::Unsupported("+AIDC data"));
+    }
+
+    let sgtin = match &epc.identity {
+        Gs1Identity::Sgtin(sgtin) => sgtin,
+        _ => return Err(TdsError::Field("identity")),
+    };
+
+    writer.write(0, 1);
+    writer.write(epc.filter as u128, 3);
+
+    match (epc.scheme, &epc.date) {
+        (EpcScheme::DsgtinPlus, Some(date)) => {
+            let bytes = date.date.as_bytes();
+
+            if date.date.len() != 6 || !is_digits(&date.date) {
+                return Err(TdsError::Field("date"));
+            }
+
+            let field = |i: usize| ((bytes[i] - b'0') * 10 + (bytes[i + 1] - b'0')) as u128;
+            let (year, month, day) = (field(0), field(2), field(4));
+
+            if !(1..=12).contains(&month) || day > 31 {
+                return Err(TdsError::Field("date"));
+            }
+
+            writer.write(date.date_type as u128, 4);
+            writer.write(year, 7);
+            writer.write(month, 4);
+            writer.write(day, 5);
+        }
+        (EpcScheme::SgtinPlus, None) => {}
+        _ => return Err(TdsError::Field("date")),
+    }
+
+    check_key(&sgtin.gtin, 14, "gtin")?;
+    for digit in sgtin.gtin.bytes() {
+        writer.write((digit - b'0') as u128, 4);
+    }
+
+    encode_serial(&sgtin.serial, writer)
+}
+
+/// Reads a variable length alphanumeric serial
+fn decode_serial(reader: &mut BitReader) -> Result<String, TdsError> {
+    let encoding = match reader.read(3)? {
+        0 => SerialEncoding::Numeric,
+        1 => SerialEncoding::UpperHex,
+        2 => SerialEncoding::LowerHex,
+        3 => SerialEncoding::Base64,
+        4 => SerialEncoding::Ascii,
+        _ => return Err(TdsError::Unsupported("serial encoding")),
+    };
+    let len = reader.read(5)? as usize;
+
+    if len > MAX_SERIAL_LEN {
+        return Err(TdsError::Field("serial"));
+    }
+
+    let bits = encoding.bits(len);
+
+    let serial = match encoding {
+        SerialEncoding::Numeric => format_digits(reader.read(bits)?, len, "serial")?,
+        SerialEncoding::UpperHex | SerialEncoding::LowerHex => {
+            let mut serial = String::new();
+
+            for _ in 0..len {
+                let digit = char::from_digit(reader.read(4)? as u32, 16).unwrap();
+
+                if encoding == SerialEncoding::UpperHex {
+                    serial.push(digit.to_ascii_uppercase());
+                } else {
+                    serial.push(digit);
+                }
+            }
+
+            serial
+        }
+        SerialEncoding::Base64 => {
+            let mut serial = String::new();
+
+            for _ in 0..len {
+                serial.push(BASE64_CHARS[reader.read(6)? as usize] as char);
+            }
+
+            serial
+        }
+        SerialEncoding::Ascii => {
+            let mut serial = String::new();
+
+            for _ in 0..len {
+                let c = reader.read(7)? as u8 as char;
+
+                if !is_string_char(c) {
+                    return Err(TdsError::Field("serial"));
+                }
+                serial.push(c);
+            }
+
+            serial
+        }
+    };
+
+    Ok(serial)
+}
+
+/// Writes a serial with the most compact encoding it fits
+fn encode_serial(serial: &str, writer: &mut BitWriter) -> Result<(), TdsError> {
+    if serial.is_empty() || serial.len() > MAX_SERIAL_LEN {
+        return Err(TdsError::Field("serial"));
+    }
+
+    let encoding = [
+        SerialEncoding::Numeric,
+        SerialEncoding::UpperHex,
+        SerialEncoding::LowerHex,
+        SerialEncoding::Base64,
+        SerialEncoding::Ascii,
+    ]
+    .into_iter()
+    .filter(|encoding| encoding.fits(serial))
+    .min_by_key(|encoding| encoding.bits(serial.len()))
+    .ok_or(TdsError::Field("serial"))?;
+
+    let len = serial.len();
+
+    writer.write(encoding as u128, 3);
+    writer.write(len as u128, 5);
+
+    match encoding {
+        SerialEncoding::Numeric => writer.write(serial.parse().unwrap(), encoding.bits(len)),
+        SerialEncoding::UpperHex | SerialEncoding::LowerHex => {
+            for c in serial.chars() {
+                writer.write(c.to_digit(16).unwrap() as u128, 4);
+            }
+        }
+        SerialEncoding::Base64 => {
+            for b in serial.bytes() {
+                let index = BASE64_CHARS.iter().position(|&c| c == b).unwrap();
+                writer.write(index as u128, 6);
+            }
+        }
+        SerialEncoding::Ascii => {
+            for c in serial.chars() {
+                writer.write(c as u128, 7);
+            }
+        }
+    }
+
+    Ok(())
+}
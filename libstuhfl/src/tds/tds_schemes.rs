@@ -0,0 +1,379 @@
+use super::tds_bits::*;
+use super::tds_enums::*;
+use super::tds_structs::*;
+use crate::error::TdsError;
+
+/// Company prefix bits & digits, then bits & digits (or characters) of
+/// the following reference, for each partition value
+type PartitionTable = [(usize, usize, usize, usize); 7];
+
+/// SGTIN: company prefix + indicator digit & item reference
+const SGTIN_PARTITIONS: PartitionTable = [
+    (40, 12, 4, 1),
+    (37, 11, 7, 2),
+    (34, 10, 10, 3),
+    (30, 9, 14, 4),
+    (27, 8, 17, 5),
+    (24, 7, 20, 6),
+    (20, 6, 24, 7),
+];
+
+/// SSCC: company prefix + extension digit & serial reference
+const SSCC_PARTITIONS: PartitionTable = [
+    (40, 12, 18, 5),
+    (37, 11, 21, 6),
+    (34, 10, 24, 7),
+    (30, 9, 28, 8),
+    (27, 8, 31, 9),
+    (24, 7, 34, 10),
+    (20, 6, 38, 11),
+];
+
+/// SGLN: company prefix + location reference
+const SGLN_PARTITIONS: PartitionTable = [
+    (40, 12, 1, 0),
+    (37, 11, 4, 1),
+    (34, 10, 7, 2),
+    (30, 9, 11, 3),
+    (27, 8, 14, 4),
+    (24, 7, 17, 5),
+    (20, 6, 21, 6),
+];
+
+/// GRAI: company prefix + asset type
+const GRAI_PARTITIONS: PartitionTable = [
+    (40, 12, 4, 0),
+    (37, 11, 7, 1),
+    (34, 10, 10, 2),
+    (30, 9, 14, 3),
+    (27, 8, 17, 4),
+    (24, 7, 20, 5),
+    (20, 6, 24, 6),
+];
+
+/// GIAI-96: company prefix + numeric asset reference (maximum digits)
+const GIAI96_PARTITIONS: PartitionTable = [
+    (40, 12, 42, 12),
+    (37, 11, 45, 13),
+    (34, 10, 48, 14),
+    (30, 9, 52, 15),
+    (27, 8, 55, 16),
+    (24, 7, 58, 17),
+    (20, 6, 62, 18),
+];
+
+/// GIAI-202: company prefix + alphanumeric asset reference (maximum characters)
+const GIAI202_PARTITIONS: PartitionTable = [
+    (40, 12, 148, 18),
+    (37, 11, 151, 19),
+    (34, 10, 154, 20),
+    (30, 9, 158, 21),
+    (27, 8, 161, 22),
+    (24, 7, 164, 23),
+    (20, 6, 168, 24),
+];
+
+/// Partition value for a company prefix length
+pub(crate) fn partition(company_prefix_len: usize) -> Option<u8> {
+    if (6..=12).contains(&company_prefix_len) {
+        Some((12 - company_prefix_len) as u8)
+    } else {
+        None
+    }
+}
+
+/// Reads the partition value and the company prefix. Returns the
+/// company prefix and the size of the following reference.
+fn read_partitioned(
+    reader: &mut BitReader,
+    table: &PartitionTable,
+) -> Result<(String, usize, usize), TdsError> {
+    let partition = reader.read(3)? as u8;
+    let (prefix_bits, prefix_digits, ref_bits, ref_len) = *table
+        .get(partition as usize)
+        .ok_or(TdsError::Partition(partition))?;
+
+    let company_prefix = format_digits(reader.read(prefix_bits)?, prefix_digits, "company prefix")?;
+
+    Ok((company_prefix, ref_bits, ref_len))
+}
+
+/// Writes the partition value and the company prefix. Returns the
+/// size of the following reference.
+fn write_partitioned(
+    writer: &mut BitWriter,
+    table: &PartitionTable,
+    company_prefix: &str,
+) -> Result<(usize, usize), TdsError> {
+    let partition = partition(company_prefix.len()).ok_or(TdsError::Field("company prefix"))?;
+    let (prefix_bits, prefix_digits, ref_bits, ref_len) = table[partition as usize];
+
+    writer.write(partition as u128, 3);
+    writer.write(
+        parse_digits(company_prefix, prefix_digits, "company prefix")?,
+        prefix_bits,
+    );
+
+    Ok((ref_bits, ref_len))
+}
+
+/// Makes sure a string fits into an alphanumeric field
+fn check_string(value: &str, max_len: usize, field: &'static str) -> Result<(), TdsError> {
+    if value.len() > max_len || !value.chars().all(is_string_char) {
+        Err(TdsError::Field(field))
+    } else {
+        Ok(())
+    }
+}
+
+/// Decodes the schemes with partition tables, and GID-96
+pub(crate) fn decode(scheme: EpcScheme, reader: &mut BitReader) -> Result<Gs1Epc, TdsError> {
+    if scheme == EpcScheme::Gid96 {
+        let gid = Gid {
+            manager: reader.read(28)? as u32,
+            class: reader.read(24)? as u32,
+            serial: reader.read_u64(36)?,
+        };
+
+        return Ok(Gs1Epc::new(scheme, 0, Gs1Identity::Gid(gid)));
+    }
+
+    let filter = reader.read(3)? as u8;
+
+    let identity = match scheme {
+        EpcScheme::Sgtin96 | EpcScheme::Sgtin198 => {
+            let (company_prefix, bits, len) = read_partitioned(reader, &SGTIN_PARTITIONS)?;
+            let item_reference = format_digits(reader.read(bits)?, len, "item reference")?;
+
+            let serial = if scheme == EpcScheme::Sgtin96 {
+                reader.read(38)?.to_string()
+            } else {
+                reader.read_string(140, "serial")?
+            };
+
+            let gtin = format!(
+                "{}{}{}",
+                &item_reference[..1],
+                company_prefix,
+                &item_reference[1..]
+            );
+
+            Gs1Identity::Sgtin(Sgtin {
+                gtin: format!("{}{}", gtin, check_digit(&gtin)),
+                company_prefix_len: Some(company_prefix.len()),
+                serial,
+            })
+        }
+        EpcScheme::Sscc96 => {
+            let (company_prefix, bits, len) = read_partitioned(reader, &SSCC_PARTITIONS)?;
+            let serial_reference = format_digits(reader.read(bits)?, len, "serial reference")?;
+
+            // Unallocated
+            reader.read(24)?;
+
+            let sscc = format!(
+                "{}{}{}",
+                &serial_reference[..1],
+                company_prefix,
+                &serial_reference[1..]
+            );
+
+            Gs1Identity::Sscc(Sscc {
+                sscc: format!("{}{}", sscc, check_digit(&sscc)),
+                company_prefix_len: company_prefix.len(),
+            })
+        }
+        EpcScheme::Sgln96 | EpcScheme::Sgln195 => {
+            let (company_prefix, bits, len) = read_partitioned(reader, &SGLN_PARTITIONS)?;
+            let location_reference = format_digits(reader.read(bits)?, len, "location reference")?;
+
+            let extension = if scheme == EpcScheme::Sgln96 {
+                reader.read(41)?.to_string()
+            } else {
+                reader.read_string(140, "extension")?
+            };
+
+            let gln = format!("{}{}", company_prefix, location_reference);
+
+            Gs1Identity::Sgln(Sgln {
+                gln: format!("{}{}", gln, check_digit(&gln)),
+                company_prefix_len: company_prefix.len(),
+                extension,
+            })
+        }
+        EpcScheme::Grai96 | EpcScheme::Grai170 => {
+            let (company_prefix, bits, len) = read_partitioned(reader, &GRAI_PARTITIONS)?;
+            let asset_type = format_digits(reader.read(bits)?, len, "asset type")?;
+
+            let serial = if scheme == EpcScheme::Grai96 {
+                reader.read(38)?.to_string()
+            } else {
+                reader.read_string(112, "serial")?
+            };
+
+            let grai = format!("0{}{}", company_prefix, asset_type);
+
+            Gs1Identity::Grai(Grai {
+                grai: format!("{}{}", grai, check_digit(&grai)),
+                company_prefix_len: company_prefix.len(),
+                serial,
+            })
+        }
+        EpcScheme::Giai96 | EpcScheme::Giai202 => {
+            let (company_prefix, bits, _) = if scheme == EpcScheme::Giai96 {
+                read_partitioned(reader, &GIAI96_PARTITIONS)?
+            } else {
+                read_partitioned(reader, &GIAI202_PARTITIONS)?
+            };
+
+            let asset_reference = if scheme == EpcScheme::Giai96 {
+                reader.read(bits)?.to_string()
+            } else {
+                reader.read_string(bits, "asset reference")?
+            };
+
+            Gs1Identity::Giai(Giai {
+                giai: format!("{}{}", company_prefix, asset_reference),
+                company_prefix_len: company_prefix.len(),
+            })
+        }
+        _ => return Err(TdsError::UnknownHeader(scheme as u8)),
+    };
+
+    Ok(Gs1Epc::new(scheme, filter, identity))
+}
+
+/// Encodes the schemes with partition tables, and GID-96. The header
+/// has already been written.
+pub(crate) fn encode(epc: &Gs1Epc, writer: &mut BitWriter) -> Result<(), TdsError> {
+    let scheme = epc.scheme;
+
+    if let (Gs1Identity::Gid(gid), EpcScheme::Gid96) = (&epc.identity, scheme) {
+        if gid.manager >> 28 != 0 || gid.class >> 24 != 0 || gid.serial >> 36 != 0 {
+            return Err(TdsError::Field("gid"));
+        }
+
+        writer.write(gid.manager as u128, 28);
+        writer.write(gid.class as u128, 24);
+        writer.write(gid.serial as u128, 36);
+
+        return Ok(());
+    }
+
+    writer.write(epc.filter as u128, 3);
+
+    match (&epc.identity, scheme) {
+        (Gs1Identity::Sgtin(sgtin), EpcScheme::Sgtin96 | EpcScheme::Sgtin198) => {
+            check_key(&sgtin.gtin, 14, "gtin")?;
+
+            let company_prefix = sgtin
+                .company_prefix()
+                .ok_or(TdsError::Field("company prefix"))?;
+            let item_reference = sgtin
+                .item_reference()
+                .ok_or(TdsError::Field("company prefix"))?;
+
+            let (bits, len) = write_partitioned(writer, &SGTIN_PARTITIONS, company_prefix)?;
+            writer.write(parse_digits(&item_reference, len, "item reference")?, bits);
+
+            if scheme == EpcScheme::Sgtin96 {
+                writer.write(parse_integer(&sgtin.serial, 38, "serial")?, 38);
+            } else {
+                check_string(&sgtin.serial, 20, "serial")?;
+                writer.write_string(&sgtin.serial, 140);
+            }
+        }
+        (Gs1Identity::Sscc(sscc), EpcScheme::Sscc96) => {
+            check_key(&sscc.sscc, 18, "sscc")?;
+
+            let company_prefix = sscc
+                .company_prefix()
+                .ok_or(TdsError::Field("company prefix"))?;
+            let serial_reference = sscc
+                .serial_reference()
+                .ok_or(TdsError::Field("company prefix"))?;
+
+            let (bits, len) = write_partitioned(writer, &SSCC_PARTITIONS, company_prefix)?;
+            writer.write(
+                parse_digits(&serial_reference, len, "serial reference")?,
+                bits,
+            );
+
+            // Unallocated
+            writer.write(0, 24);
+        }
+        (Gs1Identity::Sgln(sgln), EpcScheme::Sgln96 | EpcScheme::Sgln195) => {
+            check_key(&sgln.gln, 13, "gln")?;
+
+            let company_prefix = sgln
+                .company_prefix()
+                .ok_or(TdsError::Field("company prefix"))?;
+            let location_reference = sgln
+                .location_reference()
+                .ok_or(TdsError::Field("company prefix"))?;
+
+            let (bits, len) = write_partitioned(writer, &SGLN_PARTITIONS, company_prefix)?;
+            writer.write(
+                parse_digits(location_reference, len, "location reference")?,
+                bits,
+            );
+
+            if scheme == EpcScheme::Sgln96 {
+                writer.write(parse_integer(&sgln.extension, 41, "extension")?, 41);
+            } else {
+                check_string(&sgln.extension, 20, "extension")?;
+                writer.write_string(&sgln.extension, 140);
+            }
+        }
+        (Gs1Identity::Grai(grai), EpcScheme::Grai96 | EpcScheme::Grai170) => {
+            check_key(&grai.grai, 14, "grai")?;
+
+            if !grai.grai.starts_with('0') {
+                return Err(TdsError::Field("grai"));
+            }
+
+            let company_prefix = grai
+                .company_prefix()
+                .ok_or(TdsError::Field("company prefix"))?;
+            let asset_type = grai.asset_type().ok_or(TdsError::Field("company prefix"))?;
+
+            let (bits, len) = write_partitioned(writer, &GRAI_PARTITIONS, company_prefix)?;
+            writer.write(parse_digits(asset_type, len, "asset type")?, bits);
+
+            if scheme == EpcScheme::Grai96 {
+                writer.write(parse_integer(&grai.serial, 38, "serial")?, 38);
+            } else {
+                check_string(&grai.serial, 16, "serial")?;
+                writer.write_string(&grai.serial, 112);
+            }
+        }
+        (Gs1Identity::Giai(giai), EpcScheme::Giai96 | EpcScheme::Giai202) => {
+            let company_prefix = giai
+                .company_prefix()
+                .ok_or(TdsError::Field("company prefix"))?;
+            let asset_reference = giai
+                .asset_reference()
+                .ok_or(TdsError::Field("company prefix"))?;
+
+            if scheme == EpcScheme::Giai96 {
+                let (bits, len) = write_partitioned(writer, &GIAI96_PARTITIONS, company_prefix)?;
+
+                if asset_reference.len() > len {
+                    return Err(TdsError::Field("asset reference"));
+                }
+                writer.write(
+                    parse_integer(asset_reference, bits, "asset reference")?,
+                    bits,
+                );
+            } else {
+                let (bits, len) = write_partitioned(writer, &GIAI202_PARTITIONS, company_prefix)?;
+
+                check_string(asset_reference, len, "asset reference")?;
+                writer.write_string(asset_reference, bits);
+            }
+        }
+        _ => return Err(TdsError::Field("identity")),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,322 @@
+use super::tds_bits::{BitReader, BitWriter};
+use super::tds_enums::*;
+use super::{tds_plus, tds_schemes};
+use crate::data_types::Epc;
+use crate::error::TdsError;
+use enum_primitive::FromPrimitive;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Serialised Global Trade Item Number, AI (01) + (21)
+pub struct Sgtin {
+    /// GTIN-14 including the check digit
+    pub gtin: String,
+    /// Number of GS1 company prefix digits in the GTIN (6..=12).
+    /// Unknown for the `+` schemes.
+    pub company_prefix_len: Option<usize>,
+    /// Serial number
+    pub serial: String,
+}
+
+impl Sgtin {
+    /// GS1 company prefix, if its length is known
+    pub fn company_prefix(&self) -> Option<&str> {
+        let len = self.company_prefix_len?;
+        self.gtin.get(1..1 + len)
+    }
+
+    /// Item reference including the indicator digit, as used in EPC URIs
+    pub fn item_reference(&self) -> Option<String> {
+        let len = self.company_prefix_len?;
+        Some(format!(
+            "{}{}",
+            self.gtin.get(..1)?,
+            self.gtin.get(1 + len..13)?
+        ))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Serial Shipping Container Code, AI (00)
+pub struct Sscc {
+    /// SSCC-18 including the extension and check digit
+    pub sscc: String,
+    /// Number of GS1 company prefix digits in the SSCC (6..=12)
+    pub company_prefix_len: usize,
+}
+
+impl Sscc {
+    /// GS1 company prefix
+    pub fn company_prefix(&self) -> Option<&str> {
+        self.sscc.get(1..1 + self.company_prefix_len)
+    }
+
+    /// Serial reference including the extension digit, as used in EPC URIs
+    pub fn serial_reference(&self) -> Option<String> {
+        Some(format!(
+            "{}{}",
+            self.sscc.get(..1)?,
+            self.sscc.get(1 + self.company_prefix_len..17)?
+        ))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Global Location Number with extension, AI (414) + (254)
+pub struct Sgln {
+    /// GLN-13 including the check digit
+    pub gln: String,
+    /// Number of GS1 company prefix digits in the GLN (6..=12)
+    pub company_prefix_len: usize,
+    /// GLN extension, `"0"` if there is none
+    pub extension: String,
+}
+
+impl Sgln {
+    /// GS1 company prefix
+    pub fn company_prefix(&self) -> Option<&str> {
+        self.gln.get(..self.company_prefix_len)
+    }
+
+    /// Location reference, as used in EPC URIs
+    pub fn location_reference(&self) -> Option<&str> {
+        self.gln.get(self.company_prefix_len..12)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Global Returnable Asset Identifier, AI (8003)
+pub struct Grai {
+    /// Leading zero, company prefix, asset type and check digit (14 digits)
+    pub grai: String,
+    /// Number of GS1 company prefix digits in the GRAI (6..=12)
+    pub company_prefix_len: usize,
+    /// Serial number
+    pub serial: String,
+}
+
+impl Grai {
+    /// GS1 company prefix
+    pub fn company_prefix(&self) -> Option<&str> {
+        self.grai.get(1..1 + self.company_prefix_len)
+    }
+
+    /// Asset type, as used in EPC URIs
+    pub fn asset_type(&self) -> Option<&str> {
+        self.grai.get(1 + self.company_prefix_len..13)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Global Individual Asset Identifier, AI (8004)
+pub struct Giai {
+    /// Company prefix followed by the individual asset reference
+    pub giai: String,
+    /// Number of GS1 company prefix digits in the GIAI (6..=12)
+    pub company_prefix_len: usize,
+}
+
+impl Giai {
+    /// GS1 company prefix
+    pub fn company_prefix(&self) -> Option<&str> {
+        self.giai.get(..self.company_prefix_len)
+    }
+
+    /// Individual asset reference, as used in EPC URIs
+    pub fn asset_reference(&self) -> Option<&str> {
+        self.giai.get(self.company_prefix_len..)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// General Identifier (not a GS1 key)
+pub struct Gid {
+    /// General manager number (28 bits)
+    pub manager: u32,
+    /// Object class (24 bits)
+    pub class: u32,
+    /// Serial number (36 bits)
+    pub serial: u64,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Identity encoded in an EPC
+pub enum Gs1Identity {
+    /// SGTIN-96, SGTIN-198, SGTIN+ and DSGTIN+
+    Sgtin(Sgtin),
+    /// SSCC-96
+    Sscc(Sscc),
+    /// SGLN-96 and SGLN-195
+    Sgln(Sgln),
+    /// GRAI-96 and GRAI-170
+    Grai(Grai),
+    /// GIAI-96 and GIAI-202
+    Giai(Giai),
+    /// GID-96
+    Gid(Gid),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Date encoded in a DSGTIN+
+pub struct PrioritisedDate {
+    /// Kind of date
+    pub date_type: DateType,
+    /// Date as `YYMMDD`. A day of `00` means the end of the month.
+    pub date: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// An EPC decoded according to the GS1 Tag Data Standard.
+/// See the [module](super) docs for an example.
+pub struct Gs1Epc {
+    /// Binary encoding scheme
+    pub scheme: EpcScheme,
+    /// Filter value (0..=7), always 0 for GID-96
+    pub filter: u8,
+    /// Encoded identity
+    pub identity: Gs1Identity,
+    /// Prioritised date, only used by DSGTIN+
+    pub date: Option<PrioritisedDate>,
+    /// Whether +AIDC data follows the identity (`+` schemes only).
+    /// This data is not decoded.
+    pub has_aidc_data: bool,
+}
+
+impl Gs1Epc {
+    /// Creates an EPC for encoding. Use [`Gs1Epc::encode()`] to check
+    /// whether the identity fits the scheme.
+    pub fn new(scheme: EpcScheme, filter: u8, identity: Gs1Identity) -> Self {
+        Self {
+            scheme,
+            filter,
+            identity,
+            date: None,
+            has_aidc_data: false,
+        }
+    }
+
+    /// Decodes the contents of the EPC memory bank (without PC word)
+    pub fn decode(epc: &[u8]) -> Result<Self, TdsError> {
+        let mut reader = BitReader::new(epc);
+
+        let header = reader.read(8)? as u8;
+        let scheme = EpcScheme::from_u8(header).ok_or(TdsError::UnknownHeader(header))?;
+
+        if scheme.is_plus() {
+            tds_plus::decode(scheme, &mut reader)
+        } else {
+            tds_schemes::decode(scheme, &mut reader)
+        }
+    }
+
+    /// Encodes the EPC into bytes, padded to whole words
+    pub fn encode(&self) -> Result<Vec<u8>, TdsError> {
+        let mut writer = BitWriter::new();
+
+        if self.filter > 7 {
+            return Err(TdsError::Field("filter"));
+        }
+
+        writer.write(self.scheme as u128, 8);
+
+        if self.scheme.is_plus() {
+            tds_plus::encode(self, &mut writer)?;
+        } else {
+            tds_schemes::encode(self, &mut writer)?;
+        }
+
+        Ok(writer.finish())
+    }
+
+    /// Encodes the EPC, ready to be written to a tag
+    pub fn to_epc(&self) -> Result<Epc, TdsError> {
+        Ok(Epc::from_id(self.encode()?))
+    }
+
+    /// Partition value of the company prefix length. Not used by GID-96
+    /// and the `+` schemes.
+    pub fn partition(&self) -> Option<u8> {
+        if self.scheme.is_plus() {
+            return None;
+        }
+
+        let company_prefix_len = match &self.identity {
+            Gs1Identity::Sgtin(sgtin) => sgtin.company_prefix_len?,
+            Gs1Identity::Sscc(sscc) => sscc.company_prefix_len,
+            Gs1Identity::Sgln(sgln) => sgln.company_prefix_len,
+            Gs1Identity::Grai(grai) => grai.company_prefix_len,
+            Gs1Identity::Giai(giai) => giai.company_prefix_len,
+            Gs1Identity::Gid(_) => return None,
+        };
+
+        tds_schemes::partition(company_prefix_len)
+    }
+}
+
+impl TryFrom<&Epc> for Gs1Epc {
+    type Error = TdsError;
+
+    fn try_from(epc: &Epc) -> Result<Self, Self::Error> {
+        Self::decode(epc.get_id())
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// Header of an extended TID (XTID), telling which segments follow it
+pub struct XtidHeader {
+    /// Another header word follows (not decoded)
+    pub extended_header: bool,
+    /// Lock bit segment present
+    pub lock_bit_segment: bool,
+    /// User memory and BlockPermalock segment present
+    pub user_memory_segment: bool,
+    /// BlockWrite and BlockErase segment present
+    pub block_write_segment: bool,
+    /// Optional command support segment present
+    pub optional_command_segment: bool,
+    /// Length of the serial number segment in bits, 0 if there is none
+    pub serial_bits: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A decoded TID. See [`TidInfo::decode()`] for an example.
+pub struct TidInfo {
+    /// Allocation class (first byte)
+    pub allocation_class: AllocationClass,
+    /// XTID indicator: an XTID header follows the model number
+    pub xtid: bool,
+    /// Security indicator: the tag supports Authenticate and/or Challenge
+    pub security: bool,
+    /// File indicator: the tag supports the FileOpen command
+    pub file: bool,
+    /// 12 bit mask designer ID as read from the TID, including the
+    /// indicator bits in its upper three bits. For the ISO classes this
+    /// is the 8 bit IC manufacturer code.
+    pub mask_designer_id: u16,
+    /// 12 bit tag model number (GS1 class only)
+    pub model_number: u16,
+    /// XTID header, if the XTID indicator is set and the TID is long enough
+    pub xtid_header: Option<XtidHeader>,
+    /// Serial number from the XTID serial segment, or the unique ID of
+    /// the ISO classes
+    pub serial: Option<Vec<u8>>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// Chip model and capabilities from the built-in registry,
+/// see [`ChipInfo::lookup()`].
+pub struct ChipInfo {
+    /// Mask designer ID, without indicator bits (9 bits)
+    pub mask_designer_id: u16,
+    /// Tag model number
+    pub model_number: u16,
+    /// Chip manufacturer
+    pub manufacturer: &'static str,
+    /// Chip model
+    pub model: &'static str,
+    /// Maximum EPC length in bits
+    pub epc_bits: u16,
+    /// User memory size in bits
+    pub user_memory_bits: u16,
+    /// Whether writes of more than one word with BlockWrite are supported
+    pub block_write: bool,
+}
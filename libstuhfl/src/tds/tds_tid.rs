@@ -0,0 +1,137 @@
+use super::tds_bits::BitReader;
+use super::tds_enums::*;
+use super::tds_structs::*;
+use crate::data_types::Tid;
+use crate::error::TdsError;
+use enum_primitive::FromPrimitive;
+
+/// XTID indicator bit of the 12 bit mask designer ID
+const XTID_INDICATOR: u16 = 0x800;
+
+/// Security indicator bit of the 12 bit mask designer ID
+const SECURITY_INDICATOR: u16 = 0x400;
+
+/// File indicator bit of the 12 bit mask designer ID
+const FILE_INDICATOR: u16 = 0x200;
+
+/// Mask designer ID without the indicator bits
+const MDID_MASK: u16 = 0x1FF;
+
+impl TidInfo {
+    /// Decodes the contents of the TID memory bank
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use libstuhfl::tds::*;
+    /// # fn main() -> core::result::Result<(), Box<dyn std::error::Error>> {
+    ///
+    /// let tid = TidInfo::decode(&[0xE2, 0x80, 0x11, 0x05, 0x20, 0x00])?;
+    ///
+    /// assert_eq!(tid.allocation_class, AllocationClass::Gs1);
+    /// assert_eq!(tid.manufacturer(), Some("Impinj"));
+    ///
+    /// let chip = tid.chip().unwrap();
+    /// assert_eq!(chip.model, "Monza 4QT");
+    /// assert_eq!(chip.user_memory_bits, 512);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TdsError::Field`] for unknown allocation classes, and
+    /// [`TdsError::Length`] if a GS1 TID is shorter than 32 bits.
+    pub fn decode(tid: &[u8]) -> Result<Self, TdsError> {
+        let mut reader = BitReader::new(tid);
+
+        let class = reader.read(8)? as u8;
+        let allocation_class =
+            AllocationClass::from_u8(class).ok_or(TdsError::Field("allocation class"))?;
+
+        if allocation_class != AllocationClass::Gs1 {
+            let manufacturer = tid.get(1).copied().unwrap_or(0);
+            let serial = tid.get(2..).filter(|serial| !serial.is_empty());
+
+            return Ok(Self {
+                allocation_class,
+                xtid: false,
+                security: false,
+                file: false,
+                mask_designer_id: manufacturer as u16,
+                model_number: 0,
+                xtid_header: None,
+                serial: serial.map(|serial| serial.to_vec()),
+            });
+        }
+
+        let mask_designer_id = reader.read(12)? as u16;
+        let model_number = reader.read(12)? as u16;
+        let xtid = mask_designer_id & XTID_INDICATOR != 0;
+
+        // Readers often only fetch the first two words of the TID
+        let xtid_header = if xtid && reader.remaining() >= 16 {
+            let header = reader.read(16)? as u16;
+            let serialization = (header & 0x7) as usize;
+
+            Some(XtidHeader {
+                extended_header: header & 0x8000 != 0,
+                lock_bit_segment: header & 0x0040 != 0,
+                user_memory_segment: header & 0x0020 != 0,
+                block_write_segment: header & 0x0010 != 0,
+                optional_command_segment: header & 0x0008 != 0,
+                serial_bits: if serialization == 0 {
+                    0
+                } else {
+                    48 + 16 * (serialization - 1)
+                },
+            })
+        } else {
+            None
+        };
+
+        let serial = match xtid_header {
+            Some(header) if header.serial_bits > 0 && reader.remaining() >= header.serial_bits => {
+                let start = 6;
+                Some(tid[start..start + header.serial_bits / 8].to_vec())
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            allocation_class,
+            xtid,
+            security: mask_designer_id & SECURITY_INDICATOR != 0,
+            file: mask_designer_id & FILE_INDICATOR != 0,
+            mask_designer_id,
+            model_number,
+            xtid_header,
+            serial,
+        })
+    }
+
+    /// Mask designer ID without the indicator bits, as used in the
+    /// GS1 mask designer registry (GS1 class only)
+    pub fn designer(&self) -> Option<u16> {
+        (self.allocation_class == AllocationClass::Gs1).then_some(self.mask_designer_id & MDID_MASK)
+    }
+
+    /// Name of the mask designer, if it is known (GS1 class only)
+    pub fn manufacturer(&self) -> Option<&'static str> {
+        ChipInfo::manufacturer(self.designer()?)
+    }
+
+    /// Chip model and capabilities, if the chip is in the registry
+    pub fn chip(&self) -> Option<&'static ChipInfo> {
+        ChipInfo::lookup(self.designer()?, self.model_number)
+    }
+}
+
+impl TryFrom<&Tid> for TidInfo {
+    type Error = TdsError;
+
+    fn try_from(tid: &Tid) -> Result<Self, Self::Error> {
+        Self::decode(tid.get_id())
+    }
+}
@@ -0,0 +1,422 @@
+use super::tds_bits::*;
+use super::tds_enums::*;
+use super::tds_structs::*;
+use crate::error::TdsError;
+use enum_primitive::FromPrimitive;
+
+/// Prefix of EPC pure identity URIs
+const ID_PREFIX: &str = "urn:epc:id:";
+
+/// Prefix of EPC tag URIs
+const TAG_PREFIX: &str = "urn:epc:tag:";
+
+/// GS1 Digital Link resolver used as pure identity of the `+` schemes
+const DIGITAL_LINK_PREFIX: &str = "https://id.gs1.org/01/";
+
+impl Gs1Epc {
+    /// Pure identity URI, e.g. `urn:epc:id:sgtin:0614141.812345.6789`.
+    ///
+    /// The `+` schemes don't know the length of the company prefix, so
+    /// their identity is given as a GS1 Digital Link URI instead, e.g.
+    /// `https://id.gs1.org/01/80614141123458/21/6789`.
+    pub fn pure_identity_uri(&self) -> Result<String, TdsError> {
+        if self.scheme.is_plus() {
+            return self.digital_link();
+        }
+
+        Ok(format!(
+            "{}{}:{}",
+            ID_PREFIX,
+            identity_kind(&self.identity),
+            self.identity_fields()?
+        ))
+    }
+
+    /// Tag URI, which also contains the scheme and filter value, e.g.
+    /// `urn:epc:tag:sgtin-96:3.0614141.812345.6789`
+    pub fn tag_uri(&self) -> Result<String, TdsError> {
+        let fields = match (&self.identity, self.scheme) {
+            (Gs1Identity::Gid(_), _) => self.identity_fields()?,
+            (Gs1Identity::Sgtin(sgtin), EpcScheme::SgtinPlus | EpcScheme::DsgtinPlus) => {
+                let date = match &self.date {
+                    Some(date) => format!("{}.{}.", date.date_type as u8, date.date),
+                    None => String::new(),
+                };
+
+                format!(
+                    "{}.{}{}.{}",
+                    self.filter,
+                    date,
+                    sgtin.gtin,
+                    uri_escape(&sgtin.serial)
+                )
+            }
+            _ => format!("{}.{}", self.filter, self.identity_fields()?),
+        };
+
+        Ok(format!("{}{}:{}", TAG_PREFIX, self.scheme.name(), fields))
+    }
+
+    /// Parses a tag URI as returned by [`Gs1Epc::tag_uri()`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TdsError::Uri`] if the URI is malformed, or the error of
+    /// [`Gs1Epc::encode()`] if the identity doesn't fit the scheme.
+    pub fn from_tag_uri(uri: &str) -> Result<Self, TdsError> {
+        let invalid = || TdsError::Uri(uri.to_owned());
+
+        let (name, fields) = uri
+            .strip_prefix(TAG_PREFIX)
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or_else(invalid)?;
+        let scheme = EpcScheme::from_name(name).ok_or_else(invalid)?;
+
+        let epc = if scheme == EpcScheme::Gid96 {
+            Gs1Epc::new(
+                scheme,
+                0,
+                parse_identity("gid", fields).ok_or_else(invalid)?,
+            )
+        } else {
+            let (filter, fields) = fields.split_once('.').ok_or_else(invalid)?;
+            let filter = filter.parse().map_err(|_| invalid())?;
+
+            if scheme.is_plus() {
+                parse_plus(scheme, filter, fields).ok_or_else(invalid)?
+            } else {
+                let kind = scheme.name().split('-').next().unwrap();
+                Gs1Epc::new(
+                    scheme,
+                    filter,
+                    parse_identity(kind, fields).ok_or_else(invalid)?,
+                )
+            }
+        };
+
+        epc.encode()?;
+
+        Ok(epc)
+    }
+
+    /// Parses a pure identity URI as returned by [`Gs1Epc::pure_identity_uri()`].
+    /// As the URI doesn't contain these, the binary `scheme` and `filter` value
+    /// to encode the identity with must be given.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TdsError::Uri`] if the URI is malformed or doesn't match
+    /// `scheme`, or the error of [`Gs1Epc::encode()`] if the identity doesn't
+    /// fit the scheme.
+    pub fn from_pure_identity_uri(
+        uri: &str,
+        scheme: EpcScheme,
+        filter: u8,
+    ) -> Result<Self, TdsError> {
+        let invalid = || TdsError::Uri(uri.to_owned());
+
+        let epc = if scheme.is_plus() {
+            parse_digital_link(scheme, filter, uri).ok_or_else(invalid)?
+        } else {
+            let (kind, fields) = uri
+                .strip_prefix(ID_PREFIX)
+                .and_then(|rest| rest.split_once(':'))
+                .ok_or_else(invalid)?;
+
+            if !scheme.name().starts_with(kind) {
+                return Err(invalid());
+            }
+
+            let filter = if scheme == EpcScheme::Gid96 {
+                0
+            } else {
+                filter
+            };
+
+            Gs1Epc::new(
+                scheme,
+                filter,
+                parse_identity(kind, fields).ok_or_else(invalid)?,
+            )
+        };
+
+        epc.encode()?;
+
+        Ok(epc)
+    }
+
+    /// Dot separated identity fields shared by pure identity and tag URIs
+    fn identity_fields(&self) -> Result<String, TdsError> {
+        let missing = TdsError::Field("company prefix");
+
+        Ok(match &self.identity {
+            Gs1Identity::Sgtin(sgtin) => format!(
+                "{}.{}.{}",
+                sgtin.company_prefix().ok_or(missing.clone())?,
+                sgtin.item_reference().ok_or(missing)?,
+                uri_escape(&sgtin.serial)
+            ),
+            Gs1Identity::Sscc(sscc) => format!(
+                "{}.{}",
+                sscc.company_prefix().ok_or(missing.clone())?,
+                sscc.serial_reference().ok_or(missing)?
+            ),
+            Gs1Identity::Sgln(sgln) => format!(
+                "{}.{}.{}",
+                sgln.company_prefix().ok_or(missing.clone())?,
+                sgln.location_reference().ok_or(missing)?,
+                uri_escape(&sgln.extension)
+            ),
+            Gs1Identity::Grai(grai) => format!(
+                "{}.{}.{}",
+                grai.company_prefix().ok_or(missing.clone())?,
+                grai.asset_type().ok_or(missing)?,
+                uri_escape(&grai.serial)
+            ),
+            Gs1Identity::Giai(giai) => format!(
+                "{}.{}",
+                giai.company_prefix().ok_or(missing.clone())?,
+                uri_escape(giai.asset_reference().ok_or(missing)?)
+            ),
+            Gs1Identity::Gid(gid) => format!("{}.{}.{}", gid.manager, gid.class, gid.serial),
+        })
+    }
+
+    /// GS1 Digital Link URI of an SGTIN, with the date as query parameter
+    fn digital_link(&self) -> Result<String, TdsError> {
+        let sgtin = match &self.identity {
+            Gs1Identity::Sgtin(sgtin) => sgtin,
+            _ => return Err(TdsError::Field("identity")),
+        };
+
+        let mut uri = format!(
+            "{}{}/21/{}",
+            DIGITAL_LINK_PREFIX,
+            sgtin.gtin,
+            percent_encode(&sgtin.serial)
+        );
+
+        if let Some(date) = &self.date {
+            uri += &format!("?{}={}", date.date_type.ai(), date.date);
+        }
+
+        Ok(uri)
+    }
+}
+
+/// Name of an identity in pure identity URIs
+fn identity_kind(identity: &Gs1Identity) -> &'static str {
+    match identity {
+        Gs1Identity::Sgtin(_) => "sgtin",
+        Gs1Identity::Sscc(_) => "sscc",
+        Gs1Identity::Sgln(_) => "sgln",
+        Gs1Identity::Grai(_) => "grai",
+        Gs1Identity::Giai(_) => "giai",
+        Gs1Identity::Gid(_) => "gid",
+    }
+}
+
+/// Splits company prefix and reference off the fields, checking
+/// that they have `len` digits together
+fn split_key(fields: &str, len: usize) -> Option<(&str, &str, Option<&str>)> {
+    let mut parts = fields.splitn(3, '.');
+    let company_prefix = parts.next()?;
+    let reference = parts.next()?;
+
+    let valid = (6..=12).contains(&company_prefix.len())
+        && company_prefix.len() + reference.len() == len
+        && is_digits(company_prefix)
+        && is_digits(reference);
+
+    valid.then_some((company_prefix, reference, parts.next()))
+}
+
+/// Parses the identity fields of a pure identity or (non `+`) tag URI
+fn parse_identity(kind: &str, fields: &str) -> Option<Gs1Identity> {
+    let with_check = |key: String| {
+        let check = check_digit(&key);
+        format!("{}{}", key, check)
+    };
+
+    Some(match kind {
+        "sgtin" => {
+            let (company_prefix, reference, serial) = split_key(fields, 13)?;
+
+            Gs1Identity::Sgtin(Sgtin {
+                gtin: with_check(format!(
+                    "{}{}{}",
+                    &reference[..1],
+                    company_prefix,
+                    &reference[1..]
+                )),
+                company_prefix_len: Some(company_prefix.len()),
+                serial: uri_unescape(serial?, "serial").ok()?,
+            })
+        }
+        "sscc" => {
+            let (company_prefix, reference, rest) = split_key(fields, 17)?;
+
+            if rest.is_some() {
+                return None;
+            }
+
+            Gs1Identity::Sscc(Sscc {
+                sscc: with_check(format!(
+                    "{}{}{}",
+                    &reference[..1],
+                    company_prefix,
+                    &reference[1..]
+                )),
+                company_prefix_len: company_prefix.len(),
+            })
+        }
+        "sgln" => {
+            let (company_prefix, reference, extension) = split_key(fields, 12)?;
+
+            Gs1Identity::Sgln(Sgln {
+                gln: with_check(format!("{}{}", company_prefix, reference)),
+                company_prefix_len: company_prefix.len(),
+                extension: uri_unescape(extension?, "extension").ok()?,
+            })
+        }
+        "grai" => {
+            let (company_prefix, reference, serial) = split_key(fields, 12)?;
+
+            Gs1Identity::Grai(Grai {
+                grai: with_check(format!("0{}{}", company_prefix, reference)),
+                company_prefix_len: company_prefix.len(),
+                serial: uri_unescape(serial?, "serial").ok()?,
+            })
+        }
+        "giai" => {
+            let (company_prefix, reference) = fields.split_once('.')?;
+
+            if !(6..=12).contains(&company_prefix.len()) || !is_digits(company_prefix) {
+                return None;
+            }
+
+            Gs1Identity::Giai(Giai {
+                giai: format!(
+                    "{}{}",
+                    company_prefix,
+                    uri_unescape(reference, "asset reference").ok()?
+                ),
+                company_prefix_len: company_prefix.len(),
+            })
+        }
+        "gid" => {
+            let mut parts = fields.split('.');
+
+            let gid = Gid {
+                manager: parts.next()?.parse().ok()?,
+                class: parts.next()?.parse().ok()?,
+                serial: parts.next()?.parse().ok()?,
+            };
+
+            if parts.next().is_some() {
+                return None;
+            }
+
+            Gs1Identity::Gid(gid)
+        }
+        _ => return None,
+    })
+}
+
+/// Parses the fields of an SGTIN+ or DSGTIN+ tag URI (after the filter)
+fn parse_plus(scheme: EpcScheme, filter: u8, fields: &str) -> Option<Gs1Epc> {
+    let (date, fields) = if scheme == EpcScheme::DsgtinPlus {
+        let mut parts = fields.splitn(3, '.');
+        let date_type = DateType::from_u8(parts.next()?.parse().ok()?)?;
+        let date = parts.next()?.to_owned();
+
+        (Some(PrioritisedDate { date_type, date }), parts.next()?)
+    } else {
+        (None, fields)
+    };
+
+    let (gtin, serial) = fields.split_once('.')?;
+
+    Some(Gs1Epc {
+        date,
+        ..Gs1Epc::new(
+            scheme,
+            filter,
+            Gs1Identity::Sgtin(Sgtin {
+                gtin: gtin.to_owned(),
+                company_prefix_len: None,
+                serial: uri_unescape(serial, "serial").ok()?,
+            }),
+        )
+    })
+}
+
+/// Parses a GS1 Digital Link URI as returned by [`Gs1Epc::pure_identity_uri()`]
+fn parse_digital_link(scheme: EpcScheme, filter: u8, uri: &str) -> Option<Gs1Epc> {
+    let (path, query) = match uri.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (uri, None),
+    };
+
+    let (gtin, serial) = path.strip_prefix(DIGITAL_LINK_PREFIX)?.split_once("/21/")?;
+
+    let date = match (scheme, query) {
+        (EpcScheme::DsgtinPlus, Some(query)) => {
+            let (ai, date) = query.split_once('=')?;
+            let date_type = (0..=6)
+                .filter_map(DateType::from_u8)
+                .find(|date_type| date_type.ai() == ai)?;
+
+            Some(PrioritisedDate {
+                date_type,
+                date: date.to_owned(),
+            })
+        }
+        (EpcScheme::SgtinPlus, None) => None,
+        _ => return None,
+    };
+
+    Some(Gs1Epc {
+        date,
+        ..Gs1Epc::new(
+            scheme,
+            filter,
+            Gs1Identity::Sgtin(Sgtin {
+                gtin: gtin.to_owned(),
+                company_prefix_len: None,
+                serial: percent_decode(serial)?,
+            }),
+        )
+    })
+}
+
+/// Percent-encodes a Digital Link path segment
+fn percent_encode(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "-._".contains(c) {
+                c.to_string()
+            } else {
+                format!("%{:02X}", c as u8)
+            }
+        })
+        .collect()
+}
+
+/// Reverts [`percent_encode()`]
+fn percent_decode(value: &str) -> Option<String> {
+    let mut decoded = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            let hex: String = chars.by_ref().take(2).collect();
+            decoded.push(u8::from_str_radix(&hex, 16).ok()? as char);
+        } else {
+            decoded.push(c);
+        }
+    }
+
+    Some(decoded)
+}
@@ -67,6 +67,793 @@ fn cfg_builder() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn adaptive_power_validation() {
+    // Initial level above the Tx ceiling
+    assert!(AutoTxStrength::builder()
+        .start_tx(-2)
+        .min_tx(-6)
+        .build()
+        .is_err());
+    assert!(AutoTxStrength::builder().max_tx(-20).build().is_err());
+
+    assert!(AutoRxSensitivity::builder().start_rx(20).build().is_err());
+    assert!(AutoRxSensitivity::builder()
+        .inc_threshold([0; 20])
+        .build()
+        .is_err());
+
+    // Defaults match the firmware defaults
+    let rx = AutoRxSensitivity::builder().build().unwrap();
+    let rx_ffi = rx.as_ffi();
+    let rx_default = ffi::STUHFL_T_ST25RU3993_AdaptiveSensitivity::default();
+    assert_eq!(rx_ffi.startRx, rx_default.startRx);
+    assert_eq!(rx_ffi.decThreshold, rx_default.decThreshold);
+    assert!(AutoRxSensitivity::from(rx_ffi) == rx);
+}
+
+#[test]
+fn adaptive_q_presets() -> TestResult {
+    use crate::gen2::*;
+
+    for preset in [
+        Gen2AdaptiveQPreset::Default,
+        Gen2AdaptiveQPreset::DensePopulation,
+        Gen2AdaptiveQPreset::SparsePopulation,
+    ] {
+        let adaptive_q = Gen2AdaptiveQ::Enable(Gen2AdaptiveQCfg::builder().preset(preset).build()?);
+
+        // Converting to the FFI struct and back keeps every setting
+        let ffi = adaptive_q.as_ffi();
+        let roundtrip = Gen2AdaptiveQ::from(ffi).as_ffi();
+        assert_eq!(ffi.C1, roundtrip.C1);
+        assert_eq!(ffi.C2, roundtrip.C2);
+        assert_eq!(ffi.startQ, roundtrip.startQ);
+    }
+
+    // Zero weights are rejected
+    assert!(Gen2AdaptiveQCfg::builder().c1([0; 16]).build().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn protocol_cfg_filters() {
+    use crate::gen2::*;
+
+    let filters = Gen2FilterSet {
+        rx_filter: 0x24,
+        high_pass: 8,
+        low_pass: 16,
+    };
+
+    // Calibration values are limited to 4 bits
+    assert!(Gen2ProtocolCfg::builder().filters(filters).build().is_err());
+
+    let filters = Gen2FilterSet {
+        low_pass: 15,
+        ..filters
+    };
+    assert!(Gen2ProtocolCfg::builder().filters(filters).build().is_ok());
+}
+
+#[test]
+fn timings_validation() {
+    use crate::gen2::*;
+
+    // 2 * RTcal at 12.5 us Tari, 10 * Tpri at 40 kHz BLF
+    assert_eq!(
+        Gen2Timings::min_t4(Gen2Tari::Twelve, Gen2Blf::SixHundredForty),
+        75
+    );
+    assert_eq!(Gen2Timings::min_t4(Gen2Tari::Six, Gen2Blf::Forty), 250);
+
+    let short = Gen2Timings::builder().t4_min(30).build().unwrap();
+    let long = Gen2Timings::builder().t4_min(500).build().unwrap();
+
+    assert!(Gen2Cfg::builder().timings(short).build().is_err());
+    assert!(Gen2Cfg::builder().timings(long).build().is_ok());
+}
+
+#[test]
+fn cfg_diff() -> TestResult {
+    use crate::gen2::*;
+
+    let requested = Gen2Cfg::builder().build()?;
+
+    // Nothing differs from itself
+    assert!(requested.diff(&requested).is_empty());
+
+    let tx_rx_cfg = TxRxCfg::builder().tx_output_level(-8).build()?;
+    let accepted = Gen2Cfg::builder().tx_rx_cfg(tx_rx_cfg).build()?;
+
+    let diff = requested.diff(&accepted);
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff[0].setting, "tx_rx_cfg");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "use-serde")]
+fn cfg_toml() -> TestResult {
+    use crate::gen2::*;
+
+    let filters = Gen2FilterSet {
+        rx_filter: 0x24,
+        high_pass: 8,
+        low_pass: 8,
+    };
+    let cfg = Gen2Cfg::builder()
+        .proto_cfg(Gen2ProtocolCfg::builder().filters(filters).build()?)
+        .lbt(Lbt::Enable(LbtCfg::builder().build()?))
+        .build()?;
+
+    let toml = cfg.to_toml_string()?;
+    assert!(toml.contains("tari = \"6.25us\""));
+    assert!(toml.contains("blf = \"320kHz\""));
+    assert_eq!(toml::from_str::<Gen2Cfg>(&toml)?, cfg);
+
+    // Missing settings keep their builder defaults
+    let partial: Gen2Cfg = toml::from_str(
+        "[tx_rx_cfg]\n\
+         tx_output_level = -6\n\
+         [proto_cfg]\n\
+         tari = \"25us\"\n\
+         blf = \"160kHz\"\n\
+         coding = \"Miller4\"\n",
+    )?;
+    let expected = Gen2Cfg::builder()
+        .tx_rx_cfg(TxRxCfg::builder().tx_output_level(-6).build()?)
+        .proto_cfg(
+            Gen2ProtocolCfg::builder()
+                .tari(Gen2Tari::TwentyFive)
+                .blf(Gen2Blf::OneHundredSixty)
+                .coding(Gen2Coding::Miller4)
+                .build()?,
+        )
+        .build()?;
+    assert_eq!(partial, expected);
+
+    // Builder validation also applies to files
+    assert!(toml::from_str::<Gen2Cfg>("[tx_rx_cfg]\ntx_output_level = 3\n").is_err());
+    assert!(toml::from_str::<Gen2Cfg>("channel_list = []\n").is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "firmware")]
+mod firmware {
+    use super::TestResult;
+    use crate::firmware::*;
+    use std::collections::VecDeque;
+    use std::io::{self, Read, Write};
+
+    /// Vector table of a minimal image: stack at the end of SRAM1,
+    /// reset handler right behind the table
+    fn image_bytes(len: usize) -> Vec<u8> {
+        let mut data = vec![0u8; len];
+        data[..4].copy_from_slice(&0x2001_8000u32.to_le_bytes());
+        data[4..8].copy_from_slice(&0x0800_0009u32.to_le_bytes());
+        data
+    }
+
+    /// Scripted bootloader: replies are played back, commands recorded
+    struct FakeBootloader {
+        replies: VecDeque<u8>,
+        sent: Vec<u8>,
+    }
+
+    impl Read for FakeBootloader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.replies.pop_front() {
+                Some(b) => {
+                    buf[0] = b;
+                    Ok(1)
+                }
+                None => Err(io::ErrorKind::TimedOut.into()),
+            }
+        }
+    }
+
+    impl Write for FakeBootloader {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.sent.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn image_validation() -> TestResult {
+        // Padded to the next double word with erased flash
+        let image = FirmwareImage::from_bytes(image_bytes(13))?;
+        assert_eq!(image.size(), 16);
+        assert_eq!(&image.data()[13..], &[0xFF; 3]);
+        assert_eq!(image.reset_handler(), 0x0800_0009);
+
+        // Stack pointer outside SRAM
+        let mut bad_sp = image_bytes(16);
+        bad_sp[3] = 0x08;
+        assert!(FirmwareImage::from_bytes(bad_sp).is_err());
+
+        // Reset handler without thumb bit, or past the end of the image
+        let mut bad_reset = image_bytes(16);
+        bad_reset[4] = 0x08;
+        assert!(FirmwareImage::from_bytes(bad_reset).is_err());
+        assert!(FirmwareImage::from_bytes(image_bytes(8)).is_err());
+
+        assert!(FirmwareImage::from_bytes(b":10000000".to_vec()).is_err());
+        assert!(FirmwareImage::from_bytes(image_bytes(FirmwareImage::FLASH_SIZE + 8)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn bootloader_flash() -> TestResult {
+        const ACK: u8 = 0x79;
+
+        let image = FirmwareImage::from_bytes(image_bytes(16))?;
+
+        // sync, erase (2), write (3), read (3) + data
+        let mut replies = vec![ACK; 9];
+        replies.extend_from_slice(image.data());
+        let mut bootloader = crate::firmware::Bootloader::new(FakeBootloader {
+            replies: replies.into(),
+            sent: Vec::new(),
+        });
+
+        let mut stages = Vec::new();
+        bootloader.sync()?;
+        crate::firmware::flash(&mut bootloader, &image, true, &mut |p| stages.push(p))?;
+
+        assert_eq!(
+            stages,
+            [
+                UpgradeProgress::Erasing { done: 1, total: 1 },
+                UpgradeProgress::Writing {
+                    done: 16,
+                    total: 16
+                },
+                UpgradeProgress::Verifying {
+                    done: 16,
+                    total: 16
+                },
+            ]
+        );
+
+        // Extended erase of page 0: count - 1, page number, checksum
+        let sent = &bootloader.into_link().sent;
+        assert_eq!(
+            &sent[..8],
+            &[0x7F, 0x44, 0xBB, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+        // Write memory at flash base with address checksum
+        assert_eq!(&sent[8..15], &[0x31, 0xCE, 0x08, 0x00, 0x00, 0x00, 0x08]);
+
+        Ok(())
+    }
+}
+
+#[test]
+#[cfg(feature = "logging")]
+fn log_levels() {
+    use crate::logging::forward_level;
+    use log::Level;
+
+    // Layer traces get their own target, so they can be filtered separately
+    assert_eq!(
+        forward_level(ffi::STUHFL_D_LOG_LEVEL_TRACE_DL),
+        Some(("stuhfl::dl", Level::Trace))
+    );
+    assert_eq!(
+        forward_level(ffi::STUHFL_D_LOG_LEVEL_WARNING),
+        Some(("stuhfl", Level::Warn))
+    );
+    assert_eq!(forward_level(ffi::STUHFL_D_LOG_LEVEL_ALL), None);
+}
+
+#[test]
+fn connection_options() {
+    use std::time::Duration;
+
+    assert_eq!(
+        ConnectionOptions::default(),
+        ConnectionOptions::builder()
+            .baud_rate(3000000)
+            .build()
+            .unwrap()
+    );
+
+    // Unsupported rates would silently fall back to 115200 on POSIX
+    assert!(ConnectionOptions::builder()
+        .baud_rate(9600)
+        .build()
+        .is_err());
+    assert!(ConnectionOptions::builder()
+        .read_timeout(Duration::ZERO)
+        .build()
+        .is_err());
+    assert!(ConnectionOptions::builder()
+        .write_timeout(Duration::from_secs(5))
+        .ready_timeout(Duration::ZERO)
+        .build()
+        .is_ok());
+}
+
+#[test]
+#[cfg(feature = "port-scanning")]
+fn supervisor_link_errors() {
+    use crate::error::Error;
+    use crate::supervisor::is_link_error;
+
+    // Only a lost link should trigger reconnecting, not failed tag access
+    assert!(is_link_error(&Error::GeneralIo));
+    assert!(is_link_error(&Error::Timeout));
+    assert!(!is_link_error(&Error::ChipNoResp));
+    assert!(!is_link_error(&Error::Gen2ChannelTimeout));
+}
+
+mod tds {
+    use super::TestResult;
+    use crate::error::TdsError;
+    use crate::tds::*;
+
+    /// Parses a hex string into EPC bytes
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn sgtin96() -> TestResult {
+        // Example from the GS1 Tag Data Standard
+        let bytes = hex("3074257BF7194E4000001A85");
+        let epc = Gs1Epc::decode(&bytes)?;
+
+        assert_eq!(epc.scheme, EpcScheme::Sgtin96);
+        assert_eq!(epc.filter, 3);
+        assert_eq!(epc.partition(), Some(5));
+        assert_eq!(
+            epc.pure_identity_uri()?,
+            "urn:epc:id:sgtin:0614141.812345.6789"
+        );
+        assert_eq!(epc.tag_uri()?, "urn:epc:tag:sgtin-96:3.0614141.812345.6789");
+
+        match &epc.identity {
+            Gs1Identity::Sgtin(sgtin) => {
+                assert_eq!(sgtin.gtin, "80614141123458");
+                assert_eq!(sgtin.serial, "6789");
+                assert_eq!(sgtin.company_prefix(), Some("0614141"));
+            }
+            identity => panic!("unexpected identity {:?}", identity),
+        }
+
+        assert_eq!(epc.encode()?, bytes);
+        assert_eq!(
+            Gs1Epc::from_pure_identity_uri(
+                "urn:epc:id:sgtin:0614141.812345.6789",
+                EpcScheme::Sgtin96,
+                3
+            )?,
+            epc
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn uri_round_trips() -> TestResult {
+        let uris = [
+            "urn:epc:tag:sscc-96:3.0614141.1234567890",
+            "urn:epc:tag:sgln-96:1.0614141.12345.400",
+            "urn:epc:tag:sgln-195:1.0614141.12345.A%2FB",
+            "urn:epc:tag:grai-96:0.0614141.12345.400",
+            "urn:epc:tag:grai-170:0.0614141.12345.32a%2Fb",
+            "urn:epc:tag:giai-96:0.0614141.5678",
+            "urn:epc:tag:giai-202:0.0614141.12345ABCD%25",
+            "urn:epc:tag:gid-96:95100000.12345.400",
+            "urn:epc:tag:sgtin-198:3.0614141.812345.ABC.%22x",
+            "urn:epc:tag:sgtin+:3.80614141123458.0012",
+            "urn:epc:tag:sgtin+:1.80614141123458.ab12ef",
+            "urn:epc:tag:sgtin+:1.80614141123458.Lot-7_x",
+            "urn:epc:tag:dsgtin+:0.4.251231.80614141123458.A%2F1",
+        ];
+
+        for uri in uris {
+            let epc = Gs1Epc::from_tag_uri(uri)?;
+            let decoded = Gs1Epc::decode(&epc.encode()?)?;
+
+            assert_eq!(decoded, epc, "{}", uri);
+            assert_eq!(decoded.tag_uri()?, uri);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn known_encodings() -> TestResult {
+        let sscc = Gs1Epc::from_tag_uri("urn:epc:tag:sscc-96:3.0614141.1234567890")?;
+        assert_eq!(sscc.encode()?, hex("3174257BF4499602D2000000"));
+        assert_eq!(
+            sscc.pure_identity_uri()?,
+            "urn:epc:id:sscc:0614141.1234567890"
+        );
+
+        let gid = Gs1Epc::from_tag_uri("urn:epc:tag:gid-96:95100000.12345.400")?;
+        assert_eq!(gid.encode()?, hex("355AB1C60003039000000190"));
+        assert_eq!(gid.partition(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn plus_schemes() -> TestResult {
+        let epc = Gs1Epc::from_tag_uri("urn:epc:tag:dsgtin+:0.4.251231.80614141123458.6789")?;
+
+        assert_eq!(
+            epc.date,
+            Some(PrioritisedDate {
+                date_type: DateType::Expiration,
+                date: "251231".to_owned(),
+            })
+        );
+        assert_eq!(epc.partition(), None);
+        assert_eq!(
+            epc.pure_identity_uri()?,
+            "https://id.gs1.org/01/80614141123458/21/6789?17=251231"
+        );
+        assert_eq!(
+            Gs1Epc::from_pure_identity_uri(&epc.pure_identity_uri()?, EpcScheme::DsgtinPlus, 0)?,
+            epc
+        );
+
+        // Leading zeros of numeric serials are kept
+        let epc = Gs1Epc::from_tag_uri("urn:epc:tag:sgtin+:3.80614141123458.000")?;
+        match Gs1Epc::decode(&epc.encode()?)?.identity {
+            Gs1Identity::Sgtin(sgtin) => assert_eq!(sgtin.serial, "000"),
+            identity => panic!("unexpected identity {:?}", identity),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_epcs() {
+        assert_eq!(
+            Gs1Epc::decode(&hex("E2004216")),
+            Err(TdsError::UnknownHeader(0xE2))
+        );
+        assert!(matches!(
+            Gs1Epc::decode(&hex("3074257BF719")),
+            Err(TdsError::Length { .. })
+        ));
+
+        // Partition 7 is reserved
+        assert_eq!(
+            Gs1Epc::decode(&hex("307C257BF7194E4000001A85")),
+            Err(TdsError::Partition(7))
+        );
+
+        // SGTIN-96 serials are integers without leading zeros
+        assert_eq!(
+            Gs1Epc::from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.812345.0789"),
+            Err(TdsError::Field("serial"))
+        );
+
+        // Wrong check digit
+        let mut epc = Gs1Epc::from_tag_uri("urn:epc:tag:sgtin+:3.80614141123458.1").unwrap();
+        if let Gs1Identity::Sgtin(sgtin) = &mut epc.identity {
+            sgtin.gtin = "80614141123459".to_owned();
+        }
+        assert_eq!(epc.encode(), Err(TdsError::Field("gtin")));
+
+        assert!(matches!(
+            Gs1Epc::from_tag_uri("urn:epc:tag:sgtin-96:3.0614141.6789"),
+            Err(TdsError::Uri(_))
+        ));
+    }
+
+    #[test]
+    fn tid_decoding() -> TestResult {
+        // UCODE 8 with XTID header announcing a 48 bit serial
+        let tid = TidInfo::decode(&hex("E28068940011010203040506"))?;
+
+        assert_eq!(tid.allocation_class, AllocationClass::Gs1);
+        assert!(tid.xtid);
+        assert!(!tid.security && !tid.file);
+        assert_eq!(tid.mask_designer_id, 0x806);
+        assert_eq!(tid.designer(), Some(0x006));
+        assert_eq!(tid.model_number, 0x894);
+        assert_eq!(tid.manufacturer(), Some("NXP Semiconductors"));
+        assert_eq!(tid.chip().map(|chip| chip.model), Some("UCODE 8"));
+
+        let header = tid.xtid_header.unwrap();
+        assert!(!header.extended_header && header.block_write_segment);
+        assert_eq!(header.serial_bits, 48);
+        assert_eq!(tid.serial, Some(hex("010203040506")));
+
+        // Only the first two words were read
+        let tid = TidInfo::decode(&hex("E2806894"))?;
+        assert_eq!((tid.xtid_header, tid.serial), (None, None));
+
+        let tid = TidInfo::decode(&hex("E0041122334455667788"))?;
+        assert_eq!(tid.allocation_class, AllocationClass::Iso7816);
+        assert_eq!((tid.mask_designer_id, tid.chip()), (0x04, None));
+        assert_eq!(tid.serial, Some(hex("1122334455667788")));
+
+        assert_eq!(
+            TidInfo::decode(&hex("3000")),
+            Err(TdsError::Field("allocation class"))
+        );
+        assert!(matches!(
+            TidInfo::decode(&hex("E280")),
+            Err(TdsError::Length { .. })
+        ));
+
+        Ok(())
+    }
+}
+
+#[test]
+fn antenna_power_mode() {
+    let on = AntennaPower {
+        on: true,
+        timeout: 100,
+        frequency: 915250,
+    };
+
+    // The firmware uses 0x00 for on and 0xFF for off
+    assert_eq!(on.as_ffi().mode, 0x00);
+    assert_eq!(AntennaPower::from(on.as_ffi()), on);
+
+    let off = AntennaPower { on: false, ..on };
+    assert_eq!(off.as_ffi().mode, 0xFF);
+    assert_eq!(AntennaPower::from(off.as_ffi()), off);
+}
+
+#[test]
+fn rwd_config_keys() {
+    // Every key the firmware knows about is covered, in ID order
+    assert_eq!(RwdConfigKey::ALL.len(), ffi::STUHFL_D_NUM_RWD_CFG as usize);
+    for (id, key) in RwdConfigKey::ALL.iter().enumerate() {
+        assert_eq!(*key as usize, id);
+    }
+}
+
+#[cfg(feature = "advanced")]
+#[test]
+fn register_bits() {
+    use crate::gen2::*;
+    use crate::registers::*;
+
+    let mut rx = RxOptions::from(0x00);
+    rx.set_blf(Gen2Blf::ThreeHundredTwenty);
+    rx.set_coding(Gen2Coding::Miller4);
+    assert!(rx.blf() == Some(Gen2Blf::ThreeHundredTwenty));
+    assert!(rx.coding() == Gen2Coding::Miller4);
+
+    let mut dsc = DeviceStatusControl::from(0x00);
+    dsc.set_rf_on(true);
+    dsc.set_standby(true);
+    assert_eq!(u8::from(dsc), 0x81);
+}
+
+#[cfg(feature = "advanced")]
+#[test]
+fn register_dump_diff() {
+    use crate::registers::*;
+
+    let a = RegisterDump::from(&[(Register::TxOptions, 0x01), (Register::RxOptions, 0x60)]);
+    let b = RegisterDump::from(&[(Register::RxOptions, 0x62), (Register::Icd, 0x00)]);
+
+    let diff = a.diff(&b);
+    assert_eq!(diff.len(), 3);
+    assert_eq!(diff[0].register, Register::TxOptions);
+    assert_eq!(diff[1].this, Some(0x60));
+    assert_eq!(diff[1].other, Some(0x62));
+    assert_eq!(diff[2].this, None);
+}
+
+#[test]
+fn protocol_control() -> TestResult {
+    use crate::gen2::*;
+
+    // 96 bit EPC, ISO numbering with AFI 0xA2 and XPC
+    let pc = ProtocolControl::from([0x33, 0xA2]);
+    assert_eq!(pc.epc_words, 6);
+    assert!(pc.xi && pc.toggle && !pc.umi);
+    assert_eq!(pc.afi, 0xA2);
+    assert_eq!(pc.nsi(), 0x1A2);
+
+    // Changing the length keeps the other bits
+    let longer = pc.with_epc_len(16)?;
+    assert_eq!(longer.to_bytes(), [0x43, 0xA2]);
+    assert_eq!(ProtocolControl::from(u16::from(longer)), longer);
+    assert!(pc.with_epc_len(15).is_err());
+    assert!(pc.with_epc_len(64).is_err());
+
+    let xpc = ExtendedPc::decode(&[0x80, 0x89, 0x12, 0x34]).unwrap();
+    assert!(xpc.xeb && xpc.battery_assisted && xpc.untraceable && xpc.hazmat);
+    assert!(!xpc.killable && !xpc.sli);
+    assert_eq!(xpc.xpc_w2, Some(0x1234));
+    assert_eq!(xpc.to_bytes(), vec![0x80, 0x89, 0x12, 0x34]);
+    assert_eq!(ExtendedPc::decode(&[0x80]), None);
+
+    Ok(())
+}
+
+#[test]
+fn rssi_model() -> TestResult {
+    use crate::rssi::*;
+
+    let mut model = RssiModel::default();
+
+    // Less sensitivity and AGC gain reduction mean a stronger signal
+    let base = model.raw_to_dbm(6, 4, 0, 0);
+    assert!((model.raw_to_dbm(6, 4, 0, 3) - base - 3.0).abs() < 1e-4);
+    assert!((model.raw_to_dbm(6, 4, 0x02, 0) - base - 6.0).abs() < 1e-4);
+
+    let offset = model.fit_offset(&[(6, 4, 0, 0), (6, 4, 0, 0)], -50.0);
+    assert_eq!(offset, Some(model.offset_dbm()));
+    assert!((model.raw_to_dbm(6, 4, 0, 0) - -50.0).abs() < 1e-4);
+    assert_eq!(model.fit_offset(&[], -50.0), None);
+
+    assert!(RssiModel::builder().log_step_db(-1.0).build().is_err());
+
+    // 1 m at 866 MHz is about 31.2 dB of path loss each way
+    let reference = ReferenceTag::builder()
+        .epc(Epc::from_id(vec![0x30, 0x00]))
+        .distance_m(1.0)
+        .tx_power_dbm(20.0)
+        .build()?;
+    assert!((reference.expected_dbm(866_000) - -32.4).abs() < 0.1);
+
+    for distance in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+        assert!(ReferenceTag::builder()
+            .epc(Epc::from_id(vec![0x30, 0x00]))
+            .distance_m(distance)
+            .tx_power_dbm(20.0)
+            .build()
+            .is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "use-serde")]
+fn rssi_calibration_file() -> TestResult {
+    use crate::rssi::*;
+
+    let calibration: RssiCalibration = toml::from_str("[boards.FT1ABCDE]\noffset_dbm = -87.5\n")?;
+
+    assert!(calibration.contains("FT1ABCDE"));
+    assert_eq!(calibration.model("FT1ABCDE").offset_dbm(), -87.5);
+    assert_eq!(calibration.model("unknown"), RssiModel::default());
+
+    let saved = calibration.to_toml_string()?;
+    assert_eq!(toml::from_str::<RssiCalibration>(&saved)?, calibration);
+
+    Ok(())
+}
+
+#[test]
+fn rssi_measurement() {
+    use crate::gen2::*;
+    use crate::rssi::RssiModel;
+
+    let sample = |agc, log_i, log_q| Gen2RssiSample {
+        agc,
+        log_i,
+        log_q,
+        lin_i: 0,
+        lin_q: 0,
+    };
+
+    let measurement = Gen2RssiMeasurement {
+        frequency: 866_900,
+        sensitivity: -3,
+        samples: vec![sample(0, 4, 6), sample(0, 6, 6), sample(1, 8, 6)],
+    };
+
+    let log_i = measurement.log_i().unwrap();
+    assert_eq!((log_i.min, log_i.max, log_i.mean), (4.0, 8.0, 6.0));
+    assert!((log_i.std_dev - (8f32 / 3.0).sqrt()).abs() < 1e-4);
+    assert_eq!(measurement.log_q().unwrap().std_dev, 0.0);
+
+    let model = RssiModel::default();
+    let dbm = measurement.to_dbm(&model).unwrap();
+    assert_eq!(dbm.max, model.raw_to_dbm(8, 6, 1, -3));
+
+    let empty = Gen2RssiMeasurement {
+        samples: vec![],
+        ..measurement
+    };
+    assert_eq!(empty.agc(), None);
+}
+
+#[test]
+fn rssi_measurement_no_reply() {
+    use crate::gen2::*;
+
+    let mut measure = ffi::STUHFL_T_Gen2_QueryMeasureRssi {
+        frequency: 866_900,
+        measureCnt: 4,
+        agc: [0; 256],
+        rssiLogI: [0; 256],
+        rssiLogQ: [0; 256],
+        rssiLinI: [0; 256],
+        rssiLinQ: [0; 256],
+    };
+
+    // Replies in slots 1 and 3 only, slot 4 is past 'measureCnt'
+    measure.rssiLogI[1] = 5;
+    measure.rssiLogQ[1] = 7;
+    measure.agc[3] = 2;
+    measure.rssiLogI[3] = 6;
+    measure.rssiLinI[3] = -4;
+    measure.rssiLogI[4] = 9;
+
+    let measurement = Gen2RssiMeasurement::from_ffi(&measure, -3);
+
+    assert_eq!(measurement.frequency, 866_900);
+    assert_eq!(measurement.sensitivity, -3);
+    assert_eq!(
+        measurement.samples,
+        vec![
+            Gen2RssiSample {
+                agc: 0,
+                log_i: 5,
+                log_q: 7,
+                lin_i: 0,
+                lin_q: 0,
+            },
+            Gen2RssiSample {
+                agc: 2,
+                log_i: 6,
+                log_q: 0,
+                lin_i: -4,
+                lin_q: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn lock_payload() -> TestResult {
+    use crate::gen2::*;
+
+    // Nothing to lock
+    assert!(Gen2LockCfg::builder().build().is_err());
+
+    let lock = Gen2LockCfg::builder()
+        .kill_password(Gen2LockAction::Unlock)
+        .build()?;
+    assert_eq!(lock.payload(), [0xC0, 0x00, 0x00]);
+
+    let lock = Gen2LockCfg::builder()
+        .user(Gen2LockAction::PermaUnlock)
+        .build()?;
+    assert_eq!(lock.payload(), [0x00, 0xC0, 0x10]);
+
+    let lock = Gen2LockCfg::builder()
+        .kill_password(Gen2LockAction::PermaLock)
+        .access_password(Gen2LockAction::PermaLock)
+        .epc(Gen2LockAction::PermaLock)
+        .tid(Gen2LockAction::PermaLock)
+        .user(Gen2LockAction::PermaLock)
+        .build()?;
+    assert_eq!(lock.payload(), [0xFF, 0xFF, 0xF0]);
+
+    Ok(())
+}
+
 #[test]
 fn hex_id() -> TestResult {
     let id: Vec<u8> = vec![226, 0, 66, 22, 97, 128, 96, 21, 0, 149, 24, 56];
@@ -78,6 +865,178 @@ fn hex_id() -> TestResult {
     Ok(())
 }
 
+/// Reads the PC word of the first tag an inventory finds, with nothing
+/// but the protocol commands
+#[cfg(feature = "use-serde")]
+fn first_pc<R: ProtocolCommands>(reader: &mut R) -> crate::error::Result<Vec<u8>> {
+    reader.tune(TuningAlgorithm::None)?;
+    let (_, tags) = reader.inventory_once()?;
+    let tag = tags.first().ok_or(crate::error::Error::ChipNoResp)?;
+
+    reader.select(&tag.epc)?;
+    reader.read(MemoryBank::Epc, 1, 2, None)
+}
+
+#[test]
+#[cfg(feature = "use-serde")]
+fn recording_replay() -> TestResult {
+    use crate::error::{Error, RecordingError};
+    use crate::gen2::*;
+    use crate::recording::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    let tag = |epc: u8, tid: Vec<u8>| InventoryTag {
+        slot_id: 0,
+        timestamp: 0,
+        antenna: Antenna::Antenna1,
+        agc: 0,
+        rssi_log_i: 6,
+        rssi_log_q: 4,
+        rssi_lin_i: 0,
+        rssi_lin_q: 0,
+        // EPC length of one word
+        pc: [0x08, 0x00],
+        xpc: Xpc::from_id(Vec::new()),
+        epc: Epc::from_id(vec![0xE2, epc]),
+        tid: Tid::from_id(tid),
+    };
+    let statistics = |round_count| InventoryStatistics {
+        round_count,
+        ..InventoryStatistics::new()
+    };
+
+    let version = unsafe { DummyReader::new() }.get_version()?;
+    let config = Gen2Cfg::builder().build()?;
+    let changed = Gen2Cfg::builder()
+        .tx_rx_cfg(TxRxCfg::builder().tx_output_level(-6).build()?)
+        .build()?;
+
+    let mut recorder = Recorder::new(Vec::new(), &version, &config)?;
+    recorder.round(&statistics(1), &[tag(1, vec![0xE2, 0x80]), tag(2, vec![])])?;
+    recorder.config(&changed)?;
+    recorder.round(&statistics(2), &[tag(2, vec![])])?;
+    recorder.tag(&tag(3, vec![]))?;
+    let file = recorder.into_inner();
+
+    let recording = Recording::read(&file[..])?;
+    assert_eq!(recording.version.sw_ver, version.sw_ver);
+    assert!(recording.config == config);
+    assert_eq!(recording.records.len(), 7);
+    assert_eq!(recording.rounds(), 2);
+
+    let mut reader = ReplayReader::new(recording.clone());
+    reader.set_speed(f64::INFINITY)?;
+    assert_eq!(reader.set_speed(0.0), Err(Error::Param));
+    assert_eq!(reader.inventory_once().err(), Some(Error::Generic));
+    reader.tune(TuningAlgorithm::None)?;
+
+    let (stats, tags) = reader.inventory_once()?;
+    assert_eq!(stats.round_count, 1);
+    assert!(tags == [tag(1, vec![0xE2, 0x80]), tag(2, vec![])]);
+    assert!(reader.current_config()? == config);
+
+    let (stats, tags) = reader.inventory_once()?;
+    assert_eq!(stats.round_count, 2);
+    assert_eq!(tags.len(), 1);
+    assert!(reader.current_config()? == changed);
+
+    // The last tag has no statistics
+    assert_eq!(reader.inventory_once().err(), Some(Error::NoMsg));
+    assert!(reader.is_finished());
+
+    // Selecting filters the tags, and answers reads from the recording
+    reader.rewind();
+    reader.select(&Epc::from_id(vec![0xE2, 0x01]))?;
+    let (_, tags) = reader.inventory_once()?;
+    assert_eq!(tags.len(), 1);
+    assert_eq!(
+        reader.read(MemoryBank::Epc, 1, 4, None)?,
+        [0x08, 0x00, 0xE2, 0x01]
+    );
+    assert_eq!(reader.read(MemoryBank::Epc, 2, 2, None)?, [0xE2, 0x01]);
+    assert_eq!(reader.read(MemoryBank::Tid, 0, 2, None)?, [0xE2, 0x80]);
+    assert_eq!(
+        reader.read(MemoryBank::Epc, 2, 4, None),
+        Err(Error::Gen2MemOverRun)
+    );
+    assert_eq!(
+        reader.read(MemoryBank::Epc, u32::MAX, 255, None),
+        Err(Error::Gen2MemOverRun)
+    );
+    assert_eq!(
+        reader.read(MemoryBank::User, 0, 2, None),
+        Err(Error::Gen2NotSupported)
+    );
+    assert_eq!(
+        reader.write(MemoryBank::User, 0, [0, 0], None),
+        Err(Error::Gen2NotSupported)
+    );
+    reader.clear_select()?;
+    assert_eq!(
+        reader.read(MemoryBank::Epc, 1, 2, None),
+        Err(Error::ChipNoResp)
+    );
+
+    // Code generic over the protocol commands runs on recordings and readers
+    reader.rewind();
+    assert_eq!(first_pc(&mut reader)?, [0x08, 0x00]);
+    reader.clear_select()?;
+    let _ = first_pc::<Gen2Reader>;
+
+    // Threaded inventory, looping back to the start
+    reader.rewind();
+    reader.set_looping(true);
+    let found = Arc::new(Mutex::new(Vec::new()));
+    let found2 = Arc::clone(&found);
+    let stats = reader.inventory(3, Box::new(move |tag| found2.lock().unwrap().push(tag.epc)))?;
+    assert_eq!(stats.round_count, 1);
+    assert_eq!(found.lock().unwrap().len(), 6);
+
+    // Stops at the end of the recording
+    reader.set_looping(false);
+    assert_eq!(reader.inventory(5, Box::new(|_| {}))?.round_count, 2);
+    assert_eq!(
+        reader.inventory(1, Box::new(|_| {})).err(),
+        Some(Error::NoMsg)
+    );
+
+    // Real time, 10 times faster
+    let mut timed = recording;
+    for (i, record) in timed.records.iter_mut().enumerate() {
+        record.time_ms = i as u64 * 40;
+    }
+    assert_eq!(timed.duration(), Duration::from_millis(240));
+    let mut reversed = timed.clone();
+    reversed.records.reverse();
+    assert_eq!(reversed.duration(), Duration::ZERO);
+    let mut reader = ReplayReader::new(timed);
+    reader.set_speed(10.0)?;
+    reader.tune(TuningAlgorithm::None)?;
+    let started = Instant::now();
+    reader.inventory(2, Box::new(|_| {}))?;
+    assert!(started.elapsed() >= Duration::from_millis(16));
+
+    // Invalid files
+    assert!(matches!(
+        Recording::read(&b""[..]),
+        Err(RecordingError::Parse(1, _))
+    ));
+    let mut file = String::from_utf8(file)?;
+    file.push_str("{\"time_ms\":1}\n");
+    assert!(matches!(
+        Recording::read(file.as_bytes()),
+        Err(RecordingError::Parse(9, _))
+    ));
+    let file = file.replacen("{\"format\":1", "{\"format\":9", 1);
+    assert!(matches!(
+        Recording::read(file.as_bytes()),
+        Err(RecordingError::UnsupportedFormat(9))
+    ));
+
+    Ok(())
+}
+
 #[cfg(feature = "reader-tests")]
 #[test]
 #[serial]
@@ -91,6 +1050,31 @@ fn check_reader_version() -> TestResult {
     Ok(())
 }
 
+#[cfg(feature = "reader-tests")]
+#[test]
+#[serial]
+fn discover_readers() -> TestResult {
+    let candidates = Reader::discover();
+
+    assert!(!candidates.is_empty(), "No reader found");
+
+    for candidate in &candidates {
+        assert!(
+            candidate.version.is_some(),
+            "{} didn't answer",
+            candidate.port
+        );
+    }
+
+    // Bind to the same board again through its serial number
+    if let Some(serial_number) = &candidates[0].serial_number {
+        let reader = Reader::connect_by_serial(serial_number)?;
+        assert_eq!(reader.steal_connection().port(), candidates[0].port);
+    }
+
+    Ok(())
+}
+
 //#[cfg(feature = "reader-tests")]
 mod gen2 {
     use super::*;
@@ -248,6 +1232,94 @@ mod gen2 {
 
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "reader-tests")]
+    //#[serial]
+    fn write_epc() -> TestResult {
+        let reader = Reader::autoconnect()?;
+
+        let gen2_config = Gen2Cfg::builder().build()?;
+
+        let mut reader = reader.configure_gen2(&gen2_config)?;
+
+        reader.tune(TuningAlgorithm::Exact)?;
+
+        let (_stats, tags) = reader.inventory_once()?;
+
+        if tags.is_empty() {
+            panic!("No tags found")
+        }
+
+        let old = tags[0].epc.clone();
+
+        // Change the last word, then restore the old EPC
+        let mut id = old.get_id().to_vec();
+        let last = id.len() - 1;
+        id[last] ^= 0xFF;
+        let new = Epc::from_id(id);
+
+        let result = reader.write_epc(&old, &new, None)?;
+        assert_eq!(result.status, Gen2EpcWriteStatus::Complete);
+        assert_eq!((result.words_written, result.pc_updated), (1, false));
+
+        let result = reader.write_epc(&new, &old, None)?;
+        assert_eq!(result.status, Gen2EpcWriteStatus::Complete);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "reader-tests")]
+    //#[serial]
+    fn measure_rssi() -> TestResult {
+        let reader = Reader::autoconnect()?;
+
+        let gen2_config = Gen2Cfg::builder().build()?;
+
+        let mut reader = reader.configure_gen2(&gen2_config)?;
+
+        reader.tune(TuningAlgorithm::Exact)?;
+
+        let (_stats, tags) = reader.inventory_once()?;
+
+        if tags.is_empty() {
+            panic!("No tags found")
+        }
+
+        reader.select(&tags[0].epc)?;
+
+        let channels: Vec<ChannelItem> = reader.get_channel_list()?.into();
+        let measurements = reader.measure_rssi_channels(8)?;
+
+        assert_eq!(measurements.len(), channels.len());
+        assert!(measurements.iter().all(|m| m.samples.len() <= 8));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "reader-tests")]
+    //#[serial]
+    fn adaptive_q_readback() -> TestResult {
+        let reader = Reader::autoconnect()?;
+
+        let adaptive_q = Gen2AdaptiveQ::Enable(
+            Gen2AdaptiveQCfg::builder()
+                .preset(Gen2AdaptiveQPreset::DensePopulation)
+                .build()?,
+        );
+        let gen2_config = Gen2Cfg::builder()
+            .inv_cfg(Gen2InventoryCfg::builder().adaptive_q(adaptive_q).build()?)
+            .build()?;
+
+        let reader = reader.configure_gen2(&gen2_config)?;
+
+        // Settings read back from the firmware match what was applied
+        assert_eq!(reader.get_adaptive_q()?, adaptive_q);
+
+        Ok(())
+    }
     /*
         #[test]
         #[serial]
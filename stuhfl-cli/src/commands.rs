@@ -0,0 +1,303 @@
+//! Implementation of the commands
+
+use crate::output::{hex, Format, ReaderRow, RowWriter, SweepRow, TagRow};
+use crate::{Cli, Command, ConfigCommand, ConnectionArgs};
+use crate::{InventoryArgs, ReadArgs, SweepArgs, TuneArgs, WriteArgs};
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use libstuhfl::rssi::{RssiCalibration, RssiModel};
+use serde::Serialize;
+use std::error::Error;
+use std::io;
+
+/// Result of a command
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Runs the command given on the command line
+pub(crate) fn run(cli: Cli) -> Result<()> {
+    let args = &cli.connection;
+
+    match cli.command {
+        Command::Discover => discover(),
+        Command::Info => info(args),
+        Command::Inventory(inventory_args) => inventory(args, &inventory_args),
+        Command::Read(read_args) => read(args, &read_args),
+        Command::Write(write_args) => write(args, &write_args),
+        Command::Tune(tune_args) => tune(args, &tune_args),
+        Command::Sweep(sweep_args) => sweep(args, &sweep_args),
+        Command::Config(ConfigCommand::Dump { output }) => config_dump(args, output.as_deref()),
+        Command::Config(ConfigCommand::Apply { file }) => config_apply(args, &file),
+    }
+}
+
+/// Connects to the reader chosen with `--port` or `--serial`, or the
+/// first one found
+fn connect(args: &ConnectionArgs) -> Result<Reader> {
+    Ok(stuhfl_common::connect(
+        args.port.as_deref(),
+        args.serial.as_deref(),
+    )?)
+}
+
+/// Connects and configures Gen2 with `--config`, or the default
+/// configuration
+fn gen2_reader(args: &ConnectionArgs) -> Result<Gen2Reader> {
+    let config = stuhfl_common::load_config(args.config.as_deref())?;
+
+    Ok(connect(args)?.configure_gen2(&config)?)
+}
+
+/// Same as [`gen2_reader()`], tuned with the `--tuning` algorithm so the
+/// reader can talk to tags
+fn tuned_reader(args: &ConnectionArgs) -> Result<Gen2Reader> {
+    let mut reader = gen2_reader(args)?;
+    reader.tune(args.tuning.into())?;
+
+    Ok(reader)
+}
+
+/// Runs `command` on the tag with `epc` if given, selecting it for the
+/// duration of the command only
+fn on_tag<T>(
+    reader: &mut Gen2Reader,
+    epc: Option<&Epc>,
+    command: impl FnOnce(&mut Gen2Reader) -> Result<T>,
+) -> Result<T> {
+    let Some(epc) = epc else {
+        return command(reader);
+    };
+
+    reader.select(epc)?;
+    let result = command(reader);
+    let cleared = reader.clear_select();
+
+    let value = result?;
+    cleared?;
+
+    Ok(value)
+}
+
+/// `stuhfl discover`
+fn discover() -> Result<()> {
+    let candidates = Reader::discover();
+
+    if candidates.is_empty() {
+        return Err("no readers found".into());
+    }
+
+    let mut out = RowWriter::new(Format::Table, io::stdout());
+
+    for candidate in candidates {
+        out.write(&ReaderRow::from(candidate))?;
+    }
+
+    out.finish()?;
+
+    Ok(())
+}
+
+/// `stuhfl info`
+fn info(args: &ConnectionArgs) -> Result<()> {
+    let version = connect(args)?.get_version()?;
+
+    println!("Firmware: {} ({})", version.sw_ver, version.sw_info);
+    println!("Hardware: {} ({})", version.hw_ver, version.hw_info);
+
+    Ok(())
+}
+
+/// `stuhfl inventory`
+fn inventory(args: &ConnectionArgs, inventory_args: &InventoryArgs) -> Result<()> {
+    let reader = tuned_reader(args)?;
+    let mut out = RowWriter::new(inventory_args.format, io::stdout());
+
+    let mut rounds = 0;
+    let mut tag_count = 0;
+
+    while inventory_args.continuous || rounds < inventory_args.rounds {
+        let (_stats, tags) = reader.inventory_once()?;
+
+        for tag in &tags {
+            out.write(&TagRow::from(tag))?;
+        }
+
+        rounds += 1;
+        tag_count += tags.len();
+    }
+
+    out.finish()?;
+    eprintln!("{} tags found in {} rounds", tag_count, rounds);
+
+    Ok(())
+}
+
+/// `stuhfl read`
+fn read(args: &ConnectionArgs, read_args: &ReadArgs) -> Result<()> {
+    let mut reader = tuned_reader(args)?;
+
+    let data = on_tag(&mut reader, read_args.epc.as_ref(), |reader| {
+        Ok(reader.read(
+            read_args.bank.into(),
+            read_args.address,
+            read_args.bytes,
+            read_args.password,
+        )?)
+    })?;
+
+    println!("{}", hex(&data));
+
+    Ok(())
+}
+
+/// `stuhfl write`
+fn write(args: &ConnectionArgs, write_args: &WriteArgs) -> Result<()> {
+    if write_args.data.len() & 1 != 0 {
+        return Err("data must be a whole number of words".into());
+    }
+
+    let mut reader = tuned_reader(args)?;
+
+    on_tag(&mut reader, write_args.epc.as_ref(), |reader| {
+        for (i, word) in write_args.data.chunks(2).enumerate() {
+            let address = write_args.address + i as u32;
+
+            reader
+                .write(
+                    write_args.bank.into(),
+                    address,
+                    [word[0], word[1]],
+                    write_args.password,
+                )
+                .map_err(|e| format!("writing word 0x{:X} failed: {}", address, e))?;
+        }
+
+        Ok(())
+    })?;
+
+    println!("Wrote {} words", write_args.data.len() / 2);
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+/// Output of `stuhfl tune`, in the layout of a configuration file
+struct TuneOutput {
+    /// Channel list with the tuning capacitors found
+    channel_list: ChannelListCfg,
+}
+
+/// `stuhfl tune`
+fn tune(args: &ConnectionArgs, tune_args: &TuneArgs) -> Result<()> {
+    let mut reader = gen2_reader(args)?;
+    reader.tune(tune_args.algorithm.into())?;
+
+    let output = TuneOutput {
+        channel_list: reader.get_channel_list()?,
+    };
+
+    print!("{}", toml::to_string(&output)?);
+
+    Ok(())
+}
+
+/// `stuhfl sweep`
+fn sweep(args: &ConnectionArgs, sweep_args: &SweepArgs) -> Result<()> {
+    let model = match (&sweep_args.calibration, &sweep_args.board) {
+        (Some(path), Some(board)) => {
+            let calibration = RssiCalibration::from_toml_file(path)?;
+
+            if !calibration.contains(board) {
+                return Err(format!("no calibration for board {}", board).into());
+            }
+
+            calibration.model(board)
+        }
+        _ => RssiModel::default(),
+    };
+
+    let mut reader = tuned_reader(args)?;
+
+    let measurements = on_tag(&mut reader, sweep_args.epc.as_ref(), |reader| {
+        Ok(reader.measure_rssi_channels(sweep_args.count)?)
+    })?;
+
+    let mut out = RowWriter::new(sweep_args.format, io::stdout());
+
+    for measurement in measurements {
+        out.write(&SweepRow::new(&measurement, &model))?;
+    }
+
+    out.finish()?;
+
+    Ok(())
+}
+
+/// `stuhfl config dump`
+fn config_dump(args: &ConnectionArgs, output: Option<&std::path::Path>) -> Result<()> {
+    let config = gen2_reader(args)?.current_config()?.to_toml_string()?;
+
+    match output {
+        Some(path) => std::fs::write(path, config)?,
+        None => print!("{}", config),
+    }
+
+    Ok(())
+}
+
+/// `stuhfl config apply`
+fn config_apply(args: &ConnectionArgs, file: &std::path::Path) -> Result<()> {
+    let config = Gen2Cfg::from_toml_file(file)?;
+    let reader = connect(args)?.configure_gen2(&config)?;
+
+    let diff = config.diff(&reader.current_config()?);
+
+    if !diff.is_empty() {
+        for setting in &diff {
+            eprintln!("{}", setting);
+        }
+
+        return Err(format!("{} settings were not accepted", diff.len()).into());
+    }
+
+    println!("Configuration applied");
+
+    Ok(())
+}
+
+/// Parses hex bytes, optionally separated by `:` like [`Epc`]'s display
+pub(crate) fn parse_hex(s: &str) -> std::result::Result<Vec<u8>, String> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let digits: Vec<u8> = s.bytes().filter(|&b| b != b':').collect();
+
+    if digits.is_empty() || digits.len() & 1 != 0 || !digits.iter().all(u8::is_ascii_hexdigit) {
+        return Err("expected hex bytes, e.g. E2801160 or E2:80:11:60".to_owned());
+    }
+
+    Ok(digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect())
+}
+
+/// Parses an EPC given as hex
+pub(crate) fn parse_epc(s: &str) -> std::result::Result<Epc, String> {
+    parse_hex(s).map(Epc::from_id)
+}
+
+/// Parses a password given as 8 hex digits
+pub(crate) fn parse_password(s: &str) -> std::result::Result<Password, String> {
+    let bytes = parse_hex(s)?;
+
+    <[u8; 4]>::try_from(bytes.as_slice())
+        .map(Password::from)
+        .map_err(|_| "password must be 4 bytes (8 hex digits)".to_owned())
+}
+
+/// Parses a decimal or `0x` prefixed hex number
+pub(crate) fn parse_number(s: &str) -> std::result::Result<u32, String> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+    .map_err(|e| e.to_string())
+}
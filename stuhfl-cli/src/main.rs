@@ -0,0 +1,234 @@
+//! `stuhfl` - command line tool for ST25RU3993 evaluation boards.
+//!
+//! Covers the everyday jobs of the ST GUI: finding readers, running
+//! inventories, reading and writing tag memory, tuning and managing the
+//! Gen2 configuration.
+//!
+//! Every invocation opens its own connection, which resets the board, so
+//! each command applies the `--config` file (or the library's default
+//! configuration) first. Commands on a single tag take its `--epc`, the
+//! tag is only selected while the command runs.
+
+#![warn(missing_docs)]
+#![warn(clippy::missing_docs_in_private_items)]
+
+mod commands;
+mod output;
+#[cfg(test)]
+mod tests;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use libstuhfl::prelude::*;
+use output::Format;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use stuhfl_common::Tuning;
+
+#[derive(Parser)]
+#[command(
+    name = "stuhfl",
+    version,
+    about = "Command line tool for ST25RU3993 RFID readers"
+)]
+/// Command line arguments
+struct Cli {
+    /// Reader selection & setup
+    #[command(flatten)]
+    connection: ConnectionArgs,
+
+    /// Command to run
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+/// Options shared by all commands
+struct ConnectionArgs {
+    /// Serial port of the reader [default: first reader found]
+    #[arg(long, global = true, conflicts_with = "serial")]
+    port: Option<String>,
+
+    /// USB serial number of the reader (see `stuhfl discover`)
+    #[arg(long, global = true)]
+    serial: Option<String>,
+
+    /// Gen2 configuration file to apply first [default: the library's
+    /// default configuration]
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Tuning algorithm run before talking to tags
+    #[arg(long, global = true, value_enum, default_value_t = Tuning::Fast)]
+    tuning: Tuning,
+}
+
+#[derive(Subcommand)]
+/// Available commands
+enum Command {
+    /// List the readers attached over USB
+    Discover,
+    /// Show the firmware and hardware version
+    Info,
+    /// Run inventory rounds and list the tags found
+    Inventory(InventoryArgs),
+    /// Read tag memory
+    Read(ReadArgs),
+    /// Write tag memory
+    Write(WriteArgs),
+    /// Tune the antenna and print the resulting channel list
+    Tune(TuneArgs),
+    /// Measure the RSSI of a tag (or all tags) on every channel
+    Sweep(SweepArgs),
+    /// Read or apply the Gen2 configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Args)]
+/// Arguments of `stuhfl inventory`
+struct InventoryArgs {
+    /// Number of inventory rounds
+    #[arg(long, default_value_t = 1, conflicts_with = "continuous")]
+    rounds: u32,
+
+    /// Keep running until interrupted
+    #[arg(long)]
+    continuous: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+#[derive(Args)]
+/// Arguments of `stuhfl read`
+struct ReadArgs {
+    /// Memory bank
+    #[arg(value_enum)]
+    bank: Bank,
+
+    /// Word address, decimal or 0x-prefixed hex
+    #[arg(value_parser = commands::parse_number)]
+    address: u32,
+
+    /// Number of bytes to read
+    bytes: u8,
+
+    /// Access password (8 hex digits)
+    #[arg(long, value_parser = commands::parse_password)]
+    password: Option<Password>,
+
+    /// EPC of the tag to read from [default: the first tag answering]
+    #[arg(long, value_parser = commands::parse_epc)]
+    epc: Option<Epc>,
+}
+
+#[derive(Args)]
+/// Arguments of `stuhfl write`
+struct WriteArgs {
+    /// Memory bank
+    #[arg(value_enum)]
+    bank: Bank,
+
+    /// Word address, decimal or 0x-prefixed hex
+    #[arg(value_parser = commands::parse_number)]
+    address: u32,
+
+    /// Data as hex, a whole number of words
+    #[arg(value_parser = commands::parse_hex)]
+    data: ::std::vec::Vec<u8>,
+
+    /// Access password (8 hex digits)
+    #[arg(long, value_parser = commands::parse_password)]
+    password: Option<Password>,
+
+    /// EPC of the tag to write to [default: the first tag answering]
+    #[arg(long, value_parser = commands::parse_epc)]
+    epc: Option<Epc>,
+}
+
+#[derive(Args)]
+/// Arguments of `stuhfl tune`
+struct TuneArgs {
+    /// Tuning algorithm
+    #[arg(value_enum, default_value_t = Tuning::Exact)]
+    algorithm: Tuning,
+}
+
+#[derive(Args)]
+/// Arguments of `stuhfl sweep`
+struct SweepArgs {
+    /// EPC of the tag to measure [default: all tags answering]
+    #[arg(long, value_parser = commands::parse_epc)]
+    epc: Option<Epc>,
+
+    /// Number of measurements per channel
+    #[arg(long, default_value_t = 16, value_parser = clap::value_parser!(u8).range(1..))]
+    count: u8,
+
+    /// RSSI calibration file for the dBm column
+    #[arg(long, value_name = "FILE", requires = "board")]
+    calibration: Option<PathBuf>,
+
+    /// Board entry of the calibration file to use
+    #[arg(long)]
+    board: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+#[derive(Subcommand)]
+/// Configuration commands
+enum ConfigCommand {
+    /// Print the configuration the board uses after applying `--config`
+    /// as TOML
+    Dump {
+        /// Write to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Apply a configuration file and check that the board accepted it
+    Apply {
+        /// Configuration file (TOML)
+        file: PathBuf,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, ValueEnum)]
+/// Memory banks, see [`MemoryBank`]
+enum Bank {
+    /// Reserved memory (kill & access passwords)
+    Reserved,
+    /// EPC memory (CRC, PC, EPC)
+    Epc,
+    /// TID memory
+    Tid,
+    /// User memory
+    User,
+}
+
+impl From<Bank> for MemoryBank {
+    fn from(bank: Bank) -> Self {
+        match bank {
+            Bank::Reserved => MemoryBank::Reserved,
+            Bank::Epc => MemoryBank::Epc,
+            Bank::Tid => MemoryBank::Tid,
+            Bank::User => MemoryBank::User,
+        }
+    }
+}
+
+/// Runs the command, reporting errors on stderr
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match commands::run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
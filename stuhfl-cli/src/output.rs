@@ -0,0 +1,280 @@
+//! Output formats for commands printing rows of data
+
+use clap::ValueEnum;
+use libstuhfl::gen2::Gen2RssiMeasurement;
+use libstuhfl::prelude::*;
+use libstuhfl::rssi::RssiModel;
+use serde::Serialize;
+use std::error::Error;
+use std::io::Write;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, ValueEnum)]
+/// How rows are printed
+pub(crate) enum Format {
+    /// Aligned columns for reading on a terminal
+    Table,
+    /// One JSON object per line
+    Jsonl,
+    /// Comma separated values with a header line
+    Csv,
+}
+
+/// A row of output
+pub(crate) trait Row: Serialize {
+    /// Column names and widths of the table format
+    const COLUMNS: &'static [(&'static str, usize)];
+
+    /// Cells of the table format, in column order
+    fn cells(&self) -> Vec<String>;
+}
+
+/// Destination of the rows, depending on the format
+enum Sink<W: Write> {
+    /// Aligned columns, with whether the header was printed
+    Table(W, bool),
+    /// JSON lines
+    Jsonl(W),
+    /// CSV, the header is written with the first row
+    Csv(Box<csv::Writer<W>>),
+}
+
+/// Prints rows in the chosen [`Format`]. Every row is flushed right
+/// away, so continuous output can be piped into other tools.
+pub(crate) struct RowWriter<W: Write> {
+    /// Where rows go
+    sink: Sink<W>,
+}
+
+impl<W: Write> RowWriter<W> {
+    /// Creates a writer printing to `out`
+    pub(crate) fn new(format: Format, out: W) -> Self {
+        let sink = match format {
+            Format::Table => Sink::Table(out, false),
+            Format::Jsonl => Sink::Jsonl(out),
+            Format::Csv => Sink::Csv(Box::new(csv::Writer::from_writer(out))),
+        };
+
+        Self { sink }
+    }
+
+    /// Prints a single row
+    pub(crate) fn write<R: Row>(&mut self, row: &R) -> Result<(), Box<dyn Error>> {
+        match &mut self.sink {
+            Sink::Table(out, header) => {
+                if !*header {
+                    let names = R::COLUMNS.iter().map(|(name, _)| name.to_uppercase());
+                    writeln!(out, "{}", align(names, R::COLUMNS))?;
+                    *header = true;
+                }
+                writeln!(out, "{}", align(row.cells(), R::COLUMNS))?;
+                out.flush()?;
+            }
+            Sink::Jsonl(out) => {
+                writeln!(out, "{}", serde_json::to_string(row)?)?;
+                out.flush()?;
+            }
+            Sink::Csv(out) => {
+                out.serialize(row)?;
+                out.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finishes printing and returns the destination
+    pub(crate) fn finish(self) -> Result<W, Box<dyn Error>> {
+        match self.sink {
+            Sink::Table(out, _) | Sink::Jsonl(out) => Ok(out),
+            Sink::Csv(out) => Ok(out.into_inner().map_err(|e| e.into_error())?),
+        }
+    }
+}
+
+/// Pads `cells` to the column widths, the last column isn't padded
+fn align<I: IntoIterator<Item = String>>(cells: I, columns: &[(&str, usize)]) -> String {
+    cells
+        .into_iter()
+        .zip(columns)
+        .map(|(cell, (_, width))| format!("{:<width$}", cell, width = width))
+        .collect::<Vec<_>>()
+        .join("  ")
+        .trim_end()
+        .to_owned()
+}
+
+/// Formats bytes as upper case hex without separators
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// Formats an optional value with one decimal, `-` if missing
+fn decimal(value: Option<f32>) -> String {
+    value.map_or_else(|| "-".to_owned(), |value| format!("{:.1}", value))
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+/// A reader found by `stuhfl discover`
+pub(crate) struct ReaderRow {
+    /// Serial port
+    pub(crate) port: String,
+    /// USB serial number
+    pub(crate) serial: Option<String>,
+    /// USB product string
+    pub(crate) product: Option<String>,
+    /// Firmware version, if the board answered
+    pub(crate) firmware: Option<String>,
+}
+
+impl From<ReaderCandidate> for ReaderRow {
+    fn from(candidate: ReaderCandidate) -> Self {
+        Self {
+            port: candidate.port,
+            serial: candidate.serial_number,
+            product: candidate.product,
+            firmware: candidate.version.map(|v| v.sw_ver.to_string()),
+        }
+    }
+}
+
+impl Row for ReaderRow {
+    const COLUMNS: &'static [(&'static str, usize)] = &[
+        ("port", 16),
+        ("serial", 10),
+        ("product", 24),
+        ("firmware", 12),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_owned());
+
+        vec![
+            self.port.clone(),
+            or_dash(&self.serial),
+            or_dash(&self.product),
+            or_dash(&self.firmware),
+        ]
+    }
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+/// A tag found by `stuhfl inventory`
+pub(crate) struct TagRow {
+    /// Time the tag was found, ms after starting inventory
+    pub(crate) timestamp: u32,
+    /// Antenna the tag was found on
+    pub(crate) antenna: u8,
+    /// EPC as hex
+    pub(crate) epc: String,
+    /// PC as hex
+    pub(crate) pc: String,
+    /// TID as hex, empty unless read during inventory
+    pub(crate) tid: String,
+    /// I part of logarithmic RSSI
+    pub(crate) rssi_log_i: u8,
+    /// Q part of logarithmic RSSI
+    pub(crate) rssi_log_q: u8,
+    /// AGC gain reduction
+    pub(crate) agc: u8,
+}
+
+impl From<&InventoryTag> for TagRow {
+    fn from(tag: &InventoryTag) -> Self {
+        Self {
+            timestamp: tag.timestamp,
+            antenna: tag.antenna as u8,
+            epc: hex(tag.epc.get_id()),
+            pc: hex(&tag.pc),
+            tid: hex(tag.tid.get_id()),
+            rssi_log_i: tag.rssi_log_i,
+            rssi_log_q: tag.rssi_log_q,
+            agc: tag.agc,
+        }
+    }
+}
+
+impl Row for TagRow {
+    const COLUMNS: &'static [(&'static str, usize)] = &[
+        ("time", 8),
+        ("ant", 3),
+        ("epc", 24),
+        ("pc", 4),
+        ("rssi i", 6),
+        ("rssi q", 6),
+        ("agc", 3),
+        ("tid", 24),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_string(),
+            self.antenna.to_string(),
+            self.epc.clone(),
+            self.pc.clone(),
+            self.rssi_log_i.to_string(),
+            self.rssi_log_q.to_string(),
+            self.agc.to_string(),
+            self.tid.clone(),
+        ]
+    }
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+/// RSSI of one channel measured by `stuhfl sweep`
+pub(crate) struct SweepRow {
+    /// Channel frequency (kHz)
+    pub(crate) frequency: u32,
+    /// Number of replies measured
+    pub(crate) replies: usize,
+    /// Mean of logarithmic I
+    pub(crate) log_i: Option<f32>,
+    /// Mean of logarithmic Q
+    pub(crate) log_q: Option<f32>,
+    /// Mean AGC gain reduction
+    pub(crate) agc: Option<f32>,
+    /// Mean received power (dBm)
+    pub(crate) dbm: Option<f32>,
+    /// Standard deviation of the received power (dB)
+    pub(crate) dbm_std_dev: Option<f32>,
+}
+
+impl SweepRow {
+    /// Summarizes `measurement`, using `model` for the received power
+    pub(crate) fn new(measurement: &Gen2RssiMeasurement, model: &RssiModel) -> Self {
+        let dbm = measurement.to_dbm(model);
+
+        Self {
+            frequency: measurement.frequency,
+            replies: measurement.samples.len(),
+            log_i: measurement.log_i().map(|s| s.mean),
+            log_q: measurement.log_q().map(|s| s.mean),
+            agc: measurement.agc().map(|s| s.mean),
+            dbm: dbm.map(|s| s.mean),
+            dbm_std_dev: dbm.map(|s| s.std_dev),
+        }
+    }
+}
+
+impl Row for SweepRow {
+    const COLUMNS: &'static [(&'static str, usize)] = &[
+        ("khz", 6),
+        ("replies", 7),
+        ("log i", 5),
+        ("log q", 5),
+        ("agc", 4),
+        ("dbm", 6),
+        ("std dev", 7),
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.frequency.to_string(),
+            self.replies.to_string(),
+            decimal(self.log_i),
+            decimal(self.log_q),
+            decimal(self.agc),
+            decimal(self.dbm),
+            decimal(self.dbm_std_dev),
+        ]
+    }
+}
@@ -0,0 +1,105 @@
+use crate::commands::*;
+use crate::output::*;
+use crate::Cli;
+use clap::{CommandFactory, Parser};
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use libstuhfl::rssi::RssiModel;
+use stuhfl_common::test_support::tag;
+
+type TestResult = std::result::Result<(), Box<dyn std::error::Error>>;
+
+#[test]
+fn cli_definition() {
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn cli_arguments() {
+    assert!(
+        Cli::try_parse_from(["stuhfl", "inventory", "--rounds", "5", "--format", "csv"]).is_ok()
+    );
+    assert!(Cli::try_parse_from(["stuhfl", "inventory", "--rounds", "5", "--continuous"]).is_err());
+    assert!(Cli::try_parse_from(["stuhfl", "read", "tid", "0", "12", "--epc", "E2:80"]).is_ok());
+    assert!(Cli::try_parse_from(["stuhfl", "write", "user", "0x10", "12345678"]).is_ok());
+    assert!(Cli::try_parse_from(["stuhfl", "write", "user", "0x10", "123"]).is_err());
+    assert!(Cli::try_parse_from(["stuhfl", "sweep", "--epc", "E280"]).is_ok());
+    assert!(Cli::try_parse_from(["stuhfl", "select", "E280"]).is_err());
+    assert!(Cli::try_parse_from(["stuhfl", "sweep", "--count", "0"]).is_err());
+    assert!(Cli::try_parse_from(["stuhfl", "--port", "/dev/ttyUSB0", "info"]).is_ok());
+    assert!(Cli::try_parse_from(["stuhfl", "config", "apply", "station.toml"]).is_ok());
+}
+
+#[test]
+fn value_parsers() {
+    assert_eq!(parse_hex("E2:80:11:60"), Ok(vec![0xE2, 0x80, 0x11, 0x60]));
+    assert_eq!(parse_hex("0xe28011"), Ok(vec![0xE2, 0x80, 0x11]));
+    assert!(parse_hex("E28").is_err());
+    assert!(parse_hex("").is_err());
+    assert!(parse_hex("E2G0").is_err());
+
+    assert_eq!(parse_number("0x20"), Ok(32));
+    assert_eq!(parse_number("32"), Ok(32));
+    assert!(parse_number("0x").is_err());
+
+    assert!(parse_password("00000000").is_ok());
+    assert!(parse_password("000000").is_err());
+}
+
+#[test]
+fn row_formats() -> TestResult {
+    let tag = InventoryTag {
+        timestamp: 12,
+        agc: 1,
+        ..tag(vec![0xE2, 0x80])
+    };
+    let row = TagRow::from(&tag);
+
+    let mut out = RowWriter::new(Format::Table, Vec::new());
+    out.write(&row)?;
+    let table = String::from_utf8(out.finish()?)?;
+    let lines: Vec<&str> = table.lines().collect();
+    assert!(lines[0].starts_with("TIME      ANT  EPC"));
+    assert!(lines[1].contains("E280"));
+
+    let mut out = RowWriter::new(Format::Jsonl, Vec::new());
+    out.write(&row)?;
+    out.write(&row)?;
+    let jsonl = String::from_utf8(out.finish()?)?;
+    assert_eq!(jsonl.lines().count(), 2);
+    assert!(jsonl.starts_with(r#"{"timestamp":12,"antenna":0,"epc":"E280","pc":"0800""#));
+
+    let mut out = RowWriter::new(Format::Csv, Vec::new());
+    out.write(&row)?;
+    let csv = String::from_utf8(out.finish()?)?;
+    assert_eq!(
+        csv,
+        "timestamp,antenna,epc,pc,tid,rssi_log_i,rssi_log_q,agc\n12,0,E280,0800,,6,5,1\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn sweep_rows() -> TestResult {
+    let measurement = Gen2RssiMeasurement {
+        frequency: 866_900,
+        sensitivity: 0,
+        samples: vec![],
+    };
+    let row = SweepRow::new(&measurement, &RssiModel::default());
+
+    assert_eq!(row.replies, 0);
+    assert_eq!(row.dbm, None);
+
+    let mut out = RowWriter::new(Format::Table, Vec::new());
+    out.write(&row)?;
+    let table = String::from_utf8(out.finish()?)?;
+    assert!(table
+        .lines()
+        .nth(1)
+        .unwrap()
+        .starts_with("866900  0        -"));
+
+    Ok(())
+}
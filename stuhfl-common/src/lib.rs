@@ -0,0 +1,81 @@
+//! Helpers shared by the command line tools (`stuhfl`, `stuhfl-tui`,
+//! `stuhfl-server` and `stuhfl-llrp`), so that they pick readers,
+//! configurations and tuning algorithms the same way.
+//!
+//! Internal to the workspace, not published.
+
+#![warn(missing_docs)]
+#![warn(clippy::missing_docs_in_private_items)]
+
+use libstuhfl::gen2::Gen2Cfg;
+use libstuhfl::prelude::*;
+use std::error::Error;
+use std::path::Path;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, clap::ValueEnum)]
+/// Tuning algorithms selectable on the command line, see [`TuningAlgorithm`]
+pub enum Tuning {
+    /// Quick local search
+    Fast,
+    /// Search of the whole capacitor range (slow)
+    Exact,
+    /// Local search in the best segments
+    GroupedExact,
+}
+
+impl From<Tuning> for TuningAlgorithm {
+    fn from(tuning: Tuning) -> Self {
+        match tuning {
+            Tuning::Fast => TuningAlgorithm::Fast,
+            Tuning::Exact => TuningAlgorithm::Exact,
+            Tuning::GroupedExact => TuningAlgorithm::GroupedExact,
+        }
+    }
+}
+
+/// Connects to the reader on `port` if given, else to the board with the
+/// USB serial number `serial` if given, else to the first reader found.
+pub fn connect(port: Option<&str>, serial: Option<&str>) -> libstuhfl::error::Result<Reader> {
+    match (port, serial) {
+        (Some(port), _) => Reader::connect(port),
+        (None, Some(serial)) => Reader::connect_by_serial(serial),
+        (None, None) => Reader::autoconnect(),
+    }
+}
+
+/// Loads the configuration file at `path` if given, else returns the
+/// library's default configuration.
+///
+/// Connecting resets the board, so the tools always apply one of them.
+pub fn load_config(path: Option<&Path>) -> Result<Gen2Cfg, Box<dyn Error>> {
+    Ok(match path {
+        Some(path) => Gen2Cfg::from_toml_file(path)?,
+        None => Gen2Cfg::builder().build()?,
+    })
+}
+
+/// Fixtures for the tools' unit tests
+#[cfg(feature = "test-support")]
+pub mod test_support {
+    use libstuhfl::prelude::*;
+
+    /// Tag as reported by an inventory, with the given EPC (and a matching
+    /// PC) and fixed signal values
+    pub fn tag(epc: Vec<u8>) -> InventoryTag {
+        InventoryTag {
+            slot_id: 0,
+            timestamp: 0,
+            antenna: Antenna::Antenna1,
+            agc: 0,
+            rssi_lin_i: 0,
+            rssi_lin_q: 0,
+            rssi_log_i: 6,
+            rssi_log_q: 5,
+            // EPC length in words, upper 5 bits of the PC
+            pc: [((epc.len() / 2) << 3) as u8, 0x00],
+            xpc: Xpc::from_id(Vec::new()),
+            epc: Epc::from_id(epc),
+            tid: Tid::from_id(Vec::new()),
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! Running AccessSpec operations on a tag
+//!
+//! Result codes are the ones of LLRP 1.1. On LLRP 1.0.1 connections, the
+//! codes added in 1.1 (incorrect password, memory overrun and memory
+//! locked where 1.0.1 had none) are reported as nonspecific tag errors.
+
+use crate::backend::Backend;
+use crate::codec::Bits;
+use crate::report::OpResult;
+use crate::spec::{AccessSpec, LockField, LockPrivilege, OpSpec};
+use libstuhfl::error::Error;
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+
+/// Most words read by a single C1G2Read
+pub const MAX_READ_WORDS: u16 = 32;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// Why an operation failed
+pub enum Failure {
+    /// The tag didn't answer
+    NoResponse,
+    /// The tag answered with an error not listed here
+    TagError,
+    /// The reader failed
+    ReaderError,
+    /// Address past the end of the memory bank
+    MemoryOverrun,
+    /// Memory bank or password locked
+    MemoryLocked,
+    /// The tag doesn't get enough power for the operation
+    InsufficientPower,
+    /// Wrong access password
+    IncorrectPassword,
+}
+
+impl From<&Error> for Failure {
+    fn from(e: &Error) -> Self {
+        match e {
+            Error::Gen2MemOverRun => Failure::MemoryOverrun,
+            Error::Gen2MemLocked | Error::Gen2Privileges => Failure::MemoryLocked,
+            Error::Gen2PowerShortage => Failure::InsufficientPower,
+            Error::Gen2Access => Failure::IncorrectPassword,
+            Error::ChipNoResp
+            | Error::Timeout
+            | Error::Gen2ReqRn
+            | Error::Gen2Select
+            | Error::Gen2ChannelTimeout => Failure::NoResponse,
+            Error::Gen2Other
+            | Error::Gen2NotSupported
+            | Error::Gen2Crypto
+            | Error::Gen2Encapsulation
+            | Error::Gen2RespBufOverflow
+            | Error::Gen2SecurityTimeout
+            | Error::Gen2Nonspecific
+            | Error::ChipHeader
+            | Error::ChipPreamble
+            | Error::ChipRxCount
+            | Error::ChipCrc
+            | Error::ChipColl => Failure::TagError,
+            _ => Failure::ReaderError,
+        }
+    }
+}
+
+impl Failure {
+    /// C1G2ReadOpSpecResult code
+    pub fn read_code(self, v1_1: bool) -> u8 {
+        match self {
+            Failure::TagError | Failure::InsufficientPower => 1,
+            Failure::NoResponse => 2,
+            Failure::ReaderError => 3,
+            Failure::MemoryOverrun if v1_1 => 4,
+            Failure::MemoryLocked if v1_1 => 5,
+            Failure::IncorrectPassword if v1_1 => 6,
+            _ => 1,
+        }
+    }
+
+    /// C1G2WriteOpSpecResult code
+    pub fn write_code(self, v1_1: bool) -> u8 {
+        match self {
+            Failure::MemoryOverrun => 1,
+            Failure::MemoryLocked => 2,
+            Failure::InsufficientPower => 3,
+            Failure::TagError => 4,
+            Failure::NoResponse => 5,
+            Failure::ReaderError => 6,
+            Failure::IncorrectPassword if v1_1 => 7,
+            Failure::IncorrectPassword => 4,
+        }
+    }
+
+    /// C1G2KillOpSpecResult code
+    pub fn kill_code(self, v1_1: bool) -> u8 {
+        match self {
+            Failure::InsufficientPower => 2,
+            Failure::NoResponse => 4,
+            Failure::ReaderError => 5,
+            Failure::IncorrectPassword if v1_1 => 6,
+            _ => 3,
+        }
+    }
+
+    /// C1G2LockOpSpecResult code
+    pub fn lock_code(self, v1_1: bool) -> u8 {
+        match self {
+            Failure::InsufficientPower => 1,
+            Failure::NoResponse => 3,
+            Failure::ReaderError => 4,
+            Failure::IncorrectPassword if v1_1 => 5,
+            Failure::MemoryOverrun if v1_1 => 6,
+            Failure::MemoryLocked if v1_1 => 7,
+            _ => 2,
+        }
+    }
+
+    /// Result of `op` failing this way
+    pub fn result(self, op: &OpSpec, v1_1: bool) -> OpResult {
+        match op {
+            OpSpec::Read { id, .. } => OpResult::Read {
+                id: *id,
+                result: self.read_code(v1_1),
+                data: Vec::new(),
+            },
+            OpSpec::Write { id, .. } => OpResult::Write {
+                id: *id,
+                result: self.write_code(v1_1),
+                words: 0,
+            },
+            OpSpec::Kill { id, .. } => OpResult::Kill {
+                id: *id,
+                result: self.kill_code(v1_1),
+            },
+            OpSpec::Lock { id, .. } => OpResult::Lock {
+                id: *id,
+                result: self.lock_code(v1_1),
+            },
+        }
+    }
+}
+
+/// Memory bank of an LLRP MB field
+pub fn memory_bank(bank: u8) -> MemoryBank {
+    match bank {
+        0 => MemoryBank::Reserved,
+        1 => MemoryBank::Epc,
+        2 => MemoryBank::Tid,
+        _ => MemoryBank::User,
+    }
+}
+
+/// Password of an LLRP password field, 0 meaning none
+fn password(password: u32) -> Option<Password> {
+    if password == 0 {
+        None
+    } else {
+        Some(Password::from(password.to_be_bytes()))
+    }
+}
+
+/// Known content of a tag's memory bank, as found by inventory. The CRC
+/// of the EPC bank isn't reported by the firmware and reads as zero.
+pub fn tag_memory(tag: &InventoryTag, bank: u8) -> Option<Bits> {
+    match bank {
+        1 => {
+            let mut bytes = vec![0, 0];
+            bytes.extend_from_slice(&tag.pc);
+            bytes.extend_from_slice(tag.epc.get_id());
+            Some(Bits::from_bytes(&bytes))
+        }
+        2 if !tag.tid.get_id().is_empty() => Some(Bits::from_bytes(tag.tid.get_id())),
+        _ => None,
+    }
+}
+
+/// Whether `spec` targets `tag`
+pub fn targets(spec: &AccessSpec, tag: &InventoryTag) -> bool {
+    spec.targets
+        .iter()
+        .all(|target| target.targets(tag_memory(tag, target.bank).as_ref()))
+}
+
+/// Lock settings of C1G2LockPayloads
+pub fn lock_cfg(payloads: &[(LockPrivilege, LockField)]) -> Option<Gen2LockCfg> {
+    let mut builder = Gen2LockCfg::builder();
+
+    for &(privilege, field) in payloads {
+        let action = match privilege {
+            LockPrivilege::ReadWrite => Gen2LockAction::Lock,
+            LockPrivilege::PermaLock => Gen2LockAction::PermaLock,
+            LockPrivilege::PermaUnlock => Gen2LockAction::PermaUnlock,
+            LockPrivilege::Unlock => Gen2LockAction::Unlock,
+        };
+
+        match field {
+            LockField::KillPassword => builder.kill_password(action),
+            LockField::AccessPassword => builder.access_password(action),
+            LockField::Epc => builder.epc(action),
+            LockField::Tid => builder.tid(action),
+            LockField::User => builder.user(action),
+        };
+    }
+
+    builder.build().ok()
+}
+
+/// Runs a single operation on the selected tag
+pub fn run_op<B: Backend + ?Sized>(backend: &mut B, op: &OpSpec, v1_1: bool) -> OpResult {
+    match op {
+        OpSpec::Read {
+            id,
+            password: pwd,
+            bank,
+            pointer,
+            count,
+        } => {
+            if *count == 0 || *count > MAX_READ_WORDS {
+                return Failure::ReaderError.result(op, v1_1);
+            }
+
+            let bytes = (*count * 2) as u8;
+            match backend.read(memory_bank(*bank), *pointer as u32, bytes, password(*pwd)) {
+                Ok(data) => OpResult::Read {
+                    id: *id,
+                    result: 0,
+                    data: data
+                        .chunks(2)
+                        .map(|w| u16::from_be_bytes([w[0], w.get(1).copied().unwrap_or(0)]))
+                        .collect(),
+                },
+                Err(e) => Failure::from(&e).result(op, v1_1),
+            }
+        }
+        OpSpec::Write {
+            id,
+            password: pwd,
+            bank,
+            pointer,
+            data,
+        } => {
+            for (i, word) in data.iter().enumerate() {
+                let address = *pointer as u32 + i as u32;
+
+                if let Err(e) = backend.write(
+                    memory_bank(*bank),
+                    address,
+                    word.to_be_bytes(),
+                    password(*pwd),
+                ) {
+                    return OpResult::Write {
+                        id: *id,
+                        result: Failure::from(&e).write_code(v1_1),
+                        words: i as u16,
+                    };
+                }
+            }
+
+            OpResult::Write {
+                id: *id,
+                result: 0,
+                words: data.len() as u16,
+            }
+        }
+        OpSpec::Kill { id, password: pwd } => {
+            // Tags with a zero kill password can't be killed
+            if *pwd == 0 {
+                return OpResult::Kill { id: *id, result: 1 };
+            }
+
+            match backend.kill(Password::from(pwd.to_be_bytes())) {
+                Ok(()) => OpResult::Kill { id: *id, result: 0 },
+                Err(e) => Failure::from(&e).result(op, v1_1),
+            }
+        }
+        OpSpec::Lock {
+            id,
+            password: pwd,
+            payloads,
+        } => {
+            let lock = match lock_cfg(payloads) {
+                Some(lock) => lock,
+                None => return Failure::ReaderError.result(op, v1_1),
+            };
+
+            match backend.lock(&lock, password(*pwd)) {
+                Ok(()) => OpResult::Lock { id: *id, result: 0 },
+                Err(e) => Failure::from(&e).result(op, v1_1),
+            }
+        }
+    }
+}
+
+/// Selects `tag` and runs the operations of `spec` on it, stopping at the
+/// first failure. The select list is cleared again afterwards.
+pub fn run<B: Backend + ?Sized>(
+    backend: &mut B,
+    spec: &AccessSpec,
+    tag: &InventoryTag,
+    v1_1: bool,
+) -> Vec<OpResult> {
+    if let Err(e) = backend.select(&tag.epc) {
+        return vec![Failure::from(&e).result(&spec.ops[0], v1_1)];
+    }
+
+    let mut results = Vec::new();
+    for op in &spec.ops {
+        let result = run_op(backend, op, v1_1);
+        let failed = result.result() != 0;
+
+        results.push(result);
+        if failed {
+            break;
+        }
+    }
+
+    let _ = backend.clear_select();
+
+    results
+}
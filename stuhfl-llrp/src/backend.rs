@@ -0,0 +1,222 @@
+//! Reader operations the LLRP server is built on
+
+use libstuhfl::error::{Error, Result};
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+
+/// Number of transmit power levels offered, 1 dB apart. Index 20 is the
+/// highest Tx output level (0 dB), index 1 the lowest (-19 dB).
+pub const POWER_LEVELS: u16 = 20;
+
+/// Number of antenna ports of the ST25RU3993 evaluation board
+pub const ANTENNA_PORTS: u16 = 4;
+
+/// Tx output level of a transmit power table index, `None` if the index
+/// is out of range
+pub fn tx_output_level(power_index: u16) -> Option<i8> {
+    if (1..=POWER_LEVELS).contains(&power_index) {
+        Some(power_index as i8 - POWER_LEVELS as i8)
+    } else {
+        None
+    }
+}
+
+/// LLRP AntennaID of an antenna
+pub fn antenna_id(antenna: Antenna) -> u16 {
+    antenna as u16 + 1
+}
+
+/// Antenna with an LLRP AntennaID, `None` if there is none
+pub fn antenna(id: u16) -> Option<Antenna> {
+    match id {
+        1 => Some(Antenna::Antenna1),
+        2 => Some(Antenna::Antenna2),
+        3 => Some(Antenna::Antenna3),
+        4 => Some(Antenna::Antenna4),
+        _ => None,
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// Description of the reader, reported to clients
+pub struct ReaderInfo {
+    /// Firmware version
+    pub firmware: String,
+    /// Number of antenna ports
+    pub antennas: u16,
+    /// Frequencies of the hop table (kHz)
+    pub frequencies: Vec<u32>,
+    /// Output power at the highest Tx output level (dBm)
+    pub max_power_dbm: f32,
+    /// Reader ID reported in the Identification parameter
+    pub reader_id: Vec<u8>,
+}
+
+/// Reader operations used by the LLRP server. Implemented by [`Board`]
+/// for an evaluation board, and by test doubles.
+pub trait Backend: Send {
+    /// Description of the reader
+    fn info(&self) -> &ReaderInfo;
+
+    /// Switches antenna and Tx output level (-19..=0 dB), `None` keeps the
+    /// current level
+    fn set_rf(&mut self, antenna: Antenna, tx_output_level: Option<i8>) -> Result<()>;
+
+    /// Runs a single inventory round
+    fn inventory(&mut self) -> Result<(InventoryStatistics, Vec<InventoryTag>)>;
+
+    /// Selects the tag the following commands address
+    fn select(&mut self, epc: &Epc) -> Result<()>;
+
+    /// Addresses any tag again, see [`Gen2Reader::clear_select()`]
+    fn clear_select(&mut self) -> Result<()>;
+
+    /// Reads tag memory, see [`ProtocolCommands::read()`]
+    fn read(
+        &mut self,
+        bank: MemoryBank,
+        word_address: u32,
+        num_bytes: u8,
+        password: Option<Password>,
+    ) -> Result<Vec<u8>>;
+
+    /// Writes a word of tag memory, see [`ProtocolCommands::write()`]
+    fn write(
+        &mut self,
+        bank: MemoryBank,
+        word_address: u32,
+        data: [u8; 2],
+        password: Option<Password>,
+    ) -> Result<()>;
+
+    /// Changes the lock state of the tag, see [`Gen2Reader::lock()`]
+    fn lock(&mut self, lock: &Gen2LockCfg, password: Option<Password>) -> Result<()>;
+
+    /// Kills the tag, see [`Gen2Reader::kill()`]
+    fn kill(&mut self, kill_password: Password) -> Result<()>;
+}
+
+/// An evaluation board, reconfigured and tuned again whenever an ROSpec
+/// changes antenna or transmit power. If the board rejects the new
+/// settings, the previous ones are applied and tuned again.
+pub struct Board {
+    /// The reader, `None` if it was lost while changing the configuration
+    reader: Option<Gen2Reader>,
+    /// Last configuration the reader accepted
+    cfg: Gen2Cfg,
+    /// Tuning algorithm run after each configuration change
+    tuning: TuningAlgorithm,
+    /// Description of the reader
+    info: ReaderInfo,
+}
+
+impl Board {
+    /// Wraps a configured and tuned reader. `max_power_dbm` is the output
+    /// power at the highest Tx output level, which depends on the board.
+    pub fn new(
+        reader: Gen2Reader,
+        tuning: TuningAlgorithm,
+        max_power_dbm: f32,
+        reader_id: Vec<u8>,
+    ) -> Result<Self> {
+        let cfg = reader.current_config()?;
+        let channels: Vec<ChannelItem> = reader.get_channel_list()?.into();
+
+        let info = ReaderInfo {
+            firmware: reader.get_version()?.sw_ver.to_string(),
+            antennas: ANTENNA_PORTS,
+            frequencies: channels.iter().map(|c| c.frequency()).collect(),
+            max_power_dbm,
+            reader_id,
+        };
+
+        Ok(Self {
+            reader: Some(reader),
+            cfg,
+            tuning,
+            info,
+        })
+    }
+
+    /// The reader, if it's still usable
+    fn reader(&mut self) -> Result<&mut Gen2Reader> {
+        self.reader.as_mut().ok_or(Error::Generic)
+    }
+}
+
+impl Backend for Board {
+    fn info(&self) -> &ReaderInfo {
+        &self.info
+    }
+
+    fn set_rf(&mut self, antenna: Antenna, tx_output_level: Option<i8>) -> Result<()> {
+        let mut cfg = self.cfg.with_antenna(antenna);
+        if let Some(level) = tx_output_level {
+            cfg = cfg.with_tx_output_level(level)?;
+        }
+
+        if cfg == self.cfg {
+            return Ok(());
+        }
+
+        let reader = self.reader.as_mut().ok_or(Error::Generic)?;
+
+        if let Err(e) = reader.reconfigure(&cfg) {
+            // Go back to the configuration that worked, the reader is only
+            // given up if that fails as well
+            let restored = reader
+                .reconfigure(&self.cfg)
+                .and_then(|()| reader.tune(self.tuning));
+
+            if restored.is_err() {
+                self.reader = None;
+            }
+
+            return Err(e);
+        }
+
+        self.cfg = cfg;
+
+        reader.tune(self.tuning)
+    }
+
+    fn inventory(&mut self) -> Result<(InventoryStatistics, Vec<InventoryTag>)> {
+        self.reader()?.inventory_once()
+    }
+
+    fn select(&mut self, epc: &Epc) -> Result<()> {
+        self.reader()?.select(epc)
+    }
+
+    fn clear_select(&mut self) -> Result<()> {
+        self.reader()?.clear_select()
+    }
+
+    fn read(
+        &mut self,
+        bank: MemoryBank,
+        word_address: u32,
+        num_bytes: u8,
+        password: Option<Password>,
+    ) -> Result<Vec<u8>> {
+        self.reader()?.read(bank, word_address, num_bytes, password)
+    }
+
+    fn write(
+        &mut self,
+        bank: MemoryBank,
+        word_address: u32,
+        data: [u8; 2],
+        password: Option<Password>,
+    ) -> Result<()> {
+        self.reader()?.write(bank, word_address, data, password)
+    }
+
+    fn lock(&mut self, lock: &Gen2LockCfg, password: Option<Password>) -> Result<()> {
+        self.reader()?.lock(lock, password)
+    }
+
+    fn kill(&mut self, kill_password: Password) -> Result<()> {
+        self.reader()?.kill(kill_password, None)
+    }
+}
@@ -0,0 +1,161 @@
+//! GET_READER_CAPABILITIES_RESPONSE parameters
+
+use crate::backend::{ReaderInfo, POWER_LEVELS};
+use crate::codec::{param, Encoder, LlrpError, Result, StatusCode};
+use crate::spec::PROTOCOL_C1G2;
+
+/// Most ROSpecs added at a time
+pub const MAX_ROSPECS: usize = 32;
+/// Most AISpecs per ROSpec
+pub const MAX_SPECS_PER_ROSPEC: usize = 8;
+/// Most InventoryParameterSpecs per AISpec
+pub const MAX_INVENTORY_SPECS_PER_AISPEC: usize = 8;
+/// Most AccessSpecs added at a time
+pub const MAX_ACCESS_SPECS: usize = 32;
+/// Most OpSpecs per AccessSpec
+pub const MAX_OPSPECS_PER_ACCESS_SPEC: usize = 8;
+
+/// DeviceManufacturerName, no IANA enterprise number is registered for the
+/// evaluation board
+const MANUFACTURER: u32 = 0;
+/// ModelName
+const MODEL: u32 = 3993;
+
+/// Encodes the capabilities selected by RequestedData (0 for all, 1 for
+/// general device, 2 for LLRP, 3 for regulatory, 4 for air protocol
+/// capabilities)
+pub fn encode(info: &ReaderInfo, requested: u8, e: &mut Encoder) -> Result<()> {
+    if requested > 4 {
+        return Err(LlrpError::new(
+            StatusCode::FieldError,
+            format!("unsupported RequestedData {}", requested),
+        ));
+    }
+
+    let selected = |data: u8| requested == 0 || requested == data;
+
+    if selected(1) {
+        general(info, e);
+    }
+    if selected(2) {
+        llrp(e);
+    }
+    if selected(3) {
+        regulatory(info, e);
+    }
+    if selected(4) {
+        e.param(param::C1G2_LLRP_CAPABILITIES, |e| {
+            e.u8(0).u16(0);
+        });
+    }
+
+    Ok(())
+}
+
+/// GeneralDeviceCapabilities
+fn general(info: &ReaderInfo, e: &mut Encoder) {
+    e.param(param::GENERAL_DEVICE_CAPABILITIES, |e| {
+        // HasUTCClockCapability, antenna properties can't be changed
+        e.u16(info.antennas)
+            .u16(0x4000)
+            .u32(MANUFACTURER)
+            .u32(MODEL)
+            .utf8(&info.firmware);
+
+        e.param(param::RECEIVE_SENSITIVITY_TABLE_ENTRY, |e| {
+            e.u16(1).i16(0);
+        });
+        e.param(param::GPIO_CAPABILITIES, |e| {
+            e.u16(0).u16(0);
+        });
+
+        for antenna in 1..=info.antennas {
+            e.param(param::PER_ANTENNA_AIR_PROTOCOL, |e| {
+                e.u16(antenna).u8_array(&[PROTOCOL_C1G2]);
+            });
+        }
+    });
+}
+
+/// LLRPCapabilities
+fn llrp(e: &mut Encoder) {
+    e.param(param::LLRP_CAPABILITIES, |e| {
+        // No RF survey, buffer warnings, client OpSpecs or report holding,
+        // a single priority level
+        e.u8(0)
+            .u8(1)
+            .u16(0)
+            .u32(MAX_ROSPECS as u32)
+            .u32(MAX_SPECS_PER_ROSPEC as u32)
+            .u32(MAX_INVENTORY_SPECS_PER_AISPEC as u32)
+            .u32(MAX_ACCESS_SPECS as u32)
+            .u32(MAX_OPSPECS_PER_ACCESS_SPEC as u32);
+    });
+}
+
+/// RegulatoryCapabilities
+fn regulatory(info: &ReaderInfo, e: &mut Encoder) {
+    e.param(param::REGULATORY_CAPABILITIES, |e| {
+        // Country and communications standard unspecified
+        e.u16(0).u16(0);
+
+        e.param(param::UHF_BAND_CAPABILITIES, |e| {
+            for index in 1..=POWER_LEVELS {
+                let dbm = info.max_power_dbm - (POWER_LEVELS - index) as f32;
+
+                e.param(param::TRANSMIT_POWER_LEVEL_TABLE_ENTRY, |e| {
+                    e.u16(index).i16((dbm * 100.0).round() as i16);
+                });
+            }
+
+            frequencies(info, e);
+            rf_modes(e);
+        });
+    });
+}
+
+/// FrequencyInformation, a hop table of the channel list or a fixed
+/// frequency if it has a single channel
+fn frequencies(info: &ReaderInfo, e: &mut Encoder) {
+    let hopping = info.frequencies.len() > 1;
+
+    e.param(param::FREQUENCY_INFORMATION, |e| {
+        e.u8(if hopping { 0x80 } else { 0 });
+
+        if hopping {
+            e.param(param::FREQUENCY_HOP_TABLE, |e| {
+                e.u8(1).u8(0).u16(info.frequencies.len() as u16);
+                for &frequency in &info.frequencies {
+                    e.u32(frequency);
+                }
+            });
+        } else {
+            e.param(param::FIXED_FREQUENCY_TABLE, |e| {
+                e.u16(info.frequencies.len() as u16);
+                for &frequency in &info.frequencies {
+                    e.u32(frequency);
+                }
+            });
+        }
+    });
+}
+
+/// UHF_C1G2_RFModeTable with a single mode, libstuhfl's default protocol
+/// settings (Tari 6.25 µs, BLF 320 kHz, Miller 2)
+fn rf_modes(e: &mut Encoder) {
+    e.param(param::UHF_C1G2_RF_MODE_TABLE, |e| {
+        e.param(param::UHF_C1G2_RF_MODE_TABLE_ENTRY, |e| {
+            // Mode 0, DR 64/3, M = 2, PR-ASK, spectral mask unknown
+            e.u32(0)
+                .u8(0x80)
+                .u8(1)
+                .u8(0)
+                .u8(0)
+                .u32(160_000)
+                .u32(1500)
+                .u32(6250)
+                .u32(6250)
+                .u32(0);
+        });
+    });
+}
@@ -0,0 +1,253 @@
+//! Minimal LLRP client, used to test the server and to script readers
+//!
+//! # Example
+//!
+//! ```no_run
+//! use stuhfl_llrp::client::Client;
+//! use stuhfl_llrp::spec::*;
+//! # fn main() -> std::io::Result<()> {
+//!
+//! let mut client = Client::connect("127.0.0.1:5084")?;
+//! client.events()?; // ConnectionAttemptEvent
+//!
+//! client.add_rospec(&RoSpec {
+//!     id: 1,
+//!     priority: 0,
+//!     state: RoSpecState::Disabled,
+//!     start: StartTrigger::Null,
+//!     stop: RoStopTrigger::Duration(1000),
+//!     ai_specs: vec![AiSpec {
+//!         antennas: vec![0],
+//!         stop: AiStopTrigger::Null,
+//!         inventory: vec![InventorySpec {
+//!             id: 1,
+//!             protocol: PROTOCOL_C1G2,
+//!             antennas: Vec::new(),
+//!         }],
+//!     }],
+//!     report: None,
+//! })?;
+//! client.enable_rospec(1)?;
+//! client.start_rospec(1)?;
+//!
+//! for tag in client.reports()? {
+//!     println!("{:02X?} seen {:?} times", tag.epc.bytes, tag.seen_count);
+//! }
+//!
+//! client.close()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::codec::{self, message, Encoder, LlrpError, Message, StatusCode, VERSION_1_0_1};
+use crate::report::{self, ReaderEvent, TagReport};
+use crate::spec::{AccessSpec, RoSpec};
+use std::collections::VecDeque;
+use std::io;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Converts a protocol error to an I/O error
+fn invalid(e: LlrpError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// A connection to an LLRP reader
+pub struct Client {
+    /// Connection to the reader
+    stream: TcpStream,
+    /// Protocol version of the messages sent
+    version: u8,
+    /// ID of the last message sent
+    message_id: u32,
+    /// Messages received while waiting for another one
+    received: VecDeque<Message>,
+}
+
+impl Client {
+    /// Connects to a reader
+    pub fn connect<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+
+        Ok(Self {
+            stream,
+            version: VERSION_1_0_1,
+            message_id: 0,
+            received: VecDeque::new(),
+        })
+    }
+
+    /// Sets the protocol version of the messages sent. Negotiate it with
+    /// SET_PROTOCOL_VERSION first.
+    pub fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+
+    /// Sets how long receiving waits for a message, `None` for no limit
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.stream.set_read_timeout(timeout)
+    }
+
+    /// Address of the reader
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.stream.peer_addr()
+    }
+
+    /// Sends a message, returns its message ID
+    pub fn send(&mut self, kind: u16, body: Vec<u8>) -> io::Result<u32> {
+        self.message_id = self.message_id.wrapping_add(1);
+
+        Message::new(self.version, kind, self.message_id, body).write_to(&mut self.stream)?;
+
+        Ok(self.message_id)
+    }
+
+    /// Receives the next message
+    pub fn recv(&mut self) -> io::Result<Message> {
+        match self.received.pop_front() {
+            Some(message) => Ok(message),
+            None => Message::read_from(&mut self.stream),
+        }
+    }
+
+    /// Receives the next message matching `wanted`, keeping the other
+    /// messages for later
+    fn recv_matching(&mut self, wanted: impl Fn(&Message) -> bool) -> io::Result<Message> {
+        if let Some(index) = self.received.iter().position(&wanted) {
+            return Ok(self.received.remove(index).unwrap());
+        }
+
+        loop {
+            let message = Message::read_from(&mut self.stream)?;
+
+            if wanted(&message) {
+                return Ok(message);
+            }
+            self.received.push_back(message);
+        }
+    }
+
+    /// Receives the next message of type `kind`, keeping the other
+    /// messages for later
+    pub fn recv_kind(&mut self, kind: u16) -> io::Result<Message> {
+        self.recv_matching(|message| message.kind == kind)
+    }
+
+    /// Sends a request and waits for its response, or for the
+    /// ERROR_MESSAGE answering it
+    pub fn request(&mut self, kind: u16, body: Vec<u8>) -> io::Result<Message> {
+        let id = self.send(kind, body)?;
+        let response = message::response(kind);
+
+        self.recv_matching(|message| {
+            message.id == id
+                && (Some(message.kind) == response || message.kind == message::ERROR_MESSAGE)
+        })
+    }
+
+    /// Sends a request and checks that it succeeded. Not suited to
+    /// GET_SUPPORTED_VERSION, whose response has fields before its status.
+    pub fn transact(&mut self, kind: u16, body: Vec<u8>) -> io::Result<Message> {
+        let response = self.request(kind, body)?;
+        let (status, description) =
+            codec::decode_status(&mut response.decoder()).map_err(invalid)?;
+
+        if status != StatusCode::Success as u16 {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::ParameterError);
+            return Err(invalid(LlrpError::new(status, description)));
+        }
+
+        Ok(response)
+    }
+
+    /// ADD_ROSPEC
+    pub fn add_rospec(&mut self, rospec: &RoSpec) -> io::Result<()> {
+        let mut e = Encoder::new();
+        rospec.encode(&mut e);
+
+        self.transact(message::ADD_ROSPEC, e.into_bytes())?;
+        Ok(())
+    }
+
+    /// Sends a message addressing a spec by ID
+    fn spec_request(&mut self, kind: u16, id: u32) -> io::Result<()> {
+        self.transact(kind, id.to_be_bytes().to_vec())?;
+        Ok(())
+    }
+
+    /// ENABLE_ROSPEC
+    pub fn enable_rospec(&mut self, id: u32) -> io::Result<()> {
+        self.spec_request(message::ENABLE_ROSPEC, id)
+    }
+
+    /// START_ROSPEC
+    pub fn start_rospec(&mut self, id: u32) -> io::Result<()> {
+        self.spec_request(message::START_ROSPEC, id)
+    }
+
+    /// STOP_ROSPEC
+    pub fn stop_rospec(&mut self, id: u32) -> io::Result<()> {
+        self.spec_request(message::STOP_ROSPEC, id)
+    }
+
+    /// DELETE_ROSPEC, 0 deleting all ROSpecs
+    pub fn delete_rospec(&mut self, id: u32) -> io::Result<()> {
+        self.spec_request(message::DELETE_ROSPEC, id)
+    }
+
+    /// ADD_ACCESSSPEC
+    pub fn add_access_spec(&mut self, spec: &AccessSpec) -> io::Result<()> {
+        let mut e = Encoder::new();
+        spec.encode(&mut e);
+
+        self.transact(message::ADD_ACCESSSPEC, e.into_bytes())?;
+        Ok(())
+    }
+
+    /// ENABLE_ACCESSSPEC
+    pub fn enable_access_spec(&mut self, id: u32) -> io::Result<()> {
+        self.spec_request(message::ENABLE_ACCESSSPEC, id)
+    }
+
+    /// DELETE_ACCESSSPEC, 0 deleting all AccessSpecs
+    pub fn delete_access_spec(&mut self, id: u32) -> io::Result<()> {
+        self.spec_request(message::DELETE_ACCESSSPEC, id)
+    }
+
+    /// GET_ACCESSSPECS
+    pub fn access_specs(&mut self) -> io::Result<Vec<AccessSpec>> {
+        let response = self.transact(message::GET_ACCESSSPECS, Vec::new())?;
+        let mut d = response.decoder();
+
+        d.required(codec::param::LLRP_STATUS).map_err(invalid)?;
+        d.repeated(codec::param::ACCESS_SPEC)
+            .and_then(|specs| {
+                specs
+                    .into_iter()
+                    .map(|mut s| AccessSpec::decode(&mut s))
+                    .collect()
+            })
+            .map_err(invalid)
+    }
+
+    /// Waits for the next RO_ACCESS_REPORT
+    pub fn reports(&mut self) -> io::Result<Vec<TagReport>> {
+        let message = self.recv_kind(message::RO_ACCESS_REPORT)?;
+
+        report::decode_report(&message.body).map_err(invalid)
+    }
+
+    /// Waits for the next READER_EVENT_NOTIFICATION
+    pub fn events(&mut self) -> io::Result<Vec<ReaderEvent>> {
+        let message = self.recv_kind(message::READER_EVENT_NOTIFICATION)?;
+
+        ReaderEvent::decode(&message.body).map_err(invalid)
+    }
+
+    /// Closes the connection with CLOSE_CONNECTION
+    pub fn close(mut self) -> io::Result<()> {
+        self.transact(message::CLOSE_CONNECTION, Vec::new())?;
+        Ok(())
+    }
+}
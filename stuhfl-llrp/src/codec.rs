@@ -0,0 +1,760 @@
+//! LLRP binary encoding: message framing, TLV and TV parameters
+//!
+//! All values are big endian. A message starts with a 10 byte header
+//! (version, message type, length, message ID), followed by its fields and
+//! parameters. TLV parameters have a 4 byte header (type, length), TV
+//! parameters a 1 byte type followed by a value of fixed length.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// LLRP 1.0.1
+pub const VERSION_1_0_1: u8 = 1;
+/// LLRP 1.1
+pub const VERSION_1_1: u8 = 2;
+
+/// Length of a message header
+const HEADER_LEN: usize = 10;
+/// Length of a TLV parameter header
+const TLV_HEADER_LEN: usize = 4;
+/// Longest message accepted from a peer
+const MAX_MESSAGE_LEN: usize = 1 << 20;
+
+/// Message types
+pub mod message {
+    /// GET_READER_CAPABILITIES
+    pub const GET_READER_CAPABILITIES: u16 = 1;
+    /// GET_READER_CONFIG
+    pub const GET_READER_CONFIG: u16 = 2;
+    /// SET_READER_CONFIG
+    pub const SET_READER_CONFIG: u16 = 3;
+    /// CLOSE_CONNECTION_RESPONSE
+    pub const CLOSE_CONNECTION_RESPONSE: u16 = 4;
+    /// GET_READER_CAPABILITIES_RESPONSE
+    pub const GET_READER_CAPABILITIES_RESPONSE: u16 = 11;
+    /// GET_READER_CONFIG_RESPONSE
+    pub const GET_READER_CONFIG_RESPONSE: u16 = 12;
+    /// SET_READER_CONFIG_RESPONSE
+    pub const SET_READER_CONFIG_RESPONSE: u16 = 13;
+    /// CLOSE_CONNECTION
+    pub const CLOSE_CONNECTION: u16 = 14;
+    /// ADD_ROSPEC
+    pub const ADD_ROSPEC: u16 = 20;
+    /// DELETE_ROSPEC
+    pub const DELETE_ROSPEC: u16 = 21;
+    /// START_ROSPEC
+    pub const START_ROSPEC: u16 = 22;
+    /// STOP_ROSPEC
+    pub const STOP_ROSPEC: u16 = 23;
+    /// ENABLE_ROSPEC
+    pub const ENABLE_ROSPEC: u16 = 24;
+    /// DISABLE_ROSPEC
+    pub const DISABLE_ROSPEC: u16 = 25;
+    /// GET_ROSPECS
+    pub const GET_ROSPECS: u16 = 26;
+    /// ADD_ROSPEC_RESPONSE
+    pub const ADD_ROSPEC_RESPONSE: u16 = 30;
+    /// DELETE_ROSPEC_RESPONSE
+    pub const DELETE_ROSPEC_RESPONSE: u16 = 31;
+    /// START_ROSPEC_RESPONSE
+    pub const START_ROSPEC_RESPONSE: u16 = 32;
+    /// STOP_ROSPEC_RESPONSE
+    pub const STOP_ROSPEC_RESPONSE: u16 = 33;
+    /// ENABLE_ROSPEC_RESPONSE
+    pub const ENABLE_ROSPEC_RESPONSE: u16 = 34;
+    /// DISABLE_ROSPEC_RESPONSE
+    pub const DISABLE_ROSPEC_RESPONSE: u16 = 35;
+    /// GET_ROSPECS_RESPONSE
+    pub const GET_ROSPECS_RESPONSE: u16 = 36;
+    /// ADD_ACCESSSPEC
+    pub const ADD_ACCESSSPEC: u16 = 40;
+    /// DELETE_ACCESSSPEC
+    pub const DELETE_ACCESSSPEC: u16 = 41;
+    /// ENABLE_ACCESSSPEC
+    pub const ENABLE_ACCESSSPEC: u16 = 42;
+    /// DISABLE_ACCESSSPEC
+    pub const DISABLE_ACCESSSPEC: u16 = 43;
+    /// GET_ACCESSSPECS
+    pub const GET_ACCESSSPECS: u16 = 44;
+    /// GET_SUPPORTED_VERSION
+    pub const GET_SUPPORTED_VERSION: u16 = 46;
+    /// SET_PROTOCOL_VERSION
+    pub const SET_PROTOCOL_VERSION: u16 = 47;
+    /// ADD_ACCESSSPEC_RESPONSE
+    pub const ADD_ACCESSSPEC_RESPONSE: u16 = 50;
+    /// DELETE_ACCESSSPEC_RESPONSE
+    pub const DELETE_ACCESSSPEC_RESPONSE: u16 = 51;
+    /// ENABLE_ACCESSSPEC_RESPONSE
+    pub const ENABLE_ACCESSSPEC_RESPONSE: u16 = 52;
+    /// DISABLE_ACCESSSPEC_RESPONSE
+    pub const DISABLE_ACCESSSPEC_RESPONSE: u16 = 53;
+    /// GET_ACCESSSPECS_RESPONSE
+    pub const GET_ACCESSSPECS_RESPONSE: u16 = 54;
+    /// GET_SUPPORTED_VERSION_RESPONSE
+    pub const GET_SUPPORTED_VERSION_RESPONSE: u16 = 56;
+    /// SET_PROTOCOL_VERSION_RESPONSE
+    pub const SET_PROTOCOL_VERSION_RESPONSE: u16 = 57;
+    /// GET_REPORT
+    pub const GET_REPORT: u16 = 60;
+    /// RO_ACCESS_REPORT
+    pub const RO_ACCESS_REPORT: u16 = 61;
+    /// KEEPALIVE
+    pub const KEEPALIVE: u16 = 62;
+    /// READER_EVENT_NOTIFICATION
+    pub const READER_EVENT_NOTIFICATION: u16 = 63;
+    /// ENABLE_EVENTS_AND_REPORTS
+    pub const ENABLE_EVENTS_AND_REPORTS: u16 = 64;
+    /// KEEPALIVE_ACK
+    pub const KEEPALIVE_ACK: u16 = 72;
+    /// ERROR_MESSAGE
+    pub const ERROR_MESSAGE: u16 = 100;
+
+    /// Type of the response to a request, `None` for messages without
+    /// a response
+    pub fn response(request: u16) -> Option<u16> {
+        match request {
+            CLOSE_CONNECTION => Some(CLOSE_CONNECTION_RESPONSE),
+            GET_READER_CAPABILITIES | GET_READER_CONFIG | SET_READER_CONFIG => Some(request + 10),
+            ADD_ROSPEC..=GET_ROSPECS => Some(request + 10),
+            ADD_ACCESSSPEC..=GET_ACCESSSPECS => Some(request + 10),
+            GET_SUPPORTED_VERSION | SET_PROTOCOL_VERSION => Some(request + 10),
+            _ => None,
+        }
+    }
+}
+
+/// Parameter types
+pub mod param {
+    /// AntennaID (TV)
+    pub const ANTENNA_ID: u16 = 1;
+    /// FirstSeenTimestampUTC (TV)
+    pub const FIRST_SEEN_UTC: u16 = 2;
+    /// LastSeenTimestampUTC (TV)
+    pub const LAST_SEEN_UTC: u16 = 4;
+    /// PeakRSSI (TV)
+    pub const PEAK_RSSI: u16 = 6;
+    /// ChannelIndex (TV)
+    pub const CHANNEL_INDEX: u16 = 7;
+    /// TagSeenCount (TV)
+    pub const TAG_SEEN_COUNT: u16 = 8;
+    /// ROSpecID (TV)
+    pub const ROSPEC_ID: u16 = 9;
+    /// InventoryParameterSpecID (TV)
+    pub const INVENTORY_PARAMETER_SPEC_ID: u16 = 10;
+    /// C1G2_PC (TV)
+    pub const C1G2_PC: u16 = 12;
+    /// EPC-96 (TV)
+    pub const EPC_96: u16 = 13;
+    /// SpecIndex (TV)
+    pub const SPEC_INDEX: u16 = 14;
+    /// AccessSpecID (TV)
+    pub const ACCESS_SPEC_ID: u16 = 16;
+    /// UTCTimestamp
+    pub const UTC_TIMESTAMP: u16 = 128;
+    /// GeneralDeviceCapabilities
+    pub const GENERAL_DEVICE_CAPABILITIES: u16 = 137;
+    /// ReceiveSensitivityTableEntry
+    pub const RECEIVE_SENSITIVITY_TABLE_ENTRY: u16 = 139;
+    /// PerAntennaAirProtocol
+    pub const PER_ANTENNA_AIR_PROTOCOL: u16 = 140;
+    /// GPIOCapabilities
+    pub const GPIO_CAPABILITIES: u16 = 141;
+    /// LLRPCapabilities
+    pub const LLRP_CAPABILITIES: u16 = 142;
+    /// RegulatoryCapabilities
+    pub const REGULATORY_CAPABILITIES: u16 = 143;
+    /// UHFBandCapabilities
+    pub const UHF_BAND_CAPABILITIES: u16 = 144;
+    /// TransmitPowerLevelTableEntry
+    pub const TRANSMIT_POWER_LEVEL_TABLE_ENTRY: u16 = 145;
+    /// FrequencyInformation
+    pub const FREQUENCY_INFORMATION: u16 = 146;
+    /// FrequencyHopTable
+    pub const FREQUENCY_HOP_TABLE: u16 = 147;
+    /// FixedFrequencyTable
+    pub const FIXED_FREQUENCY_TABLE: u16 = 148;
+    /// ROSpec
+    pub const ROSPEC: u16 = 177;
+    /// ROBoundarySpec
+    pub const RO_BOUNDARY_SPEC: u16 = 178;
+    /// ROSpecStartTrigger
+    pub const ROSPEC_START_TRIGGER: u16 = 179;
+    /// ROSpecStopTrigger
+    pub const ROSPEC_STOP_TRIGGER: u16 = 182;
+    /// AISpec
+    pub const AI_SPEC: u16 = 183;
+    /// AISpecStopTrigger
+    pub const AI_SPEC_STOP_TRIGGER: u16 = 184;
+    /// TagObservationTrigger
+    pub const TAG_OBSERVATION_TRIGGER: u16 = 185;
+    /// InventoryParameterSpec
+    pub const INVENTORY_PARAMETER_SPEC: u16 = 186;
+    /// AccessSpec
+    pub const ACCESS_SPEC: u16 = 207;
+    /// AccessSpecStopTrigger
+    pub const ACCESS_SPEC_STOP_TRIGGER: u16 = 208;
+    /// AccessCommand
+    pub const ACCESS_COMMAND: u16 = 209;
+    /// Identification
+    pub const IDENTIFICATION: u16 = 218;
+    /// KeepaliveSpec
+    pub const KEEPALIVE_SPEC: u16 = 220;
+    /// AntennaProperties
+    pub const ANTENNA_PROPERTIES: u16 = 221;
+    /// AntennaConfiguration
+    pub const ANTENNA_CONFIGURATION: u16 = 222;
+    /// RFTransmitter
+    pub const RF_TRANSMITTER: u16 = 224;
+    /// EventsAndReports
+    pub const EVENTS_AND_REPORTS: u16 = 226;
+    /// ROReportSpec
+    pub const RO_REPORT_SPEC: u16 = 237;
+    /// TagReportContentSelector
+    pub const TAG_REPORT_CONTENT_SELECTOR: u16 = 238;
+    /// AccessReportSpec
+    pub const ACCESS_REPORT_SPEC: u16 = 239;
+    /// TagReportData
+    pub const TAG_REPORT_DATA: u16 = 240;
+    /// EPCData
+    pub const EPC_DATA: u16 = 241;
+    /// ReaderEventNotificationSpec
+    pub const READER_EVENT_NOTIFICATION_SPEC: u16 = 244;
+    /// EventNotificationState
+    pub const EVENT_NOTIFICATION_STATE: u16 = 245;
+    /// ReaderEventNotificationData
+    pub const READER_EVENT_NOTIFICATION_DATA: u16 = 246;
+    /// ROSpecEvent
+    pub const ROSPEC_EVENT: u16 = 249;
+    /// ReaderExceptionEvent
+    pub const READER_EXCEPTION_EVENT: u16 = 252;
+    /// ConnectionAttemptEvent
+    pub const CONNECTION_ATTEMPT_EVENT: u16 = 256;
+    /// ConnectionCloseEvent
+    pub const CONNECTION_CLOSE_EVENT: u16 = 257;
+    /// LLRPStatus
+    pub const LLRP_STATUS: u16 = 287;
+    /// C1G2LLRPCapabilities
+    pub const C1G2_LLRP_CAPABILITIES: u16 = 327;
+    /// UHF_C1G2_RFModeTable
+    pub const UHF_C1G2_RF_MODE_TABLE: u16 = 328;
+    /// UHF_C1G2_RFModeTableEntry
+    pub const UHF_C1G2_RF_MODE_TABLE_ENTRY: u16 = 329;
+    /// C1G2TagSpec
+    pub const C1G2_TAG_SPEC: u16 = 338;
+    /// C1G2TargetTag
+    pub const C1G2_TARGET_TAG: u16 = 339;
+    /// C1G2Read
+    pub const C1G2_READ: u16 = 341;
+    /// C1G2Write
+    pub const C1G2_WRITE: u16 = 342;
+    /// C1G2Kill
+    pub const C1G2_KILL: u16 = 343;
+    /// C1G2Lock
+    pub const C1G2_LOCK: u16 = 344;
+    /// C1G2LockPayload
+    pub const C1G2_LOCK_PAYLOAD: u16 = 345;
+    /// C1G2EPCMemorySelector
+    pub const C1G2_EPC_MEMORY_SELECTOR: u16 = 348;
+    /// C1G2ReadOpSpecResult
+    pub const C1G2_READ_OP_SPEC_RESULT: u16 = 349;
+    /// C1G2WriteOpSpecResult
+    pub const C1G2_WRITE_OP_SPEC_RESULT: u16 = 350;
+    /// C1G2KillOpSpecResult
+    pub const C1G2_KILL_OP_SPEC_RESULT: u16 = 351;
+    /// C1G2LockOpSpecResult
+    pub const C1G2_LOCK_OP_SPEC_RESULT: u16 = 352;
+
+    /// Length of the value of a TV parameter, `None` if the type isn't
+    /// a TV parameter
+    pub fn tv_len(kind: u16) -> Option<usize> {
+        match kind {
+            6 => Some(1),
+            1 | 7 | 8 | 10 | 11 | 12 | 14 | 15 | 17 | 19 | 20 => Some(2),
+            9 | 16 | 18 => Some(4),
+            2..=5 => Some(8),
+            13 => Some(12),
+            _ => None,
+        }
+    }
+}
+
+#[repr(u16)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// Status codes of an LLRPStatus parameter
+pub enum StatusCode {
+    /// M_Success
+    Success = 0,
+    /// M_ParameterError
+    ParameterError = 100,
+    /// M_FieldError
+    FieldError = 101,
+    /// M_UnexpectedParameter
+    UnexpectedParameter = 102,
+    /// M_MissingParameter
+    MissingParameter = 103,
+    /// M_OverflowParameter
+    OverflowParameter = 105,
+    /// M_UnknownParameter
+    UnknownParameter = 107,
+    /// M_UnsupportedMessage
+    UnsupportedMessage = 109,
+    /// M_UnsupportedVersion
+    UnsupportedVersion = 110,
+    /// M_UnsupportedParameter
+    UnsupportedParameter = 111,
+    /// M_UnexpectedMessage
+    UnexpectedMessage = 112,
+    /// A_Invalid
+    Invalid = 300,
+    /// A_OutOfRange
+    OutOfRange = 301,
+    /// R_DeviceError
+    DeviceError = 401,
+}
+
+impl StatusCode {
+    /// Status code with the given value, `None` if it isn't used here
+    pub fn from_u16(value: u16) -> Option<Self> {
+        use StatusCode::*;
+
+        [
+            Success,
+            ParameterError,
+            FieldError,
+            UnexpectedParameter,
+            MissingParameter,
+            OverflowParameter,
+            UnknownParameter,
+            UnsupportedMessage,
+            UnsupportedVersion,
+            UnsupportedParameter,
+            UnexpectedMessage,
+            Invalid,
+            OutOfRange,
+            DeviceError,
+        ]
+        .into_iter()
+        .find(|&code| code as u16 == value)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// A request that can't be handled, reported to the client in an
+/// LLRPStatus parameter
+pub struct LlrpError {
+    /// Status code sent to the client
+    pub status: StatusCode,
+    /// Description sent to the client
+    pub message: String,
+}
+
+impl LlrpError {
+    /// Creates an error with a status code and description
+    pub fn new<S: Into<String>>(status: StatusCode, message: S) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for LlrpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({:?})", self.message, self.status)
+    }
+}
+
+impl std::error::Error for LlrpError {}
+
+/// Result of decoding or handling a request
+pub type Result<T> = core::result::Result<T, LlrpError>;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// An LLRP message
+pub struct Message {
+    /// Protocol version, [`VERSION_1_0_1`] or [`VERSION_1_1`]
+    pub version: u8,
+    /// Message type, see [`message`]
+    pub kind: u16,
+    /// Message ID, responses carry the ID of their request
+    pub id: u32,
+    /// Fields and parameters
+    pub body: Vec<u8>,
+}
+
+impl Message {
+    /// Creates a message
+    pub fn new(version: u8, kind: u16, id: u32, body: Vec<u8>) -> Self {
+        Self {
+            version,
+            kind,
+            id,
+            body,
+        }
+    }
+
+    /// Reads the next message. Fails with [`io::ErrorKind::InvalidData`]
+    /// if the length in the header is out of range, as the stream can't be
+    /// resynchronized afterwards.
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut header = [0; HEADER_LEN];
+        reader.read_exact(&mut header)?;
+
+        let kind = u16::from_be_bytes([header[0], header[1]]);
+        let len = u32::from_be_bytes([header[2], header[3], header[4], header[5]]) as usize;
+        let id = u32::from_be_bytes([header[6], header[7], header[8], header[9]]);
+
+        if !(HEADER_LEN..=MAX_MESSAGE_LEN).contains(&len) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid message length {}", len),
+            ));
+        }
+
+        let mut body = vec![0; len - HEADER_LEN];
+        reader.read_exact(&mut body)?;
+
+        Ok(Self {
+            version: ((kind >> 10) & 0x07) as u8,
+            kind: kind & 0x03FF,
+            id,
+            body,
+        })
+    }
+
+    /// Writes the message as a single buffer
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes())?;
+        writer.flush()
+    }
+
+    /// Encodes the message
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let kind = ((self.version as u16 & 0x07) << 10) | (self.kind & 0x03FF);
+        let len = (HEADER_LEN + self.body.len()) as u32;
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.body.len());
+        bytes.extend_from_slice(&kind.to_be_bytes());
+        bytes.extend_from_slice(&len.to_be_bytes());
+        bytes.extend_from_slice(&self.id.to_be_bytes());
+        bytes.extend_from_slice(&self.body);
+
+        bytes
+    }
+
+    /// Decoder over the body of the message
+    pub fn decoder(&self) -> Decoder<'_> {
+        Decoder::new(&self.body)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// A bit string, as used for tag masks. Bits are stored from the most
+/// significant bit of the first byte on.
+pub struct Bits {
+    /// Number of bits
+    pub len: u16,
+    /// Bits, padded with zeros to a whole number of bytes
+    pub bytes: Vec<u8>,
+}
+
+impl Bits {
+    /// Bit string of whole bytes
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            len: (bytes.len() * 8) as u16,
+            bytes: bytes.to_vec(),
+        }
+    }
+
+    /// Value of the `i`th bit, `None` past the end
+    pub fn get(&self, i: usize) -> Option<bool> {
+        if i >= self.len as usize {
+            return None;
+        }
+
+        Some(self.bytes[i / 8] & (0x80 >> (i % 8)) != 0)
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Reads the fields and parameters of a message or parameter body
+pub struct Decoder<'a> {
+    /// Data being decoded
+    data: &'a [u8],
+    /// Position of the next field
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// Decodes `data` from the start
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Whether everything was decoded
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Takes the next `len` bytes
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.data.len() - self.pos < len {
+            return Err(LlrpError::new(StatusCode::FieldError, "truncated field"));
+        }
+
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+
+        Ok(bytes)
+    }
+
+    /// Reads a u8 field
+    pub fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    /// Reads a u16 field
+    pub fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    /// Reads a u32 field
+    pub fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    /// Reads a u64 field
+    pub fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    /// Reads an s16 field
+    pub fn i16(&mut self) -> Result<i16> {
+        Ok(self.u16()? as i16)
+    }
+
+    /// Reads a u16v field (u16 count, then the values)
+    pub fn u16_array(&mut self) -> Result<Vec<u16>> {
+        let count = self.u16()?;
+        (0..count).map(|_| self.u16()).collect()
+    }
+
+    /// Reads a u8v field (u16 count, then the bytes)
+    pub fn u8_array(&mut self) -> Result<&'a [u8]> {
+        let count = self.u16()? as usize;
+        self.bytes(count)
+    }
+
+    /// Reads a utf8v field
+    pub fn utf8(&mut self) -> Result<String> {
+        String::from_utf8(self.u8_array()?.to_vec())
+            .map_err(|_| LlrpError::new(StatusCode::FieldError, "invalid UTF-8 string"))
+    }
+
+    /// Reads a u1v field (u16 bit count, then the bits)
+    pub fn bits(&mut self) -> Result<Bits> {
+        let len = self.u16()?;
+        let bytes = self.bytes((len as usize).div_ceil(8))?.to_vec();
+
+        Ok(Bits { len, bytes })
+    }
+
+    /// Type of the next parameter, without consuming it
+    pub fn peek(&self) -> Option<u16> {
+        let first = *self.data.get(self.pos)?;
+
+        if first & 0x80 != 0 {
+            Some((first & 0x7F) as u16)
+        } else {
+            let second = *self.data.get(self.pos + 1)?;
+            Some(u16::from_be_bytes([first, second]) & 0x03FF)
+        }
+    }
+
+    /// Reads the next parameter, `None` if there are none left
+    pub fn param(&mut self) -> Result<Option<(u16, Decoder<'a>)>> {
+        let kind = match self.peek() {
+            Some(kind) => kind,
+            None => return Ok(None),
+        };
+
+        if self.data[self.pos] & 0x80 != 0 {
+            let len = param::tv_len(kind).ok_or_else(|| {
+                LlrpError::new(
+                    StatusCode::UnknownParameter,
+                    format!("unknown TV parameter {}", kind),
+                )
+            })?;
+            self.pos += 1;
+
+            return Ok(Some((kind, Decoder::new(self.bytes(len)?))));
+        }
+
+        self.u16()?;
+        let len = self.u16()? as usize;
+        if len < TLV_HEADER_LEN {
+            return Err(LlrpError::new(
+                StatusCode::ParameterError,
+                format!("invalid length of parameter {}", kind),
+            ));
+        }
+
+        Ok(Some((
+            kind,
+            Decoder::new(self.bytes(len - TLV_HEADER_LEN)?),
+        )))
+    }
+
+    /// Reads the next parameter if it's of type `kind`
+    pub fn optional(&mut self, kind: u16) -> Result<Option<Decoder<'a>>> {
+        if self.peek() != Some(kind) {
+            return Ok(None);
+        }
+
+        Ok(self.param()?.map(|(_, param)| param))
+    }
+
+    /// Reads the next parameter, which must be of type `kind`
+    pub fn required(&mut self, kind: u16) -> Result<Decoder<'a>> {
+        self.optional(kind)?.ok_or_else(|| {
+            LlrpError::new(
+                StatusCode::MissingParameter,
+                format!("missing parameter {}", kind),
+            )
+        })
+    }
+
+    /// Reads all following parameters of type `kind`
+    pub fn repeated(&mut self, kind: u16) -> Result<Vec<Decoder<'a>>> {
+        let mut params = Vec::new();
+
+        while let Some(param) = self.optional(kind)? {
+            params.push(param);
+        }
+
+        Ok(params)
+    }
+
+    /// Skips the remaining parameters, checking that they are well formed
+    pub fn skip_rest(&mut self) -> Result<()> {
+        while self.param()?.is_some() {}
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// Builds message and parameter bodies
+pub struct Encoder {
+    /// Data encoded so far
+    data: Vec<u8>,
+}
+
+impl Encoder {
+    /// Creates an empty encoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes bytes as they are
+    pub fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.data.extend_from_slice(bytes);
+        self
+    }
+
+    /// Writes a u8 field
+    pub fn u8(&mut self, value: u8) -> &mut Self {
+        self.bytes(&[value])
+    }
+
+    /// Writes a u16 field
+    pub fn u16(&mut self, value: u16) -> &mut Self {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    /// Writes a u32 field
+    pub fn u32(&mut self, value: u32) -> &mut Self {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    /// Writes a u64 field
+    pub fn u64(&mut self, value: u64) -> &mut Self {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    /// Writes an s16 field
+    pub fn i16(&mut self, value: i16) -> &mut Self {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    /// Writes a u16v field
+    pub fn u16_array(&mut self, values: &[u16]) -> &mut Self {
+        self.u16(values.len() as u16);
+        for &value in values {
+            self.u16(value);
+        }
+        self
+    }
+
+    /// Writes a u8v field
+    pub fn u8_array(&mut self, bytes: &[u8]) -> &mut Self {
+        self.u16(bytes.len() as u16).bytes(bytes)
+    }
+
+    /// Writes a utf8v field
+    pub fn utf8(&mut self, value: &str) -> &mut Self {
+        self.u8_array(value.as_bytes())
+    }
+
+    /// Writes a u1v field
+    pub fn bits(&mut self, bits: &Bits) -> &mut Self {
+        self.u16(bits.len).bytes(&bits.bytes)
+    }
+
+    /// Writes a TLV parameter, its body is written by `body`
+    pub fn param(&mut self, kind: u16, body: impl FnOnce(&mut Encoder)) -> &mut Self {
+        let start = self.data.len();
+        self.u16(kind & 0x03FF).u16(0);
+
+        body(self);
+
+        let len = (self.data.len() - start) as u16;
+        self.data[start + 2..start + 4].copy_from_slice(&len.to_be_bytes());
+        self
+    }
+
+    /// Writes a TV parameter
+    pub fn tv(&mut self, kind: u16, value: &[u8]) -> &mut Self {
+        debug_assert_eq!(param::tv_len(kind), Some(value.len()));
+        self.u8(0x80 | kind as u8).bytes(value)
+    }
+
+    /// Writes an LLRPStatus parameter
+    pub fn status(&mut self, status: StatusCode, description: &str) -> &mut Self {
+        self.param(param::LLRP_STATUS, |e| {
+            e.u16(status as u16).utf8(description);
+        })
+    }
+
+    /// Encoded data
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Decodes the LLRPStatus parameter of a response, skipping the
+/// parameters before it. Fields before the parameters must have been read
+/// already. Returns the status code and description.
+pub fn decode_status(decoder: &mut Decoder) -> Result<(u16, String)> {
+    while let Some((kind, mut param)) = decoder.param()? {
+        if kind == param::LLRP_STATUS {
+            return Ok((param.u16()?, param.utf8()?));
+        }
+    }
+
+    Err(LlrpError::new(
+        StatusCode::MissingParameter,
+        "missing LLRPStatus",
+    ))
+}
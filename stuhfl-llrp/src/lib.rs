@@ -0,0 +1,41 @@
+//! LLRP (Low Level Reader Protocol) server for ST25RU3993 evaluation
+//! boards, so that they can be used by EPCglobal LLRP 1.0.1 and 1.1
+//! clients and middleware.
+//!
+//! The [`Server`] maps LLRP onto `libstuhfl`:
+//!
+//! - GET_READER_CAPABILITIES describes the board, its channel list as hop
+//!   table and 20 transmit power levels, 1 dB apart.
+//! - ROSpecs added, enabled and started by clients run inventory rounds
+//!   and send the tags found in RO_ACCESS_REPORTs.
+//! - AccessSpecs run C1G2 read, write, lock and kill operations on the
+//!   tags they target, and report the results with the tags.
+//! - Reader event notifications, keepalives, GET_REPORT and protocol
+//!   version negotiation are supported. GPIOs, RF surveys, periodic and
+//!   GPI triggers, C1G2 inventory filters and custom extensions are not.
+//!
+//! Reader operations go through the [`Backend`] trait, implemented by
+//! [`Board`] for evaluation boards, so the server can be tested without
+//! hardware. The [`Client`] connects to LLRP readers, including this
+//! server.
+
+#![warn(missing_docs)]
+#![warn(clippy::missing_docs_in_private_items)]
+
+pub mod access;
+pub mod backend;
+pub mod capabilities;
+pub mod client;
+pub mod codec;
+pub mod report;
+pub mod server;
+pub mod spec;
+#[cfg(test)]
+mod tests;
+
+pub use backend::{Backend, Board, ReaderInfo};
+pub use client::Client;
+pub use server::Server;
+
+/// Port registered for LLRP
+pub const LLRP_PORT: u16 = 5084;
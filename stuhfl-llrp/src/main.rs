@@ -0,0 +1,137 @@
+//! `stuhfl-llrp` - serves an ST25RU3993 evaluation board over LLRP.
+//!
+//! Connects to the board, tunes it and accepts LLRP clients on TCP port
+//! 5084. ROSpecs changing antenna or transmit power reconfigure and tune
+//! the board again, so keep the tuning fast.
+
+#![warn(missing_docs)]
+#![warn(clippy::missing_docs_in_private_items)]
+
+use clap::Parser;
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use libstuhfl::rssi::RssiModel;
+use std::error::Error;
+use std::net::{SocketAddr, TcpListener};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use stuhfl_common::Tuning;
+use stuhfl_llrp::{Board, Server};
+
+#[derive(Parser)]
+#[command(
+    name = "stuhfl-llrp",
+    version,
+    about = "LLRP server for ST25RU3993 RFID readers"
+)]
+/// Command line arguments
+struct Cli {
+    /// Serial port of the reader [default: first reader found]
+    #[arg(long, conflicts_with = "serial")]
+    port: Option<String>,
+
+    /// USB serial number of the reader
+    #[arg(long)]
+    serial: Option<String>,
+
+    /// Gen2 configuration file to start with [default: the library's
+    /// default configuration]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Tuning algorithm run at start and after each configuration change
+    #[arg(long, value_enum, default_value_t = Tuning::Fast)]
+    tuning: Tuning,
+
+    /// Address to listen on
+    #[arg(long, default_value = "0.0.0.0:5084")]
+    listen: SocketAddr,
+
+    /// Output power of the board at the highest Tx output level (dBm),
+    /// used for the transmit power table
+    #[arg(long, default_value_t = 20.0, value_name = "DBM")]
+    max_power: f32,
+
+    /// Reader ID reported to clients (hex)
+    #[arg(long, value_parser = parse_reader_id, default_value = "0000000000000000")]
+    reader_id: ReaderId,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Reader ID given on the command line
+struct ReaderId(Vec<u8>);
+
+/// Parses a reader ID of 1 to 32 hex bytes
+fn parse_reader_id(value: &str) -> Result<ReaderId, String> {
+    let invalid = || "expected 1 to 32 hex bytes".to_string();
+
+    if value.is_empty() || value.len() > 64 || value.len() & 1 != 0 || !value.is_ascii() {
+        return Err(invalid());
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&value[i..i + 2], 16).map_err(|_| invalid()))
+        .collect::<Result<_, _>>()
+        .map(ReaderId)
+}
+
+/// Connects, configures and tunes the reader as requested on the
+/// command line
+fn connect(cli: &Cli) -> Result<Gen2Reader, Box<dyn Error>> {
+    let cfg = stuhfl_common::load_config(cli.config.as_deref())?;
+    let reader = stuhfl_common::connect(cli.port.as_deref(), cli.serial.as_deref())?;
+
+    let mut reader = reader.configure_gen2(&cfg)?;
+    reader.tune(cli.tuning.into())?;
+
+    Ok(reader)
+}
+
+/// Runs the server until it fails
+fn serve(cli: &Cli) -> Result<(), Box<dyn Error>> {
+    let reader = connect(cli)?;
+    let board = Board::new(
+        reader,
+        cli.tuning.into(),
+        cli.max_power,
+        cli.reader_id.0.clone(),
+    )?;
+
+    let listener = TcpListener::bind(cli.listen)?;
+    eprintln!("Listening for LLRP clients on {}", cli.listen);
+
+    Server::new(board, RssiModel::default()).serve(listener);
+
+    Ok(())
+}
+
+/// Starts the server, reporting errors on stderr
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match serve(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["stuhfl-llrp", "--reader-id", "0A0B"]).unwrap();
+        assert_eq!(cli.listen.port(), stuhfl_llrp::LLRP_PORT);
+        assert_eq!(cli.reader_id, ReaderId(vec![0x0A, 0x0B]));
+
+        assert!(Cli::try_parse_from(["stuhfl-llrp", "--reader-id", "0A0"]).is_err());
+    }
+}
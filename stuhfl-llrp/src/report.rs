@@ -0,0 +1,375 @@
+//! Tag reports (RO_ACCESS_REPORT) and reader event notifications
+
+use crate::codec::{param, Bits, Decoder, Encoder, LlrpError, Result, StatusCode};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current time as an LLRP UTC timestamp (µs since the Unix epoch)
+pub fn now_utc() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_micros() as u64)
+        .unwrap_or(0)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Result of an OpSpec run on a tag
+pub enum OpResult {
+    /// C1G2ReadOpSpecResult
+    Read {
+        /// OpSpecID
+        id: u16,
+        /// Result code, 0 for success
+        result: u8,
+        /// Words read
+        data: Vec<u16>,
+    },
+    /// C1G2WriteOpSpecResult
+    Write {
+        /// OpSpecID
+        id: u16,
+        /// Result code, 0 for success
+        result: u8,
+        /// Number of words written
+        words: u16,
+    },
+    /// C1G2KillOpSpecResult
+    Kill {
+        /// OpSpecID
+        id: u16,
+        /// Result code, 0 for success
+        result: u8,
+    },
+    /// C1G2LockOpSpecResult
+    Lock {
+        /// OpSpecID
+        id: u16,
+        /// Result code, 0 for success
+        result: u8,
+    },
+}
+
+impl OpResult {
+    /// Result code, 0 for success
+    pub fn result(&self) -> u8 {
+        match self {
+            Self::Read { result, .. }
+            | Self::Write { result, .. }
+            | Self::Kill { result, .. }
+            | Self::Lock { result, .. } => *result,
+        }
+    }
+
+    /// Decodes an OpSpec result parameter of type `kind`
+    pub fn decode(kind: u16, p: &mut Decoder) -> Result<Self> {
+        let result = p.u8()?;
+        let id = p.u16()?;
+
+        Ok(match kind {
+            param::C1G2_READ_OP_SPEC_RESULT => Self::Read {
+                id,
+                result,
+                data: p.u16_array()?,
+            },
+            param::C1G2_WRITE_OP_SPEC_RESULT => Self::Write {
+                id,
+                result,
+                words: p.u16()?,
+            },
+            param::C1G2_KILL_OP_SPEC_RESULT => Self::Kill { id, result },
+            param::C1G2_LOCK_OP_SPEC_RESULT => Self::Lock { id, result },
+            kind => {
+                return Err(LlrpError::new(
+                    StatusCode::UnknownParameter,
+                    format!("unknown OpSpec result {}", kind),
+                ))
+            }
+        })
+    }
+
+    /// Encodes the result as a parameter
+    pub fn encode(&self, e: &mut Encoder) {
+        match self {
+            Self::Read { id, result, data } => e.param(param::C1G2_READ_OP_SPEC_RESULT, |e| {
+                e.u8(*result).u16(*id).u16_array(data);
+            }),
+            Self::Write { id, result, words } => e.param(param::C1G2_WRITE_OP_SPEC_RESULT, |e| {
+                e.u8(*result).u16(*id).u16(*words);
+            }),
+            Self::Kill { id, result } => e.param(param::C1G2_KILL_OP_SPEC_RESULT, |e| {
+                e.u8(*result).u16(*id);
+            }),
+            Self::Lock { id, result } => e.param(param::C1G2_LOCK_OP_SPEC_RESULT, |e| {
+                e.u8(*result).u16(*id);
+            }),
+        };
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// TagReportData: a tag seen by an ROSpec, and the results of the
+/// operations run on it. Fields not selected for the report are `None`.
+pub struct TagReport {
+    /// EPC
+    pub epc: Bits,
+    /// ROSpecID
+    pub rospec_id: Option<u32>,
+    /// SpecIndex, 1 for the first AISpec
+    pub spec_index: Option<u16>,
+    /// InventoryParameterSpecID
+    pub inventory_spec_id: Option<u16>,
+    /// AntennaID
+    pub antenna: Option<u16>,
+    /// PeakRSSI (dBm)
+    pub peak_rssi: Option<i8>,
+    /// ChannelIndex, 1 for the first frequency of the hop table
+    pub channel_index: Option<u16>,
+    /// FirstSeenTimestampUTC (µs)
+    pub first_seen: Option<u64>,
+    /// LastSeenTimestampUTC (µs)
+    pub last_seen: Option<u64>,
+    /// TagSeenCount
+    pub seen_count: Option<u16>,
+    /// C1G2_PC
+    pub pc: Option<u16>,
+    /// AccessSpecID
+    pub access_spec_id: Option<u32>,
+    /// Results of the operations run on the tag
+    pub results: Vec<OpResult>,
+}
+
+impl TagReport {
+    /// Decodes a TagReportData parameter body
+    pub fn decode(p: &mut Decoder) -> Result<Self> {
+        let mut report = Self::default();
+        let mut epc = false;
+
+        while let Some((kind, mut param)) = p.param()? {
+            match kind {
+                param::EPC_96 => {
+                    report.epc = Bits::from_bytes(param.bytes(12)?);
+                    epc = true;
+                }
+                param::EPC_DATA => {
+                    report.epc = param.bits()?;
+                    epc = true;
+                }
+                param::ROSPEC_ID => report.rospec_id = Some(param.u32()?),
+                param::SPEC_INDEX => report.spec_index = Some(param.u16()?),
+                param::INVENTORY_PARAMETER_SPEC_ID => report.inventory_spec_id = Some(param.u16()?),
+                param::ANTENNA_ID => report.antenna = Some(param.u16()?),
+                param::PEAK_RSSI => report.peak_rssi = Some(param.u8()? as i8),
+                param::CHANNEL_INDEX => report.channel_index = Some(param.u16()?),
+                param::FIRST_SEEN_UTC => report.first_seen = Some(param.u64()?),
+                param::LAST_SEEN_UTC => report.last_seen = Some(param.u64()?),
+                param::TAG_SEEN_COUNT => report.seen_count = Some(param.u16()?),
+                param::C1G2_PC => report.pc = Some(param.u16()?),
+                param::ACCESS_SPEC_ID => report.access_spec_id = Some(param.u32()?),
+                param::C1G2_READ_OP_SPEC_RESULT..=param::C1G2_LOCK_OP_SPEC_RESULT => {
+                    report.results.push(OpResult::decode(kind, &mut param)?)
+                }
+                // Other tag data and custom parameters
+                _ => (),
+            }
+        }
+
+        if !epc {
+            return Err(LlrpError::new(
+                StatusCode::MissingParameter,
+                "TagReportData without EPC",
+            ));
+        }
+
+        Ok(report)
+    }
+
+    /// Encodes the report as a TagReportData parameter
+    pub fn encode(&self, e: &mut Encoder) {
+        e.param(param::TAG_REPORT_DATA, |e| {
+            if self.epc.len == 96 {
+                e.tv(param::EPC_96, &self.epc.bytes);
+            } else {
+                e.param(param::EPC_DATA, |e| {
+                    e.bits(&self.epc);
+                });
+            }
+
+            if let Some(id) = self.rospec_id {
+                e.tv(param::ROSPEC_ID, &id.to_be_bytes());
+            }
+            if let Some(index) = self.spec_index {
+                e.tv(param::SPEC_INDEX, &index.to_be_bytes());
+            }
+            if let Some(id) = self.inventory_spec_id {
+                e.tv(param::INVENTORY_PARAMETER_SPEC_ID, &id.to_be_bytes());
+            }
+            if let Some(antenna) = self.antenna {
+                e.tv(param::ANTENNA_ID, &antenna.to_be_bytes());
+            }
+            if let Some(rssi) = self.peak_rssi {
+                e.tv(param::PEAK_RSSI, &rssi.to_be_bytes());
+            }
+            if let Some(index) = self.channel_index {
+                e.tv(param::CHANNEL_INDEX, &index.to_be_bytes());
+            }
+            if let Some(time) = self.first_seen {
+                e.tv(param::FIRST_SEEN_UTC, &time.to_be_bytes());
+            }
+            if let Some(time) = self.last_seen {
+                e.tv(param::LAST_SEEN_UTC, &time.to_be_bytes());
+            }
+            if let Some(count) = self.seen_count {
+                e.tv(param::TAG_SEEN_COUNT, &count.to_be_bytes());
+            }
+            if let Some(pc) = self.pc {
+                e.tv(param::C1G2_PC, &pc.to_be_bytes());
+            }
+            if let Some(id) = self.access_spec_id {
+                e.tv(param::ACCESS_SPEC_ID, &id.to_be_bytes());
+            }
+
+            for result in &self.results {
+                result.encode(e);
+            }
+        });
+    }
+}
+
+/// Encodes the body of an RO_ACCESS_REPORT
+pub fn encode_report(reports: &[TagReport]) -> Vec<u8> {
+    let mut e = Encoder::new();
+
+    for report in reports {
+        report.encode(&mut e);
+    }
+
+    e.into_bytes()
+}
+
+/// Decodes the body of an RO_ACCESS_REPORT
+pub fn decode_report(body: &[u8]) -> Result<Vec<TagReport>> {
+    Decoder::new(body)
+        .repeated(param::TAG_REPORT_DATA)?
+        .iter_mut()
+        .map(TagReport::decode)
+        .collect()
+}
+
+#[repr(u16)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// Event types of ReaderEventNotificationSpec
+pub enum EventType {
+    /// Upon hopping to next channel
+    Hopping = 0,
+    /// GPI event
+    Gpi = 1,
+    /// ROSpec event
+    RoSpec = 2,
+    /// Report buffer fill warning
+    BufferFillWarning = 3,
+    /// Reader exception event
+    ReaderException = 4,
+    /// RF survey event
+    RfSurvey = 5,
+    /// AISpec event
+    AiSpec = 6,
+    /// AISpec event with singulation details
+    AiSpecSingulation = 7,
+    /// Antenna event
+    Antenna = 8,
+}
+
+impl EventType {
+    /// All event types, in order
+    pub const ALL: [EventType; 9] = [
+        EventType::Hopping,
+        EventType::Gpi,
+        EventType::RoSpec,
+        EventType::BufferFillWarning,
+        EventType::ReaderException,
+        EventType::RfSurvey,
+        EventType::AiSpec,
+        EventType::AiSpecSingulation,
+        EventType::Antenna,
+    ];
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Event sent in a READER_EVENT_NOTIFICATION
+pub enum ReaderEvent {
+    /// ConnectionAttemptEvent: 0 for success, 2 if another client is
+    /// connected already
+    ConnectionAttempt(u16),
+    /// ConnectionCloseEvent
+    ConnectionClose,
+    /// ROSpecEvent
+    RoSpec {
+        /// 0 for start of ROSpec, 1 for end of ROSpec, 2 for preemption
+        event: u8,
+        /// ROSpecID
+        id: u32,
+    },
+    /// ReaderExceptionEvent
+    ReaderException(String),
+}
+
+impl ReaderEvent {
+    /// Whether the event is sent when event notifications are `enabled`
+    /// (indexed by [`EventType`]). Connection events are always sent.
+    pub fn is_enabled(&self, enabled: &[bool; 9]) -> bool {
+        match self {
+            Self::ConnectionAttempt(_) | Self::ConnectionClose => true,
+            Self::RoSpec { .. } => enabled[EventType::RoSpec as usize],
+            Self::ReaderException(_) => enabled[EventType::ReaderException as usize],
+        }
+    }
+
+    /// Encodes the body of a READER_EVENT_NOTIFICATION with this event
+    pub fn encode(&self, timestamp: u64) -> Vec<u8> {
+        let mut e = Encoder::new();
+
+        e.param(param::READER_EVENT_NOTIFICATION_DATA, |e| {
+            e.param(param::UTC_TIMESTAMP, |e| {
+                e.u64(timestamp);
+            });
+
+            match self {
+                Self::ConnectionAttempt(status) => e.param(param::CONNECTION_ATTEMPT_EVENT, |e| {
+                    e.u16(*status);
+                }),
+                Self::ConnectionClose => e.param(param::CONNECTION_CLOSE_EVENT, |_| ()),
+                Self::RoSpec { event, id } => e.param(param::ROSPEC_EVENT, |e| {
+                    e.u8(*event).u32(*id).u32(0);
+                }),
+                Self::ReaderException(message) => e.param(param::READER_EXCEPTION_EVENT, |e| {
+                    e.utf8(message);
+                }),
+            };
+        });
+
+        e.into_bytes()
+    }
+
+    /// Decodes the events of a READER_EVENT_NOTIFICATION body, skipping
+    /// the ones not listed here
+    pub fn decode(body: &[u8]) -> Result<Vec<Self>> {
+        let mut data = Decoder::new(body).required(param::READER_EVENT_NOTIFICATION_DATA)?;
+        let mut events = Vec::new();
+
+        while let Some((kind, mut param)) = data.param()? {
+            match kind {
+                param::CONNECTION_ATTEMPT_EVENT => {
+                    events.push(Self::ConnectionAttempt(param.u16()?))
+                }
+                param::CONNECTION_CLOSE_EVENT => events.push(Self::ConnectionClose),
+                param::ROSPEC_EVENT => events.push(Self::RoSpec {
+                    event: param.u8()?,
+                    id: param.u32()?,
+                }),
+                param::READER_EXCEPTION_EVENT => events.push(Self::ReaderException(param.utf8()?)),
+                _ => (),
+            }
+        }
+
+        Ok(events)
+    }
+}
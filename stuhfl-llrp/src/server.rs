@@ -0,0 +1,1160 @@
+//! The LLRP server: client connection and the ROSpec / AccessSpec engine
+//!
+//! A single thread owns the reader. It handles messages as they come in
+//! and runs inventory rounds in between while an ROSpec is active, so a
+//! message is never handled in the middle of a round. Only one client is
+//! served at a time; further connection attempts get a
+//! ConnectionAttemptEvent with status 2 and are closed.
+//!
+//! A single ROSpec runs at a time. Its AISpecs are run in turn, each
+//! inventory round using the next antenna of the AISpec, and the next
+//! InventoryParameterSpec once all antennas were used. Tags are reported
+//! once per report period per antenna, with the number of times they
+//! were seen. Each tag found is matched against the enabled AccessSpecs,
+//! and the operations of the first matching one are run on it.
+
+use crate::access;
+use crate::backend::{self, Backend};
+use crate::capabilities::{self, *};
+use crate::codec::{
+    message, param, Bits, Decoder, Encoder, LlrpError, Message, Result, StatusCode, VERSION_1_0_1,
+    VERSION_1_1,
+};
+use crate::report::{self, EventType, ReaderEvent, TagReport};
+use crate::spec::*;
+use libstuhfl::prelude::*;
+use libstuhfl::rssi::RssiModel;
+use std::collections::{HashMap, HashSet};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Input of the server thread
+enum Input {
+    /// A client connected
+    Connected(TcpStream),
+    /// A message was received on a connection
+    Message(u64, Message),
+    /// A connection was closed
+    Closed(u64),
+}
+
+/// The connected client
+struct Client {
+    /// Connection number
+    id: u64,
+    /// Connection, for writing
+    stream: TcpStream,
+    /// Protocol version of messages sent by the reader
+    version: u8,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+/// Reader settings of SET_READER_CONFIG
+struct ReaderConfig {
+    /// Event notifications enabled, indexed by [`EventType`]
+    events: [bool; 9],
+    /// Reporting of ROSpecs without an ROReportSpec
+    report: ReportSpec,
+    /// Whether access results are sent right away by default
+    report_access_immediately: bool,
+    /// Period of KEEPALIVE messages, `None` for none
+    keepalive: Option<Duration>,
+}
+
+/// Progress of the running AISpec
+struct AiProgress {
+    /// When the AISpec started
+    started: Instant,
+    /// Inventory rounds run
+    rounds: u32,
+    /// Tags seen, counting each time a tag is seen
+    observations: u32,
+    /// EPCs seen
+    unique: HashSet<Vec<u8>>,
+    /// When a tag was last seen
+    last_observation: Instant,
+    /// When a new EPC was last seen
+    last_unique: Instant,
+}
+
+impl AiProgress {
+    /// Progress of an AISpec starting now
+    fn new(now: Instant) -> Self {
+        Self {
+            started: now,
+            rounds: 0,
+            observations: 0,
+            unique: HashSet::new(),
+            last_observation: now,
+            last_unique: now,
+        }
+    }
+
+    /// Counts a tag seen
+    fn observe(&mut self, epc: &[u8], now: Instant) {
+        self.observations += 1;
+        self.last_observation = now;
+
+        if self.unique.insert(epc.to_vec()) {
+            self.last_unique = now;
+        }
+    }
+
+    /// Whether the AISpec is done according to its stop trigger
+    fn done(&self, stop: &AiStopTrigger, now: Instant) -> bool {
+        let ms = |ms: u32| Duration::from_millis(ms as u64);
+
+        match stop {
+            AiStopTrigger::Null => false,
+            AiStopTrigger::Duration(duration) => now - self.started >= ms(*duration),
+            AiStopTrigger::TagObservation(trigger) => {
+                if trigger.timeout != 0 && now - self.started >= ms(trigger.timeout) {
+                    return true;
+                }
+
+                match trigger.kind {
+                    0 => self.observations >= trigger.tags as u32,
+                    1 => now - self.last_observation >= ms(trigger.idle as u32),
+                    2 => self.rounds >= trigger.attempts as u32,
+                    3 => self.unique.len() >= trigger.tags as usize,
+                    _ => now - self.last_unique >= ms(trigger.idle as u32),
+                }
+            }
+        }
+    }
+}
+
+/// The active ROSpec
+struct Run {
+    /// ROSpecID
+    id: u32,
+    /// When the ROSpec started
+    started: Instant,
+    /// Index of the running AISpec
+    spec_index: usize,
+    /// Progress of the running AISpec
+    ai: AiProgress,
+}
+
+/// Where and how a tag was seen
+struct Sighting<'a> {
+    /// ROSpecID
+    rospec_id: u32,
+    /// SpecIndex
+    spec_index: u16,
+    /// InventoryParameterSpecID
+    inventory_spec_id: u16,
+    /// AntennaID
+    antenna: u16,
+    /// When the round finished (UTC, µs)
+    timestamp: u64,
+    /// Statistics of the round
+    statistics: &'a InventoryStatistics,
+}
+
+/// LLRP server for a reader
+pub struct Server<B: Backend> {
+    /// The reader
+    backend: B,
+    /// Converts tag readings to PeakRSSI
+    rssi: RssiModel,
+    /// ROSpecs, in the order they were added
+    rospecs: Vec<RoSpec>,
+    /// AccessSpecs, in the order they were added
+    access_specs: Vec<AccessSpec>,
+    /// Reader settings
+    config: ReaderConfig,
+    /// The active ROSpec
+    run: Option<Run>,
+    /// ROSpecs to start once their ENABLE_ROSPEC was answered
+    starting: Vec<u32>,
+    /// TagReportData not sent yet
+    reports: Vec<TagReport>,
+    /// Index in `reports` of the tags seen, by EPC and antenna
+    aggregated: HashMap<(Vec<u8>, u16), usize>,
+    /// The connected client
+    client: Option<Client>,
+    /// Number of connections accepted
+    connections: u64,
+    /// ID of the last message sent by the reader
+    message_id: u32,
+    /// When the next KEEPALIVE is due
+    next_keepalive: Option<Instant>,
+}
+
+impl<B: Backend> Server<B> {
+    /// Creates a server for `backend`. PeakRSSI is computed with `rssi`.
+    pub fn new(backend: B, rssi: RssiModel) -> Self {
+        Self {
+            backend,
+            rssi,
+            rospecs: Vec::new(),
+            access_specs: Vec::new(),
+            config: ReaderConfig::default(),
+            run: None,
+            starting: Vec::new(),
+            reports: Vec::new(),
+            aggregated: HashMap::new(),
+            client: None,
+            connections: 0,
+            message_id: 0,
+            next_keepalive: None,
+        }
+    }
+
+    /// Serves clients connecting to `listener`, forever
+    pub fn serve(mut self, listener: TcpListener) {
+        let (inputs, queue) = mpsc::channel();
+
+        let accepted = inputs.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if accepted.send(Input::Connected(stream)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        self.run(queue, &inputs);
+    }
+
+    /// Handles inputs as they come in, and runs inventory rounds in
+    /// between while an ROSpec is active
+    fn run(&mut self, queue: Receiver<Input>, inputs: &Sender<Input>) {
+        loop {
+            let timeout = if self.run.is_some() {
+                Some(Duration::ZERO)
+            } else {
+                self.next_keepalive
+                    .map(|at| at.saturating_duration_since(Instant::now()))
+            };
+
+            let input = match timeout {
+                Some(timeout) => queue.recv_timeout(timeout),
+                None => queue.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+
+            match input {
+                Ok(input) => {
+                    self.input(input, inputs);
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            if self.run.is_some() {
+                self.round();
+            }
+
+            self.keepalive();
+        }
+    }
+
+    /// Handles an input of the server thread
+    fn input(&mut self, input: Input, inputs: &Sender<Input>) {
+        let client = self.client.as_ref().map(|client| client.id);
+
+        match input {
+            Input::Connected(stream) => self.connect(stream, inputs),
+            Input::Message(id, message) if client == Some(id) => self.message(message),
+            Input::Closed(id) if client == Some(id) => self.disconnect(),
+            // Left over from a previous connection
+            Input::Message(..) | Input::Closed(_) => (),
+        }
+    }
+
+    /// Accepts a client, or turns it away if one is connected already
+    fn connect(&mut self, mut stream: TcpStream, inputs: &Sender<Input>) {
+        let _ = stream.set_nodelay(true);
+
+        if self.client.is_some() {
+            let body = ReaderEvent::ConnectionAttempt(2).encode(report::now_utc());
+            let id = self.next_message_id();
+
+            let _ = Message::new(VERSION_1_0_1, message::READER_EVENT_NOTIFICATION, id, body)
+                .write_to(&mut stream);
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+
+        let reader = match stream.try_clone() {
+            Ok(reader) => reader,
+            Err(_) => return,
+        };
+
+        self.connections += 1;
+        let id = self.connections;
+        let inputs = inputs.clone();
+        thread::spawn(move || read_messages(id, reader, inputs));
+
+        self.client = Some(Client {
+            id,
+            stream,
+            version: VERSION_1_0_1,
+        });
+        self.schedule_keepalive();
+        self.notify(ReaderEvent::ConnectionAttempt(0));
+    }
+
+    /// Closes the connection to the client. ROSpecs keep running, their
+    /// reports are kept for the next client.
+    fn disconnect(&mut self) {
+        if let Some(client) = self.client.take() {
+            let _ = client.stream.shutdown(Shutdown::Both);
+        }
+
+        self.next_keepalive = None;
+    }
+
+    /// ID for a message sent by the reader
+    fn next_message_id(&mut self) -> u32 {
+        self.message_id = self.message_id.wrapping_add(1);
+        self.message_id
+    }
+
+    /// Sends a message to the client, disconnecting it if that fails.
+    /// Returns whether the message was sent.
+    fn send(&mut self, message: Message) -> bool {
+        let client = match &mut self.client {
+            Some(client) => client,
+            None => return false,
+        };
+
+        if message.write_to(&mut client.stream).is_ok() {
+            return true;
+        }
+
+        self.disconnect();
+        false
+    }
+
+    /// Sends a message initiated by the reader
+    fn send_new(&mut self, kind: u16, body: Vec<u8>) -> bool {
+        let version = match &self.client {
+            Some(client) => client.version,
+            None => return false,
+        };
+        let id = self.next_message_id();
+
+        self.send(Message::new(version, kind, id, body))
+    }
+
+    /// Sends an event notification, if enabled
+    fn notify(&mut self, event: ReaderEvent) {
+        if event.is_enabled(&self.config.events) {
+            self.send_new(
+                message::READER_EVENT_NOTIFICATION,
+                event.encode(report::now_utc()),
+            );
+        }
+    }
+
+    /// Sends the TagReportData not sent yet. Nothing is sent if there
+    /// are none, unless `always`.
+    fn send_report(&mut self, always: bool) {
+        if (self.reports.is_empty() && !always) || self.client.is_none() {
+            return;
+        }
+
+        let body = report::encode_report(&self.reports);
+        self.reports.clear();
+        self.aggregated.clear();
+
+        self.send_new(message::RO_ACCESS_REPORT, body);
+    }
+
+    /// Sets when the next KEEPALIVE is due
+    fn schedule_keepalive(&mut self) {
+        self.next_keepalive = match (&self.client, self.config.keepalive) {
+            (Some(_), Some(period)) => Some(Instant::now() + period),
+            _ => None,
+        };
+    }
+
+    /// Sends a KEEPALIVE if it's due
+    fn keepalive(&mut self) {
+        if let Some(at) = self.next_keepalive {
+            if Instant::now() >= at {
+                self.send_new(message::KEEPALIVE, Vec::new());
+                self.schedule_keepalive();
+            }
+        }
+    }
+
+    /// Handles a message from the client
+    fn message(&mut self, request: Message) {
+        if request.version != VERSION_1_0_1 && request.version != VERSION_1_1 {
+            let version = self.client.as_ref().map_or(VERSION_1_0_1, |c| c.version);
+            let mut body = Encoder::new();
+            body.status(
+                StatusCode::UnsupportedVersion,
+                &format!("unsupported protocol version {}", request.version),
+            );
+
+            self.send(Message::new(
+                version,
+                message::ERROR_MESSAGE,
+                request.id,
+                body.into_bytes(),
+            ));
+            return;
+        }
+
+        match request.kind {
+            message::KEEPALIVE_ACK | message::ENABLE_EVENTS_AND_REPORTS => return,
+            message::GET_REPORT => {
+                self.send_report(true);
+                return;
+            }
+            _ => (),
+        }
+
+        let mut body = Encoder::new();
+
+        let response = match message::response(request.kind) {
+            Some(response) => response,
+            None => {
+                body.status(
+                    StatusCode::UnsupportedMessage,
+                    &format!("unsupported message type {}", request.kind),
+                );
+                self.send(Message::new(
+                    request.version,
+                    message::ERROR_MESSAGE,
+                    request.id,
+                    body.into_bytes(),
+                ));
+                return;
+            }
+        };
+
+        if request.kind == message::GET_SUPPORTED_VERSION {
+            let current = self.client.as_ref().map_or(VERSION_1_0_1, |c| c.version);
+            body.u8(current).u8(VERSION_1_1);
+            body.status(StatusCode::Success, "");
+        } else {
+            let mut data = Encoder::new();
+
+            match self.request(&request, &mut data) {
+                Ok(()) => body
+                    .status(StatusCode::Success, "")
+                    .bytes(&data.into_bytes()),
+                Err(e) => body.status(e.status, &e.message),
+            };
+        }
+
+        self.send(Message::new(
+            request.version,
+            response,
+            request.id,
+            body.into_bytes(),
+        ));
+
+        if request.kind == message::CLOSE_CONNECTION {
+            self.disconnect();
+        }
+
+        for id in std::mem::take(&mut self.starting) {
+            self.start(id);
+        }
+    }
+
+    /// Handles a request, encoding the parameters of the response after
+    /// its LLRPStatus into `e`
+    fn request(&mut self, request: &Message, e: &mut Encoder) -> Result<()> {
+        let mut d = request.decoder();
+
+        match request.kind {
+            message::GET_READER_CAPABILITIES => {
+                capabilities::encode(self.backend.info(), d.u8()?, e)
+            }
+            message::GET_READER_CONFIG => self.get_config(&mut d, e),
+            message::SET_READER_CONFIG => self.set_config(&mut d),
+            message::CLOSE_CONNECTION => Ok(()),
+            message::ADD_ROSPEC => self.add_rospec(&mut d),
+            message::DELETE_ROSPEC => {
+                for id in self.rospec_ids(d.u32()?)? {
+                    if self.run.as_ref().map(|run| run.id) == Some(id) {
+                        self.stop();
+                    }
+                    self.rospecs.retain(|rospec| rospec.id != id);
+                }
+                Ok(())
+            }
+            message::START_ROSPEC => self.start_rospec(d.u32()?),
+            message::STOP_ROSPEC => {
+                let id = d.u32()?;
+                self.rospec(id)?;
+
+                if self.run.as_ref().map(|run| run.id) != Some(id) {
+                    return Err(LlrpError::new(
+                        StatusCode::Invalid,
+                        format!("ROSpec {} isn't active", id),
+                    ));
+                }
+
+                self.stop();
+                Ok(())
+            }
+            message::ENABLE_ROSPEC => {
+                for id in self.rospec_ids(d.u32()?)? {
+                    let rospec = self.rospec(id)?;
+                    let enabled = rospec.state == RoSpecState::Disabled;
+
+                    if enabled {
+                        rospec.state = RoSpecState::Inactive;
+                    }
+                    if enabled && rospec.start == StartTrigger::Immediate {
+                        self.starting.push(id);
+                    }
+                }
+                Ok(())
+            }
+            message::DISABLE_ROSPEC => {
+                for id in self.rospec_ids(d.u32()?)? {
+                    if self.run.as_ref().map(|run| run.id) == Some(id) {
+                        self.stop();
+                    }
+                    self.rospec(id)?.state = RoSpecState::Disabled;
+                }
+                Ok(())
+            }
+            message::GET_ROSPECS => {
+                for rospec in &self.rospecs {
+                    rospec.encode(e);
+                }
+                Ok(())
+            }
+            message::ADD_ACCESSSPEC => self.add_access_spec(&mut d),
+            message::DELETE_ACCESSSPEC => {
+                for id in self.access_spec_ids(d.u32()?)? {
+                    self.access_specs.retain(|spec| spec.id != id);
+                }
+                Ok(())
+            }
+            message::ENABLE_ACCESSSPEC | message::DISABLE_ACCESSSPEC => {
+                let enabled = request.kind == message::ENABLE_ACCESSSPEC;
+
+                for id in self.access_spec_ids(d.u32()?)? {
+                    if let Some(spec) = self.access_specs.iter_mut().find(|spec| spec.id == id) {
+                        spec.enabled = enabled;
+                    }
+                }
+                Ok(())
+            }
+            message::GET_ACCESSSPECS => {
+                for spec in &self.access_specs {
+                    spec.encode(e);
+                }
+                Ok(())
+            }
+            message::SET_PROTOCOL_VERSION => {
+                let version = d.u8()?;
+
+                if version != VERSION_1_0_1 && version != VERSION_1_1 {
+                    return Err(LlrpError::new(
+                        StatusCode::UnsupportedVersion,
+                        format!("unsupported protocol version {}", version),
+                    ));
+                }
+
+                if let Some(client) = &mut self.client {
+                    client.version = version;
+                }
+                Ok(())
+            }
+            kind => Err(LlrpError::new(
+                StatusCode::UnsupportedMessage,
+                format!("unsupported message type {}", kind),
+            )),
+        }
+    }
+
+    /// GET_READER_CONFIG
+    fn get_config(&self, d: &mut Decoder, e: &mut Encoder) -> Result<()> {
+        let info = self.backend.info();
+        let antenna = d.u16()?;
+        let requested = d.u8()?;
+
+        if requested > 11 {
+            return Err(LlrpError::new(
+                StatusCode::FieldError,
+                format!("unsupported RequestedData {}", requested),
+            ));
+        }
+        if antenna > info.antennas {
+            return Err(LlrpError::new(
+                StatusCode::OutOfRange,
+                format!("no antenna {}", antenna),
+            ));
+        }
+
+        let selected = |data: u8| requested == 0 || requested == data;
+        let antennas = (1..=info.antennas).filter(|&a| antenna == 0 || antenna == a);
+
+        if selected(1) {
+            e.param(param::IDENTIFICATION, |e| {
+                e.u8(0).u8_array(&info.reader_id);
+            });
+        }
+        if selected(2) {
+            for antenna in antennas.clone() {
+                e.param(param::ANTENNA_PROPERTIES, |e| {
+                    e.u8(0x80).u16(antenna).i16(0);
+                });
+            }
+        }
+        if selected(3) {
+            for antenna in antennas {
+                e.param(param::ANTENNA_CONFIGURATION, |e| {
+                    e.u16(antenna);
+                });
+            }
+        }
+        if selected(5) {
+            e.param(param::READER_EVENT_NOTIFICATION_SPEC, |e| {
+                for event in EventType::ALL {
+                    e.param(param::EVENT_NOTIFICATION_STATE, |e| {
+                        let enabled = self.config.events[event as usize];
+                        e.u16(event as u16).u8(if enabled { 0x80 } else { 0 });
+                    });
+                }
+            });
+        }
+        if selected(4) {
+            self.config.report.encode(e);
+        }
+        if selected(6) {
+            e.param(param::ACCESS_REPORT_SPEC, |e| {
+                e.u8(self.config.report_access_immediately as u8);
+            });
+        }
+        if selected(8) {
+            e.param(param::KEEPALIVE_SPEC, |e| match self.config.keepalive {
+                Some(period) => {
+                    e.u8(1).u32(period.as_millis() as u32);
+                }
+                None => {
+                    e.u8(0).u32(0);
+                }
+            });
+        }
+        if selected(11) {
+            e.param(param::EVENTS_AND_REPORTS, |e| {
+                e.u8(0);
+            });
+        }
+
+        Ok(())
+    }
+
+    /// SET_READER_CONFIG. Antenna settings are chosen per ROSpec and
+    /// there are no GPIOs, so those parameters are ignored.
+    fn set_config(&mut self, d: &mut Decoder) -> Result<()> {
+        let reset = d.u8()? & 0x80 != 0;
+
+        let mut config = if reset {
+            ReaderConfig::default()
+        } else {
+            self.config.clone()
+        };
+
+        while let Some((kind, mut p)) = d.param()? {
+            match kind {
+                param::READER_EVENT_NOTIFICATION_SPEC => {
+                    for mut state in p.repeated(param::EVENT_NOTIFICATION_STATE)? {
+                        let event = state.u16()? as usize;
+                        let enabled = state.u8()? & 0x80 != 0;
+
+                        if let Some(setting) = config.events.get_mut(event) {
+                            *setting = enabled;
+                        }
+                    }
+                }
+                param::RO_REPORT_SPEC => config.report = ReportSpec::decode(&mut p)?,
+                param::ACCESS_REPORT_SPEC => config.report_access_immediately = p.u8()? == 1,
+                param::KEEPALIVE_SPEC => {
+                    let periodic = p.u8()? == 1;
+                    let period = p.u32()?;
+
+                    config.keepalive = if periodic && period > 0 {
+                        Some(Duration::from_millis(period as u64))
+                    } else {
+                        None
+                    };
+                }
+                _ => (),
+            }
+        }
+
+        if reset {
+            self.stop();
+            self.rospecs.clear();
+            self.access_specs.clear();
+            self.reports.clear();
+            self.aggregated.clear();
+        }
+
+        self.config = config;
+        self.schedule_keepalive();
+
+        Ok(())
+    }
+
+    /// ROSpec with ID `id`
+    fn rospec(&mut self, id: u32) -> Result<&mut RoSpec> {
+        self.rospecs
+            .iter_mut()
+            .find(|rospec| rospec.id == id)
+            .ok_or_else(|| LlrpError::new(StatusCode::FieldError, format!("no ROSpec {}", id)))
+    }
+
+    /// IDs of the ROSpecs addressed by `id`, 0 addressing all of them
+    fn rospec_ids(&mut self, id: u32) -> Result<Vec<u32>> {
+        if id == 0 {
+            return Ok(self.rospecs.iter().map(|rospec| rospec.id).collect());
+        }
+
+        self.rospec(id)?;
+        Ok(vec![id])
+    }
+
+    /// IDs of the AccessSpecs addressed by `id`, 0 addressing all of them
+    fn access_spec_ids(&self, id: u32) -> Result<Vec<u32>> {
+        if id == 0 {
+            return Ok(self.access_specs.iter().map(|spec| spec.id).collect());
+        }
+
+        if !self.access_specs.iter().any(|spec| spec.id == id) {
+            return Err(LlrpError::new(
+                StatusCode::FieldError,
+                format!("no AccessSpec {}", id),
+            ));
+        }
+
+        Ok(vec![id])
+    }
+
+    /// Checks that an antenna exists, 0 standing for all antennas
+    fn check_antenna(&self, antenna: u16) -> Result<()> {
+        if antenna > self.backend.info().antennas {
+            return Err(LlrpError::new(
+                StatusCode::OutOfRange,
+                format!("no antenna {}", antenna),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// ADD_ROSPEC
+    fn add_rospec(&mut self, d: &mut Decoder) -> Result<()> {
+        let rospec = RoSpec::decode(&mut d.required(param::ROSPEC)?)?;
+
+        let invalid = |message: String| Err(LlrpError::new(StatusCode::Invalid, message));
+        let overflow = |message: &str| Err(LlrpError::new(StatusCode::OverflowParameter, message));
+
+        if rospec.id == 0 {
+            return invalid("ROSpecID 0 is reserved".into());
+        }
+        if self.rospecs.iter().any(|r| r.id == rospec.id) {
+            return invalid(format!("ROSpec {} exists already", rospec.id));
+        }
+        if rospec.state != RoSpecState::Disabled {
+            return invalid("ROSpecs must be added disabled".into());
+        }
+        if rospec.priority > 7 {
+            return Err(LlrpError::new(
+                StatusCode::OutOfRange,
+                format!("priority {} out of range", rospec.priority),
+            ));
+        }
+        if self.rospecs.len() >= MAX_ROSPECS {
+            return overflow("too many ROSpecs");
+        }
+        if rospec.ai_specs.len() > MAX_SPECS_PER_ROSPEC {
+            return overflow("too many AISpecs");
+        }
+
+        for ai_spec in &rospec.ai_specs {
+            if ai_spec.antennas.is_empty() {
+                return invalid("AISpec without antennas".into());
+            }
+            if ai_spec.inventory.len() > MAX_INVENTORY_SPECS_PER_AISPEC {
+                return overflow("too many InventoryParameterSpecs");
+            }
+
+            for &antenna in &ai_spec.antennas {
+                self.check_antenna(antenna)?;
+            }
+
+            for inventory in &ai_spec.inventory {
+                if inventory.protocol != PROTOCOL_C1G2 {
+                    return invalid(format!("unsupported air protocol {}", inventory.protocol));
+                }
+
+                for config in &inventory.antennas {
+                    self.check_antenna(config.antenna)?;
+
+                    if let Some(power) = config.transmit_power {
+                        if backend::tx_output_level(power).is_none() {
+                            return Err(LlrpError::new(
+                                StatusCode::OutOfRange,
+                                format!("no transmit power level {}", power),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        self.rospecs.push(rospec);
+
+        Ok(())
+    }
+
+    /// START_ROSPEC
+    fn start_rospec(&mut self, id: u32) -> Result<()> {
+        let state = self.rospec(id)?.state;
+
+        let problem = match (state, &self.run) {
+            (RoSpecState::Disabled, _) => format!("ROSpec {} is disabled", id),
+            (RoSpecState::Active, _) => format!("ROSpec {} is active already", id),
+            (RoSpecState::Inactive, Some(run)) => format!("ROSpec {} is active", run.id),
+            (RoSpecState::Inactive, None) => {
+                self.starting.push(id);
+                return Ok(());
+            }
+        };
+
+        Err(LlrpError::new(StatusCode::Invalid, problem))
+    }
+
+    /// ADD_ACCESSSPEC
+    fn add_access_spec(&mut self, d: &mut Decoder) -> Result<()> {
+        let spec = AccessSpec::decode(&mut d.required(param::ACCESS_SPEC)?)?;
+
+        let invalid = |message: String| Err(LlrpError::new(StatusCode::Invalid, message));
+        let overflow = |message: &str| Err(LlrpError::new(StatusCode::OverflowParameter, message));
+
+        if spec.id == 0 {
+            return invalid("AccessSpecID 0 is reserved".into());
+        }
+        if self.access_specs.iter().any(|s| s.id == spec.id) {
+            return invalid(format!("AccessSpec {} exists already", spec.id));
+        }
+        if spec.enabled {
+            return invalid("AccessSpecs must be added disabled".into());
+        }
+        if spec.protocol != PROTOCOL_C1G2 {
+            return invalid(format!("unsupported air protocol {}", spec.protocol));
+        }
+        if spec.operation_count == Some(0) {
+            return invalid("operation count must be at least 1".into());
+        }
+        if self.access_specs.len() >= MAX_ACCESS_SPECS {
+            return overflow("too many AccessSpecs");
+        }
+        if spec.ops.len() > MAX_OPSPECS_PER_ACCESS_SPEC {
+            return overflow("too many OpSpecs");
+        }
+        self.check_antenna(spec.antenna)?;
+
+        self.access_specs.push(spec);
+
+        Ok(())
+    }
+
+    /// Reporting of the ROSpec with ID `id`
+    fn report_spec(&self, id: u32) -> ReportSpec {
+        self.rospecs
+            .iter()
+            .find(|rospec| rospec.id == id)
+            .and_then(|rospec| rospec.report)
+            .unwrap_or(self.config.report)
+    }
+
+    /// Starts an inactive ROSpec, unless another one is active
+    fn start(&mut self, id: u32) {
+        if self.run.is_some() {
+            return;
+        }
+
+        match self.rospec(id) {
+            Ok(rospec) if rospec.state == RoSpecState::Inactive => {
+                rospec.state = RoSpecState::Active;
+            }
+            _ => return,
+        }
+
+        let now = Instant::now();
+        self.run = Some(Run {
+            id,
+            started: now,
+            spec_index: 0,
+            ai: AiProgress::new(now),
+        });
+
+        self.notify(ReaderEvent::RoSpec { event: 0, id });
+    }
+
+    /// Stops the active ROSpec, sending its report
+    fn stop(&mut self) {
+        let run = match self.run.take() {
+            Some(run) => run,
+            None => return,
+        };
+
+        if let Ok(rospec) = self.rospec(run.id) {
+            rospec.state = RoSpecState::Inactive;
+        }
+
+        if self.report_spec(run.id).trigger != ReportTrigger::None {
+            self.send_report(false);
+        }
+
+        self.notify(ReaderEvent::RoSpec {
+            event: 1,
+            id: run.id,
+        });
+    }
+
+    /// Runs an inventory round of the active ROSpec
+    fn round(&mut self) {
+        let (id, spec_index, rounds) = match &self.run {
+            Some(run) => (run.id, run.spec_index, run.ai.rounds as usize),
+            None => return,
+        };
+        let rospec = match self.rospecs.iter().find(|rospec| rospec.id == id) {
+            Some(rospec) => rospec.clone(),
+            None => return,
+        };
+
+        let ai_spec = &rospec.ai_specs[spec_index];
+        let antennas: Vec<u16> = if ai_spec.antennas.contains(&0) {
+            (1..=self.backend.info().antennas).collect()
+        } else {
+            ai_spec.antennas.clone()
+        };
+        let antenna = antennas[rounds % antennas.len()];
+        let inventory = &ai_spec.inventory[(rounds / antennas.len()) % ai_spec.inventory.len()];
+
+        let result = self
+            .backend
+            .set_rf(
+                backend::antenna(antenna).unwrap_or(Antenna::Antenna1),
+                inventory
+                    .transmit_power(antenna)
+                    .and_then(backend::tx_output_level),
+            )
+            .and_then(|()| self.backend.inventory());
+
+        let (statistics, tags) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                self.notify(ReaderEvent::ReaderException(format!(
+                    "inventory failed: {}",
+                    e
+                )));
+                self.stop();
+                return;
+            }
+        };
+
+        let report = rospec.report.unwrap_or(self.config.report);
+        let now = Instant::now();
+        let sighting = Sighting {
+            rospec_id: id,
+            spec_index: spec_index as u16 + 1,
+            inventory_spec_id: inventory.id,
+            antenna,
+            timestamp: report::now_utc(),
+            statistics: &statistics,
+        };
+
+        for tag in &tags {
+            if let Some(run) = &mut self.run {
+                run.ai.observe(tag.epc.get_id(), now);
+            }
+
+            self.aggregate(&report.content, &sighting, tag);
+            self.access(&report.content, &sighting, tag);
+        }
+
+        if let Some(run) = &mut self.run {
+            run.ai.rounds += 1;
+        }
+
+        if report.n > 0 && self.reports.len() >= report.n as usize {
+            self.send_report(false);
+        }
+
+        self.check_triggers(&rospec, now);
+    }
+
+    /// Moves on to the next AISpec, or stops the ROSpec, as their stop
+    /// triggers say
+    fn check_triggers(&mut self, rospec: &RoSpec, now: Instant) {
+        let run = match &mut self.run {
+            Some(run) => run,
+            None => return,
+        };
+
+        if let RoStopTrigger::Duration(duration) = rospec.stop {
+            if now - run.started >= Duration::from_millis(duration as u64) {
+                self.stop();
+                return;
+            }
+        }
+
+        if !run.ai.done(&rospec.ai_specs[run.spec_index].stop, now) {
+            return;
+        }
+
+        run.spec_index += 1;
+        run.ai = AiProgress::new(now);
+
+        // ROSpecs with a duration start over until the time is up
+        let finished = run.spec_index >= rospec.ai_specs.len();
+        if finished {
+            run.spec_index = 0;
+        }
+
+        if rospec.report.unwrap_or(self.config.report).trigger == ReportTrigger::EndOfAiSpec {
+            self.send_report(false);
+        }
+
+        if finished && rospec.stop == RoStopTrigger::Null {
+            self.stop();
+        }
+    }
+
+    /// TagReportData of a tag, with the fields selected by `content`
+    fn tag_report(
+        &self,
+        content: &ContentSelector,
+        sighting: &Sighting,
+        tag: &InventoryTag,
+    ) -> TagReport {
+        let statistics = sighting.statistics;
+        let rssi = self
+            .rssi
+            .to_dbm(tag, statistics)
+            .round()
+            .clamp(-128.0, 127.0) as i8;
+        let channel = self
+            .backend
+            .info()
+            .frequencies
+            .iter()
+            .position(|&frequency| frequency == statistics.frequency)
+            .map(|index| index as u16 + 1);
+
+        TagReport {
+            epc: Bits::from_bytes(tag.epc.get_id()),
+            rospec_id: content.rospec_id.then_some(sighting.rospec_id),
+            spec_index: content.spec_index.then_some(sighting.spec_index),
+            inventory_spec_id: content
+                .inventory_spec_id
+                .then_some(sighting.inventory_spec_id),
+            antenna: content.antenna_id.then_some(sighting.antenna),
+            peak_rssi: content.peak_rssi.then_some(rssi),
+            channel_index: channel.filter(|_| content.channel_index),
+            first_seen: content.first_seen.then_some(sighting.timestamp),
+            last_seen: content.last_seen.then_some(sighting.timestamp),
+            seen_count: content.seen_count.then_some(1),
+            pc: content.pc.then(|| u16::from_be_bytes(tag.pc)),
+            access_spec_id: None,
+            results: Vec::new(),
+        }
+    }
+
+    /// Adds a tag seen to the report, or updates its TagReportData if it
+    /// was seen on the same antenna before
+    fn aggregate(&mut self, content: &ContentSelector, sighting: &Sighting, tag: &InventoryTag) {
+        let report = self.tag_report(content, sighting, tag);
+        let key = (tag.epc.get_id().to_vec(), sighting.antenna);
+
+        match self.aggregated.get(&key) {
+            Some(&index) => {
+                let seen = &mut self.reports[index];
+
+                seen.last_seen = report.last_seen;
+                seen.seen_count = seen.seen_count.map(|count| count.saturating_add(1));
+                seen.peak_rssi = seen.peak_rssi.max(report.peak_rssi);
+                seen.channel_index = report.channel_index;
+            }
+            None => {
+                self.aggregated.insert(key, self.reports.len());
+                self.reports.push(report);
+            }
+        }
+    }
+
+    /// Runs the first enabled AccessSpec targeting a tag on it, and
+    /// reports the results
+    fn access(&mut self, content: &ContentSelector, sighting: &Sighting, tag: &InventoryTag) {
+        let index = self.access_specs.iter().position(|spec| {
+            spec.enabled
+                && (spec.antenna == 0 || spec.antenna == sighting.antenna)
+                && (spec.rospec_id == 0 || spec.rospec_id == sighting.rospec_id)
+                && access::targets(spec, tag)
+        });
+        let index = match index {
+            Some(index) => index,
+            None => return,
+        };
+
+        let v1_1 = self
+            .client
+            .as_ref()
+            .is_some_and(|client| client.version == VERSION_1_1);
+        let spec = self.access_specs[index].clone();
+        let results = access::run(&mut self.backend, &spec, tag, v1_1);
+
+        // The AccessSpec is deleted once run as many times as requested
+        if let Some(count) = &mut self.access_specs[index].operation_count {
+            *count -= 1;
+            if *count == 0 {
+                self.access_specs.remove(index);
+            }
+        }
+
+        let mut report = self.tag_report(content, sighting, tag);
+        report.access_spec_id = content.access_spec_id.then_some(spec.id);
+        report.results = results;
+
+        let immediately = spec
+            .report_immediately
+            .unwrap_or(self.config.report_access_immediately);
+
+        if !immediately
+            || !self.send_new(
+                message::RO_ACCESS_REPORT,
+                report::encode_report(&[report.clone()]),
+            )
+        {
+            self.reports.push(report);
+        }
+    }
+}
+
+/// Reads messages from a connection until it's closed
+fn read_messages(id: u64, mut stream: TcpStream, inputs: Sender<Input>) {
+    loop {
+        match Message::read_from(&mut stream) {
+            Ok(message) => {
+                if inputs.send(Input::Message(id, message)).is_err() {
+                    return;
+                }
+            }
+            Err(_) => {
+                let _ = inputs.send(Input::Closed(id));
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,906 @@
+//! ROSpecs and AccessSpecs, as added by clients
+//!
+//! Only the parts of the specs the server can act on are kept. Start
+//! triggers other than Null and Immediate, GPI triggers, RF surveys and
+//! loop specs are rejected when decoding. Receiver settings and C1G2
+//! inventory commands in antenna configurations are accepted but ignored.
+
+use crate::codec::{param, Bits, Decoder, Encoder, LlrpError, Result, StatusCode};
+
+/// Protocol ID of EPCglobal Class 1 Gen 2
+pub const PROTOCOL_C1G2: u8 = 1;
+
+/// Error for a field value the server doesn't support
+fn unsupported(what: &str, value: impl std::fmt::Display) -> LlrpError {
+    LlrpError::new(
+        StatusCode::FieldError,
+        format!("unsupported {} {}", what, value),
+    )
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// State of an ROSpec
+pub enum RoSpecState {
+    /// Added, but not enabled
+    Disabled = 0,
+    /// Enabled, waiting for its start trigger
+    Inactive = 1,
+    /// Running
+    Active = 2,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// ROSpecStartTrigger
+pub enum StartTrigger {
+    /// Only started by START_ROSPEC
+    Null,
+    /// Started as soon as the ROSpec is enabled
+    Immediate,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// ROSpecStopTrigger
+pub enum RoStopTrigger {
+    /// Stops after its AISpecs or with STOP_ROSPEC
+    Null,
+    /// Stops after running for the given time (ms)
+    Duration(u32),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// AISpecStopTrigger
+pub enum AiStopTrigger {
+    /// Runs until the ROSpec stops
+    Null,
+    /// Stops after running for the given time (ms)
+    Duration(u32),
+    /// Stops depending on the tags observed
+    TagObservation(TagObservation),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// TagObservationTrigger
+pub struct TagObservation {
+    /// 0: N tag observations, 1: no new observation for T ms, 2: N
+    /// inventory rounds, 3: N unique tags, 4: no new unique tag for T ms
+    pub kind: u8,
+    /// Number of tags (N) of types 0 and 3
+    pub tags: u16,
+    /// Number of inventory rounds (N) of type 2
+    pub attempts: u16,
+    /// Idle time (T) of types 1 and 4 (ms)
+    pub idle: u16,
+    /// Time after which the AISpec stops anyway (ms), 0 for none
+    pub timeout: u32,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// AntennaConfiguration of an InventoryParameterSpec
+pub struct AntennaConfig {
+    /// Antenna the configuration applies to, 0 for all
+    pub antenna: u16,
+    /// Index in the transmit power table, `None` to keep the power
+    pub transmit_power: Option<u16>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// InventoryParameterSpec
+pub struct InventorySpec {
+    /// InventoryParameterSpecID
+    pub id: u16,
+    /// Air protocol, must be [`PROTOCOL_C1G2`]
+    pub protocol: u8,
+    /// Antenna settings used during inventory
+    pub antennas: Vec<AntennaConfig>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// AISpec: inventory on a set of antennas
+pub struct AiSpec {
+    /// Antennas to run inventory on, `[0]` for all
+    pub antennas: Vec<u16>,
+    /// When the AISpec stops
+    pub stop: AiStopTrigger,
+    /// Inventory settings, used in turn
+    pub inventory: Vec<InventorySpec>,
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// ROReportTrigger
+pub enum ReportTrigger {
+    /// Reports are sent on GET_REPORT or every N tags
+    None = 0,
+    /// Reports are sent at the end of each AISpec or every N tags
+    EndOfAiSpec = 1,
+    /// Reports are sent at the end of the ROSpec or every N tags
+    EndOfRoSpec = 2,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+/// TagReportContentSelector: the fields of TagReportData sent
+pub struct ContentSelector {
+    /// ROSpecID
+    pub rospec_id: bool,
+    /// SpecIndex
+    pub spec_index: bool,
+    /// InventoryParameterSpecID
+    pub inventory_spec_id: bool,
+    /// AntennaID
+    pub antenna_id: bool,
+    /// ChannelIndex
+    pub channel_index: bool,
+    /// PeakRSSI
+    pub peak_rssi: bool,
+    /// FirstSeenTimestampUTC
+    pub first_seen: bool,
+    /// LastSeenTimestampUTC
+    pub last_seen: bool,
+    /// TagSeenCount
+    pub seen_count: bool,
+    /// AccessSpecID
+    pub access_spec_id: bool,
+    /// C1G2_PC, from the C1G2EPCMemorySelector
+    pub pc: bool,
+}
+
+impl ContentSelector {
+    /// Bits of the selector fields, ROSpecID being the most significant
+    fn fields(&self) -> [bool; 10] {
+        [
+            self.rospec_id,
+            self.spec_index,
+            self.inventory_spec_id,
+            self.antenna_id,
+            self.channel_index,
+            self.peak_rssi,
+            self.first_seen,
+            self.last_seen,
+            self.seen_count,
+            self.access_spec_id,
+        ]
+    }
+
+    /// Decodes a TagReportContentSelector
+    pub fn decode(p: &mut Decoder) -> Result<Self> {
+        let bits = p.u16()?;
+        let bit = |i: u16| bits & (0x8000 >> i) != 0;
+
+        let mut selector = Self {
+            rospec_id: bit(0),
+            spec_index: bit(1),
+            inventory_spec_id: bit(2),
+            antenna_id: bit(3),
+            channel_index: bit(4),
+            peak_rssi: bit(5),
+            first_seen: bit(6),
+            last_seen: bit(7),
+            seen_count: bit(8),
+            access_spec_id: bit(9),
+            pc: false,
+        };
+
+        for mut memory in p.repeated(param::C1G2_EPC_MEMORY_SELECTOR)? {
+            selector.pc |= memory.u8()? & 0x40 != 0;
+        }
+
+        Ok(selector)
+    }
+
+    /// Encodes a TagReportContentSelector
+    pub fn encode(&self, e: &mut Encoder) {
+        let bits = self
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, &enabled)| enabled)
+            .fold(0, |bits, (i, _)| bits | (0x8000 >> i));
+
+        e.param(param::TAG_REPORT_CONTENT_SELECTOR, |e| {
+            e.u16(bits);
+            e.param(param::C1G2_EPC_MEMORY_SELECTOR, |e| {
+                e.u8(if self.pc { 0x40 } else { 0 });
+            });
+        });
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// ROReportSpec
+pub struct ReportSpec {
+    /// When reports are sent
+    pub trigger: ReportTrigger,
+    /// Number of TagReportData after which a report is sent anyway, 0
+    /// for no limit
+    pub n: u16,
+    /// Fields sent in each TagReportData
+    pub content: ContentSelector,
+}
+
+impl Default for ReportSpec {
+    fn default() -> Self {
+        Self {
+            trigger: ReportTrigger::EndOfRoSpec,
+            n: 0,
+            content: ContentSelector {
+                rospec_id: true,
+                antenna_id: true,
+                first_seen: true,
+                seen_count: true,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl ReportSpec {
+    /// Decodes an ROReportSpec
+    pub fn decode(p: &mut Decoder) -> Result<Self> {
+        let trigger = match p.u8()? {
+            0 => ReportTrigger::None,
+            1 => ReportTrigger::EndOfAiSpec,
+            2 => ReportTrigger::EndOfRoSpec,
+            trigger => return Err(unsupported("report trigger", trigger)),
+        };
+        let n = p.u16()?;
+        let content =
+            ContentSelector::decode(&mut p.required(param::TAG_REPORT_CONTENT_SELECTOR)?)?;
+
+        Ok(Self {
+            trigger,
+            n,
+            content,
+        })
+    }
+
+    /// Encodes an ROReportSpec
+    pub fn encode(&self, e: &mut Encoder) {
+        e.param(param::RO_REPORT_SPEC, |e| {
+            e.u8(self.trigger as u8).u16(self.n);
+            self.content.encode(e);
+        });
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// ROSpec: when and how to run inventory, and how to report tags
+pub struct RoSpec {
+    /// ROSpecID, not 0
+    pub id: u32,
+    /// Priority, 0 being the highest
+    pub priority: u8,
+    /// Current state
+    pub state: RoSpecState,
+    /// When the ROSpec starts
+    pub start: StartTrigger,
+    /// When the ROSpec stops
+    pub stop: RoStopTrigger,
+    /// Inventory to run, in turn
+    pub ai_specs: Vec<AiSpec>,
+    /// Reporting, the reader's default if `None`
+    pub report: Option<ReportSpec>,
+}
+
+impl RoSpec {
+    /// Decodes an ROSpec parameter body
+    pub fn decode(p: &mut Decoder) -> Result<Self> {
+        let id = p.u32()?;
+        let priority = p.u8()?;
+        let state = match p.u8()? {
+            0 => RoSpecState::Disabled,
+            1 => RoSpecState::Inactive,
+            2 => RoSpecState::Active,
+            state => return Err(unsupported("ROSpec state", state)),
+        };
+
+        let mut boundary = p.required(param::RO_BOUNDARY_SPEC)?;
+
+        let mut start_trigger = boundary.required(param::ROSPEC_START_TRIGGER)?;
+        let start = match start_trigger.u8()? {
+            0 => StartTrigger::Null,
+            1 => StartTrigger::Immediate,
+            trigger => return Err(unsupported("ROSpec start trigger", trigger)),
+        };
+
+        let mut stop_trigger = boundary.required(param::ROSPEC_STOP_TRIGGER)?;
+        let stop = match (stop_trigger.u8()?, stop_trigger.u32()?) {
+            (0, _) => RoStopTrigger::Null,
+            (1, duration) => RoStopTrigger::Duration(duration),
+            (trigger, _) => return Err(unsupported("ROSpec stop trigger", trigger)),
+        };
+
+        let mut ai_specs = Vec::new();
+        let mut report = None;
+
+        while let Some((kind, mut param)) = p.param()? {
+            match kind {
+                param::AI_SPEC => ai_specs.push(AiSpec::decode(&mut param)?),
+                param::RO_REPORT_SPEC => report = Some(ReportSpec::decode(&mut param)?),
+                kind => {
+                    return Err(LlrpError::new(
+                        StatusCode::UnsupportedParameter,
+                        format!("unsupported ROSpec parameter {}", kind),
+                    ))
+                }
+            }
+        }
+
+        if ai_specs.is_empty() {
+            return Err(LlrpError::new(
+                StatusCode::MissingParameter,
+                "ROSpec without AISpec",
+            ));
+        }
+
+        Ok(Self {
+            id,
+            priority,
+            state,
+            start,
+            stop,
+            ai_specs,
+            report,
+        })
+    }
+
+    /// Encodes the ROSpec as a parameter
+    pub fn encode(&self, e: &mut Encoder) {
+        e.param(param::ROSPEC, |e| {
+            e.u32(self.id).u8(self.priority).u8(self.state as u8);
+
+            e.param(param::RO_BOUNDARY_SPEC, |e| {
+                e.param(param::ROSPEC_START_TRIGGER, |e| {
+                    e.u8(match self.start {
+                        StartTrigger::Null => 0,
+                        StartTrigger::Immediate => 1,
+                    });
+                });
+                e.param(param::ROSPEC_STOP_TRIGGER, |e| {
+                    match self.stop {
+                        RoStopTrigger::Null => e.u8(0).u32(0),
+                        RoStopTrigger::Duration(duration) => e.u8(1).u32(duration),
+                    };
+                });
+            });
+
+            for ai_spec in &self.ai_specs {
+                ai_spec.encode(e);
+            }
+
+            if let Some(report) = &self.report {
+                report.encode(e);
+            }
+        });
+    }
+}
+
+impl AiSpec {
+    /// Decodes an AISpec parameter body
+    pub fn decode(p: &mut Decoder) -> Result<Self> {
+        let antennas = p.u16_array()?;
+
+        let mut trigger = p.required(param::AI_SPEC_STOP_TRIGGER)?;
+        let stop = match (trigger.u8()?, trigger.u32()?) {
+            (0, _) => AiStopTrigger::Null,
+            (1, duration) => AiStopTrigger::Duration(duration),
+            (3, _) => {
+                let mut observation = trigger.required(param::TAG_OBSERVATION_TRIGGER)?;
+                let kind = observation.u8()?;
+                if kind > 4 {
+                    return Err(unsupported("tag observation trigger", kind));
+                }
+                observation.u8()?;
+
+                AiStopTrigger::TagObservation(TagObservation {
+                    kind,
+                    tags: observation.u16()?,
+                    attempts: observation.u16()?,
+                    idle: observation.u16()?,
+                    timeout: observation.u32()?,
+                })
+            }
+            (trigger, _) => return Err(unsupported("AISpec stop trigger", trigger)),
+        };
+
+        let inventory = p
+            .repeated(param::INVENTORY_PARAMETER_SPEC)?
+            .iter_mut()
+            .map(InventorySpec::decode)
+            .collect::<Result<Vec<_>>>()?;
+
+        if inventory.is_empty() {
+            return Err(LlrpError::new(
+                StatusCode::MissingParameter,
+                "AISpec without InventoryParameterSpec",
+            ));
+        }
+
+        Ok(Self {
+            antennas,
+            stop,
+            inventory,
+        })
+    }
+
+    /// Encodes the AISpec as a parameter
+    pub fn encode(&self, e: &mut Encoder) {
+        e.param(param::AI_SPEC, |e| {
+            e.u16_array(&self.antennas);
+
+            e.param(param::AI_SPEC_STOP_TRIGGER, |e| match self.stop {
+                AiStopTrigger::Null => {
+                    e.u8(0).u32(0);
+                }
+                AiStopTrigger::Duration(duration) => {
+                    e.u8(1).u32(duration);
+                }
+                AiStopTrigger::TagObservation(observation) => {
+                    e.u8(3).u32(0);
+                    e.param(param::TAG_OBSERVATION_TRIGGER, |e| {
+                        e.u8(observation.kind)
+                            .u8(0)
+                            .u16(observation.tags)
+                            .u16(observation.attempts)
+                            .u16(observation.idle)
+                            .u32(observation.timeout);
+                    });
+                }
+            });
+
+            for inventory in &self.inventory {
+                inventory.encode(e);
+            }
+        });
+    }
+}
+
+impl InventorySpec {
+    /// Decodes an InventoryParameterSpec parameter body
+    pub fn decode(p: &mut Decoder) -> Result<Self> {
+        let id = p.u16()?;
+        let protocol = p.u8()?;
+
+        let mut antennas = Vec::new();
+        for mut config in p.repeated(param::ANTENNA_CONFIGURATION)? {
+            let antenna = config.u16()?;
+            let mut transmit_power = None;
+
+            while let Some((kind, mut param)) = config.param()? {
+                if kind == param::RF_TRANSMITTER {
+                    param.u16()?;
+                    param.u16()?;
+                    transmit_power = Some(param.u16()?);
+                }
+            }
+
+            antennas.push(AntennaConfig {
+                antenna,
+                transmit_power,
+            });
+        }
+        p.skip_rest()?;
+
+        Ok(Self {
+            id,
+            protocol,
+            antennas,
+        })
+    }
+
+    /// Encodes the InventoryParameterSpec as a parameter
+    pub fn encode(&self, e: &mut Encoder) {
+        e.param(param::INVENTORY_PARAMETER_SPEC, |e| {
+            e.u16(self.id).u8(self.protocol);
+
+            for config in &self.antennas {
+                e.param(param::ANTENNA_CONFIGURATION, |e| {
+                    e.u16(config.antenna);
+
+                    if let Some(power) = config.transmit_power {
+                        e.param(param::RF_TRANSMITTER, |e| {
+                            e.u16(1).u16(1).u16(power);
+                        });
+                    }
+                });
+            }
+        });
+    }
+
+    /// Transmit power index to use on `antenna`
+    pub fn transmit_power(&self, antenna: u16) -> Option<u16> {
+        self.antennas
+            .iter()
+            .find(|config| config.antenna == antenna)
+            .or_else(|| self.antennas.iter().find(|config| config.antenna == 0))
+            .and_then(|config| config.transmit_power)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// C1G2TargetTag: a pattern tag memory is compared to
+pub struct TargetTag {
+    /// Memory bank (0 Reserved, 1 EPC, 2 TID, 3 User)
+    pub bank: u8,
+    /// Whether matching tags are targeted, or non matching tags
+    pub matching: bool,
+    /// First bit compared
+    pub pointer: u16,
+    /// Bits compared
+    pub mask: Bits,
+    /// Expected values of the bits compared
+    pub data: Bits,
+}
+
+impl TargetTag {
+    /// Decodes a C1G2TargetTag parameter body
+    pub fn decode(p: &mut Decoder) -> Result<Self> {
+        let flags = p.u8()?;
+
+        Ok(Self {
+            bank: flags >> 6,
+            matching: flags & 0x20 != 0,
+            pointer: p.u16()?,
+            mask: p.bits()?,
+            data: p.bits()?,
+        })
+    }
+
+    /// Encodes the C1G2TargetTag as a parameter
+    pub fn encode(&self, e: &mut Encoder) {
+        e.param(param::C1G2_TARGET_TAG, |e| {
+            e.u8((self.bank << 6) | if self.matching { 0x20 } else { 0 })
+                .u16(self.pointer)
+                .bits(&self.mask)
+                .bits(&self.data);
+        });
+    }
+
+    /// Whether a tag with the given memory bank content is targeted.
+    /// `memory` is `None` if the content of the bank is unknown, which
+    /// only matches an empty mask.
+    pub fn targets(&self, memory: Option<&Bits>) -> bool {
+        let mut matches = true;
+
+        for i in 0..self.mask.len as usize {
+            if self.mask.get(i) != Some(true) {
+                continue;
+            }
+
+            let actual = memory.and_then(|memory| memory.get(self.pointer as usize + i));
+            if actual.is_none() || actual != self.data.get(i) {
+                matches = false;
+                break;
+            }
+        }
+
+        matches == self.matching
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// C1G2LockPayload privilege
+pub enum LockPrivilege {
+    /// Writable (readable for passwords) in the secured state only
+    ReadWrite = 0,
+    /// Locked for good
+    PermaLock = 1,
+    /// Unlocked for good
+    PermaUnlock = 2,
+    /// Writable (readable for passwords) in any state
+    Unlock = 3,
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// C1G2LockPayload data field
+pub enum LockField {
+    /// Kill password
+    KillPassword = 0,
+    /// Access password
+    AccessPassword = 1,
+    /// EPC memory bank
+    Epc = 2,
+    /// TID memory bank
+    Tid = 3,
+    /// User memory bank
+    User = 4,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// An operation run on a tag
+pub enum OpSpec {
+    /// C1G2Read
+    Read {
+        /// OpSpecID
+        id: u16,
+        /// Access password, 0 for none
+        password: u32,
+        /// Memory bank
+        bank: u8,
+        /// First word read
+        pointer: u16,
+        /// Number of words read
+        count: u16,
+    },
+    /// C1G2Write
+    Write {
+        /// OpSpecID
+        id: u16,
+        /// Access password, 0 for none
+        password: u32,
+        /// Memory bank
+        bank: u8,
+        /// First word written
+        pointer: u16,
+        /// Words written
+        data: Vec<u16>,
+    },
+    /// C1G2Kill
+    Kill {
+        /// OpSpecID
+        id: u16,
+        /// Kill password
+        password: u32,
+    },
+    /// C1G2Lock
+    Lock {
+        /// OpSpecID
+        id: u16,
+        /// Access password, 0 for none
+        password: u32,
+        /// Changes to the lock state
+        payloads: Vec<(LockPrivilege, LockField)>,
+    },
+}
+
+impl OpSpec {
+    /// Decodes an OpSpec parameter of type `kind`
+    pub fn decode(kind: u16, p: &mut Decoder) -> Result<Self> {
+        let id = p.u16()?;
+        let password = p.u32()?;
+
+        Ok(match kind {
+            param::C1G2_READ => Self::Read {
+                id,
+                password,
+                bank: p.u8()? >> 6,
+                pointer: p.u16()?,
+                count: p.u16()?,
+            },
+            param::C1G2_WRITE => Self::Write {
+                id,
+                password,
+                bank: p.u8()? >> 6,
+                pointer: p.u16()?,
+                data: p.u16_array()?,
+            },
+            param::C1G2_KILL => Self::Kill { id, password },
+            param::C1G2_LOCK => {
+                let mut payloads = Vec::new();
+
+                for mut payload in p.repeated(param::C1G2_LOCK_PAYLOAD)? {
+                    let privilege = match payload.u8()? {
+                        0 => LockPrivilege::ReadWrite,
+                        1 => LockPrivilege::PermaLock,
+                        2 => LockPrivilege::PermaUnlock,
+                        3 => LockPrivilege::Unlock,
+                        privilege => return Err(unsupported("lock privilege", privilege)),
+                    };
+                    let field = match payload.u8()? {
+                        0 => LockField::KillPassword,
+                        1 => LockField::AccessPassword,
+                        2 => LockField::Epc,
+                        3 => LockField::Tid,
+                        4 => LockField::User,
+                        field => return Err(unsupported("lock data field", field)),
+                    };
+
+                    payloads.push((privilege, field));
+                }
+
+                if payloads.is_empty() {
+                    return Err(LlrpError::new(
+                        StatusCode::MissingParameter,
+                        "C1G2Lock without C1G2LockPayload",
+                    ));
+                }
+
+                Self::Lock {
+                    id,
+                    password,
+                    payloads,
+                }
+            }
+            kind => {
+                return Err(LlrpError::new(
+                    StatusCode::UnsupportedParameter,
+                    format!("unsupported OpSpec {}", kind),
+                ))
+            }
+        })
+    }
+
+    /// Encodes the OpSpec as a parameter
+    pub fn encode(&self, e: &mut Encoder) {
+        match self {
+            Self::Read {
+                id,
+                password,
+                bank,
+                pointer,
+                count,
+            } => e.param(param::C1G2_READ, |e| {
+                e.u16(*id)
+                    .u32(*password)
+                    .u8(bank << 6)
+                    .u16(*pointer)
+                    .u16(*count);
+            }),
+            Self::Write {
+                id,
+                password,
+                bank,
+                pointer,
+                data,
+            } => e.param(param::C1G2_WRITE, |e| {
+                e.u16(*id)
+                    .u32(*password)
+                    .u8(bank << 6)
+                    .u16(*pointer)
+                    .u16_array(data);
+            }),
+            Self::Kill { id, password } => e.param(param::C1G2_KILL, |e| {
+                e.u16(*id).u32(*password);
+            }),
+            Self::Lock {
+                id,
+                password,
+                payloads,
+            } => e.param(param::C1G2_LOCK, |e| {
+                e.u16(*id).u32(*password);
+
+                for (privilege, field) in payloads {
+                    e.param(param::C1G2_LOCK_PAYLOAD, |e| {
+                        e.u8(*privilege as u8).u8(*field as u8);
+                    });
+                }
+            }),
+        };
+    }
+
+    /// OpSpecID
+    pub fn id(&self) -> u16 {
+        match self {
+            Self::Read { id, .. }
+            | Self::Write { id, .. }
+            | Self::Kill { id, .. }
+            | Self::Lock { id, .. } => *id,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// AccessSpec: operations run on the tags found by ROSpecs
+pub struct AccessSpec {
+    /// AccessSpecID, not 0
+    pub id: u32,
+    /// Antenna the tags must be found on, 0 for any
+    pub antenna: u16,
+    /// Air protocol, must be [`PROTOCOL_C1G2`]
+    pub protocol: u8,
+    /// Whether the AccessSpec is enabled
+    pub enabled: bool,
+    /// ROSpec the tags must be found by, 0 for any
+    pub rospec_id: u32,
+    /// Number of times the operations are run before the AccessSpec is
+    /// deleted, `None` to keep it
+    pub operation_count: Option<u16>,
+    /// Tags targeted, all patterns must match
+    pub targets: Vec<TargetTag>,
+    /// Operations run, in order
+    pub ops: Vec<OpSpec>,
+    /// Whether results are sent right away (AccessReportTrigger 1), or
+    /// with the ROSpec's reports
+    pub report_immediately: Option<bool>,
+}
+
+impl AccessSpec {
+    /// Decodes an AccessSpec parameter body
+    pub fn decode(p: &mut Decoder) -> Result<Self> {
+        let id = p.u32()?;
+        let antenna = p.u16()?;
+        let protocol = p.u8()?;
+        let enabled = p.u8()? & 0x80 != 0;
+        let rospec_id = p.u32()?;
+
+        let mut trigger = p.required(param::ACCESS_SPEC_STOP_TRIGGER)?;
+        let operation_count = match (trigger.u8()?, trigger.u16()?) {
+            (0, _) => None,
+            (1, count) => Some(count),
+            (trigger, _) => return Err(unsupported("AccessSpec stop trigger", trigger)),
+        };
+
+        let mut command = p.required(param::ACCESS_COMMAND)?;
+
+        let mut tag_spec = command.required(param::C1G2_TAG_SPEC)?;
+        let targets = tag_spec
+            .repeated(param::C1G2_TARGET_TAG)?
+            .iter_mut()
+            .map(TargetTag::decode)
+            .collect::<Result<Vec<_>>>()?;
+
+        if targets.is_empty() {
+            return Err(LlrpError::new(
+                StatusCode::MissingParameter,
+                "C1G2TagSpec without C1G2TargetTag",
+            ));
+        }
+
+        let mut ops = Vec::new();
+        while let Some((kind, mut param)) = command.param()? {
+            ops.push(OpSpec::decode(kind, &mut param)?);
+        }
+
+        if ops.is_empty() {
+            return Err(LlrpError::new(
+                StatusCode::MissingParameter,
+                "AccessCommand without OpSpec",
+            ));
+        }
+
+        let report_immediately = match p.optional(param::ACCESS_REPORT_SPEC)? {
+            Some(mut report) => Some(report.u8()? == 1),
+            None => None,
+        };
+
+        Ok(Self {
+            id,
+            antenna,
+            protocol,
+            enabled,
+            rospec_id,
+            operation_count,
+            targets,
+            ops,
+            report_immediately,
+        })
+    }
+
+    /// Encodes the AccessSpec as a parameter
+    pub fn encode(&self, e: &mut Encoder) {
+        e.param(param::ACCESS_SPEC, |e| {
+            e.u32(self.id)
+                .u16(self.antenna)
+                .u8(self.protocol)
+                .u8(if self.enabled { 0x80 } else { 0 })
+                .u32(self.rospec_id);
+
+            e.param(param::ACCESS_SPEC_STOP_TRIGGER, |e| {
+                match self.operation_count {
+                    Some(count) => e.u8(1).u16(count),
+                    None => e.u8(0).u16(0),
+                };
+            });
+
+            e.param(param::ACCESS_COMMAND, |e| {
+                e.param(param::C1G2_TAG_SPEC, |e| {
+                    for target in &self.targets {
+                        target.encode(e);
+                    }
+                });
+
+                for op in &self.ops {
+                    op.encode(e);
+                }
+            });
+
+            if let Some(immediately) = self.report_immediately {
+                e.param(param::ACCESS_REPORT_SPEC, |e| {
+                    e.u8(immediately as u8);
+                });
+            }
+        });
+    }
+}
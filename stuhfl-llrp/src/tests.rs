@@ -0,0 +1,824 @@
+use crate::backend::*;
+use crate::client::Client;
+use crate::codec::*;
+use crate::report::*;
+use crate::server::Server;
+use crate::spec::*;
+use libstuhfl::error::{Error, Result};
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use libstuhfl::rssi::RssiModel;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use stuhfl_common::test_support;
+
+/// A tag of the fake reader
+#[derive(Clone)]
+struct FakeTag {
+    epc: Vec<u8>,
+    user: Vec<u8>,
+    access_password: u32,
+    kill_password: u32,
+    user_locked: bool,
+    killed: bool,
+}
+
+fn fake_tag(epc: u8) -> FakeTag {
+    FakeTag {
+        epc: vec![0xE2, 0x00, 0x00, epc],
+        user: vec![0; 8],
+        access_password: 0,
+        kill_password: 0,
+        user_locked: false,
+        killed: false,
+    }
+}
+
+#[derive(Default)]
+struct FakeState {
+    tags: Vec<FakeTag>,
+    selected: Option<usize>,
+    antenna: Option<Antenna>,
+    levels: Vec<Option<i8>>,
+    rounds: u32,
+}
+
+/// Reader answering from tags in memory
+struct Fake {
+    state: Arc<Mutex<FakeState>>,
+    info: ReaderInfo,
+}
+
+impl Fake {
+    fn tag(&self, password: Option<Password>) -> Result<usize> {
+        let state = self.state.lock().unwrap();
+        let index = state.selected.ok_or(Error::ChipNoResp)?;
+        let tag = &state.tags[index];
+
+        let given = password.map_or(0, |p| u32::from_be_bytes(p.into_inner()));
+        if tag.access_password != 0 && given != tag.access_password {
+            return Err(Error::Gen2Access);
+        }
+
+        Ok(index)
+    }
+}
+
+impl Backend for Fake {
+    fn info(&self) -> &ReaderInfo {
+        &self.info
+    }
+
+    fn set_rf(&mut self, antenna: Antenna, tx_output_level: Option<i8>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.antenna = Some(antenna);
+        state.levels.push(tx_output_level);
+        Ok(())
+    }
+
+    fn inventory(&mut self) -> Result<(InventoryStatistics, Vec<InventoryTag>)> {
+        let mut state = self.state.lock().unwrap();
+        state.rounds += 1;
+
+        let antenna = state.antenna.unwrap_or(Antenna::Antenna1);
+        let tags: Vec<InventoryTag> = state
+            .tags
+            .iter()
+            .filter(|tag| !tag.killed)
+            .map(|tag| InventoryTag {
+                antenna,
+                ..test_support::tag(tag.epc.clone())
+            })
+            .collect();
+
+        let statistics = InventoryStatistics {
+            timestamp: 0,
+            round_count: state.rounds,
+            tuning_status: TuningStatus::Tuned,
+            rssi_log_mean: 6,
+            sensitivity: -68,
+            final_q: 4,
+            frequency: 866900,
+            adc: 0,
+            tag_count: tags.len() as u32,
+            empty_slot_count: 0,
+            slot_count: 16,
+            collision_count: 0,
+            preamble_err_count: 0,
+            crc_err_count: 0,
+            rx_count_err_count: 0,
+            resend_ack_count: 0,
+            noise_suspicion_count: 0,
+        };
+
+        Ok((statistics, tags))
+    }
+
+    fn select(&mut self, epc: &Epc) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let index = state
+            .tags
+            .iter()
+            .position(|tag| tag.epc == epc.get_id() && !tag.killed);
+
+        state.selected = Some(index.ok_or(Error::Gen2Select)?);
+        Ok(())
+    }
+
+    fn clear_select(&mut self) -> Result<()> {
+        self.state.lock().unwrap().selected = None;
+        Ok(())
+    }
+
+    fn read(
+        &mut self,
+        bank: MemoryBank,
+        word_address: u32,
+        num_bytes: u8,
+        password: Option<Password>,
+    ) -> Result<Vec<u8>> {
+        let index = self.tag(password)?;
+        let state = self.state.lock().unwrap();
+        assert!(matches!(bank, MemoryBank::User));
+
+        let start = word_address as usize * 2;
+        state.tags[index]
+            .user
+            .get(start..start + num_bytes as usize)
+            .map(|data| data.to_vec())
+            .ok_or(Error::Gen2MemOverRun)
+    }
+
+    fn write(
+        &mut self,
+        bank: MemoryBank,
+        word_address: u32,
+        data: [u8; 2],
+        password: Option<Password>,
+    ) -> Result<()> {
+        let index = self.tag(password)?;
+        let mut state = self.state.lock().unwrap();
+        let tag = &mut state.tags[index];
+        assert!(matches!(bank, MemoryBank::User));
+
+        if tag.user_locked && password.is_none() {
+            return Err(Error::Gen2MemLocked);
+        }
+
+        let start = word_address as usize * 2;
+        tag.user
+            .get_mut(start..start + 2)
+            .ok_or(Error::Gen2MemOverRun)?
+            .copy_from_slice(&data);
+        Ok(())
+    }
+
+    fn lock(&mut self, lock: &Gen2LockCfg, password: Option<Password>) -> Result<()> {
+        let index = self.tag(password)?;
+        let mut state = self.state.lock().unwrap();
+
+        // User memory, Lock action
+        let payload = lock.payload();
+        assert_eq!(payload, [0x00, 0xC0, 0x20]);
+        state.tags[index].user_locked = true;
+        Ok(())
+    }
+
+    fn kill(&mut self, kill_password: Password) -> Result<()> {
+        let index = self.tag(None)?;
+        let mut state = self.state.lock().unwrap();
+        let tag = &mut state.tags[index];
+
+        if u32::from_be_bytes(kill_password.into_inner()) != tag.kill_password {
+            return Err(Error::Gen2Access);
+        }
+
+        tag.killed = true;
+        Ok(())
+    }
+}
+
+/// Starts a server with `tags` in the field and connects to it
+fn start(tags: Vec<FakeTag>) -> (Client, Arc<Mutex<FakeState>>) {
+    let state = Arc::new(Mutex::new(FakeState {
+        tags,
+        ..Default::default()
+    }));
+    let fake = Fake {
+        state: state.clone(),
+        info: ReaderInfo {
+            firmware: "3.4.0.0".into(),
+            antennas: 4,
+            frequencies: vec![865700, 866300, 866900, 867500],
+            max_power_dbm: 20.0,
+            reader_id: vec![0; 8],
+        },
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || Server::new(fake, RssiModel::default()).serve(listener));
+
+    let mut client = Client::connect(address).unwrap();
+    client.set_timeout(Some(Duration::from_secs(10))).unwrap();
+    assert_eq!(
+        client.events().unwrap(),
+        vec![ReaderEvent::ConnectionAttempt(0)]
+    );
+
+    (client, state)
+}
+
+/// ROSpec running `rounds` inventory rounds on antenna 1
+fn rospec(id: u32, rounds: u16, report: Option<ReportSpec>) -> RoSpec {
+    RoSpec {
+        id,
+        priority: 0,
+        state: RoSpecState::Disabled,
+        start: StartTrigger::Null,
+        stop: RoStopTrigger::Null,
+        ai_specs: vec![AiSpec {
+            antennas: vec![1],
+            stop: AiStopTrigger::TagObservation(TagObservation {
+                kind: 2,
+                tags: 0,
+                attempts: rounds,
+                idle: 0,
+                timeout: 0,
+            }),
+            inventory: vec![InventorySpec {
+                id: 7,
+                protocol: PROTOCOL_C1G2,
+                antennas: vec![AntennaConfig {
+                    antenna: 0,
+                    transmit_power: Some(15),
+                }],
+            }],
+        }],
+        report,
+    }
+}
+
+/// AccessSpec running `ops` once on the tag with `epc`
+fn access_spec(id: u32, epc: &[u8], ops: Vec<OpSpec>) -> AccessSpec {
+    AccessSpec {
+        id,
+        antenna: 0,
+        protocol: PROTOCOL_C1G2,
+        enabled: false,
+        rospec_id: 0,
+        operation_count: Some(1),
+        targets: vec![TargetTag {
+            bank: 1,
+            matching: true,
+            pointer: 32,
+            mask: Bits::from_bytes(&vec![0xFF; epc.len()]),
+            data: Bits::from_bytes(epc),
+        }],
+        ops,
+        report_immediately: Some(true),
+    }
+}
+
+/// Enables ROSpec start and end events
+fn enable_rospec_events(client: &mut Client) {
+    let mut e = Encoder::new();
+    e.u8(0).param(param::READER_EVENT_NOTIFICATION_SPEC, |e| {
+        e.param(param::EVENT_NOTIFICATION_STATE, |e| {
+            e.u16(EventType::RoSpec as u16).u8(0x80);
+        });
+    });
+
+    client
+        .transact(message::SET_READER_CONFIG, e.into_bytes())
+        .unwrap();
+}
+
+/// Status code of a response
+fn status(response: &Message) -> u16 {
+    decode_status(&mut response.decoder()).unwrap().0
+}
+
+#[test]
+fn codec() {
+    let message = Message::new(VERSION_1_1, message::ADD_ROSPEC, 0x01020304, vec![0xAA]);
+    let bytes = message.to_bytes();
+    assert_eq!(
+        bytes,
+        [0x08, 0x14, 0, 0, 0, 11, 0x01, 0x02, 0x03, 0x04, 0xAA]
+    );
+    assert_eq!(Message::read_from(&mut &bytes[..]).unwrap(), message);
+    assert!(Message::read_from(&mut &[0x04, 0x14, 0, 0, 0, 2, 0, 0, 0, 1][..]).is_err());
+
+    // TLV with nested TLV, then TV
+    let mut e = Encoder::new();
+    e.param(param::ROSPEC_START_TRIGGER, |e| {
+        e.u8(1).param(param::UTC_TIMESTAMP, |e| {
+            e.u64(5);
+        });
+    })
+    .tv(param::ANTENNA_ID, &[0, 3]);
+    let bytes = e.into_bytes();
+    assert_eq!(&bytes[..5], [0x00, 0xB3, 0x00, 17, 1]);
+    assert_eq!(&bytes[17..], [0x81, 0, 3]);
+
+    let mut d = Decoder::new(&bytes);
+    let mut trigger = d.required(param::ROSPEC_START_TRIGGER).unwrap();
+    assert_eq!(trigger.u8().unwrap(), 1);
+    assert_eq!(
+        trigger
+            .required(param::UTC_TIMESTAMP)
+            .unwrap()
+            .u64()
+            .unwrap(),
+        5
+    );
+    assert_eq!(d.peek(), Some(param::ANTENNA_ID));
+    assert_eq!(d.param().unwrap().unwrap().1.u16().unwrap(), 3);
+    assert!(d.is_empty());
+    assert!(Decoder::new(&[0x00, 0xB3, 0x00, 9]).param().is_err());
+
+    let bits = Bits {
+        len: 10,
+        bytes: vec![0x80, 0x40],
+    };
+    assert_eq!(bits.get(0), Some(true));
+    assert_eq!(bits.get(9), Some(true));
+    assert_eq!(bits.get(8), Some(false));
+    assert_eq!(bits.get(10), None);
+}
+
+#[test]
+fn spec_roundtrip() {
+    let mut rospec = rospec(
+        3,
+        2,
+        Some(ReportSpec {
+            trigger: ReportTrigger::EndOfAiSpec,
+            n: 10,
+            content: ContentSelector {
+                peak_rssi: true,
+                pc: true,
+                ..Default::default()
+            },
+        }),
+    );
+    rospec.stop = RoStopTrigger::Duration(500);
+    rospec.ai_specs[0].stop = AiStopTrigger::Duration(100);
+
+    let mut e = Encoder::new();
+    rospec.encode(&mut e);
+    let bytes = e.into_bytes();
+    let decoded = RoSpec::decode(&mut Decoder::new(&bytes).required(param::ROSPEC).unwrap());
+    assert_eq!(decoded, Ok(rospec));
+
+    let spec = access_spec(
+        9,
+        &[0xE2, 0x01],
+        vec![
+            OpSpec::Write {
+                id: 1,
+                password: 0x11223344,
+                bank: 3,
+                pointer: 4,
+                data: vec![0xBEEF],
+            },
+            OpSpec::Lock {
+                id: 2,
+                password: 0,
+                payloads: vec![(LockPrivilege::PermaLock, LockField::Epc)],
+            },
+            OpSpec::Kill { id: 3, password: 1 },
+        ],
+    );
+
+    let mut e = Encoder::new();
+    spec.encode(&mut e);
+    let bytes = e.into_bytes();
+    let decoded =
+        AccessSpec::decode(&mut Decoder::new(&bytes).required(param::ACCESS_SPEC).unwrap());
+    assert_eq!(decoded, Ok(spec));
+
+    let reports = vec![
+        TagReport {
+            epc: Bits::from_bytes(&[0x30; 12]),
+            antenna: Some(2),
+            peak_rssi: Some(-55),
+            seen_count: Some(4),
+            ..Default::default()
+        },
+        TagReport {
+            epc: Bits::from_bytes(&[0xE2, 0x01]),
+            access_spec_id: Some(9),
+            results: vec![OpResult::Read {
+                id: 1,
+                result: 0,
+                data: vec![0x1234],
+            }],
+            ..Default::default()
+        },
+    ];
+    let body = encode_report(&reports);
+    // EPC-96 is sent as a TV parameter
+    assert_eq!(body[4], 0x80 | param::EPC_96 as u8);
+    assert_eq!(decode_report(&body), Ok(reports));
+
+    // Unsupported start trigger
+    let mut e = Encoder::new();
+    e.u32(1).u8(0).u8(0).param(param::RO_BOUNDARY_SPEC, |e| {
+        e.param(param::ROSPEC_START_TRIGGER, |e| {
+            e.u8(3);
+        });
+    });
+    let bytes = e.into_bytes();
+    assert_eq!(
+        RoSpec::decode(&mut Decoder::new(&bytes))
+            .unwrap_err()
+            .status,
+        StatusCode::FieldError
+    );
+}
+
+#[test]
+fn target_tag() {
+    let target = |pointer, mask: &[u8], data: &[u8], matching| TargetTag {
+        bank: 1,
+        matching,
+        pointer,
+        mask: Bits::from_bytes(mask),
+        data: Bits::from_bytes(data),
+    };
+    let memory = Bits::from_bytes(&[0x00, 0x00, 0x30, 0x00, 0xE2, 0x80]);
+
+    assert!(target(32, &[0xFF], &[0xE2], true).targets(Some(&memory)));
+    assert!(target(36, &[0xF0], &[0x20], true).targets(Some(&memory)));
+    assert!(!target(32, &[0xFF], &[0xE3], true).targets(Some(&memory)));
+    assert!(target(32, &[0xFF], &[0xE3], false).targets(Some(&memory)));
+    // Past the end of the bank, or unknown content
+    assert!(!target(40, &[0xFF, 0xFF], &[0x80, 0x00], true).targets(Some(&memory)));
+    assert!(!target(0, &[0xFF], &[0x00], true).targets(None));
+    assert!(target(0, &[], &[], true).targets(None));
+}
+
+#[test]
+fn capabilities() {
+    let (mut client, _) = start(vec![]);
+
+    let response = client
+        .transact(message::GET_READER_CAPABILITIES, vec![0])
+        .unwrap();
+    let mut d = response.decoder();
+    d.required(param::LLRP_STATUS).unwrap();
+
+    let mut general = d.required(param::GENERAL_DEVICE_CAPABILITIES).unwrap();
+    assert_eq!(general.u16().unwrap(), 4);
+    general.u16().unwrap();
+    general.u32().unwrap();
+    assert_eq!(general.u32().unwrap(), 3993);
+    assert_eq!(general.utf8().unwrap(), "3.4.0.0");
+
+    d.required(param::LLRP_CAPABILITIES).unwrap();
+
+    let mut regulatory = d.required(param::REGULATORY_CAPABILITIES).unwrap();
+    regulatory.u32().unwrap();
+    let mut band = regulatory.required(param::UHF_BAND_CAPABILITIES).unwrap();
+    let powers = band
+        .repeated(param::TRANSMIT_POWER_LEVEL_TABLE_ENTRY)
+        .unwrap();
+    assert_eq!(powers.len(), 20);
+    let mut highest = powers[19].clone();
+    assert_eq!(highest.u16().unwrap(), 20);
+    assert_eq!(highest.i16().unwrap(), 2000);
+
+    let mut frequencies = band.required(param::FREQUENCY_INFORMATION).unwrap();
+    assert_eq!(frequencies.u8().unwrap(), 0x80);
+    let mut table = frequencies.required(param::FREQUENCY_HOP_TABLE).unwrap();
+    table.u16().unwrap();
+    assert_eq!(table.u16().unwrap(), 4);
+    assert_eq!(table.u32().unwrap(), 865700);
+
+    d.required(param::C1G2_LLRP_CAPABILITIES).unwrap();
+    assert!(d.is_empty());
+
+    // Single part of the capabilities
+    let response = client
+        .transact(message::GET_READER_CAPABILITIES, vec![2])
+        .unwrap();
+    let mut d = response.decoder();
+    d.required(param::LLRP_STATUS).unwrap();
+    d.required(param::LLRP_CAPABILITIES).unwrap();
+    assert!(d.is_empty());
+
+    client.close().unwrap();
+}
+
+#[test]
+fn inventory() {
+    let (mut client, state) = start(vec![fake_tag(1), fake_tag(2)]);
+    enable_rospec_events(&mut client);
+
+    let report = ReportSpec {
+        trigger: ReportTrigger::EndOfRoSpec,
+        n: 0,
+        content: ContentSelector {
+            rospec_id: true,
+            inventory_spec_id: true,
+            antenna_id: true,
+            channel_index: true,
+            peak_rssi: true,
+            seen_count: true,
+            pc: true,
+            ..Default::default()
+        },
+    };
+
+    client.add_rospec(&rospec(1, 3, Some(report))).unwrap();
+    client.enable_rospec(1).unwrap();
+    client.start_rospec(1).unwrap();
+
+    assert_eq!(
+        client.events().unwrap(),
+        vec![ReaderEvent::RoSpec { event: 0, id: 1 }]
+    );
+
+    let mut reports = client.reports().unwrap();
+    reports.sort_by(|a, b| a.epc.bytes.cmp(&b.epc.bytes));
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].epc, Bits::from_bytes(&[0xE2, 0x00, 0x00, 0x01]));
+
+    for report in &reports {
+        assert_eq!(report.rospec_id, Some(1));
+        assert_eq!(report.inventory_spec_id, Some(7));
+        assert_eq!(report.antenna, Some(1));
+        assert_eq!(report.channel_index, Some(3));
+        assert_eq!(report.seen_count, Some(3));
+        assert_eq!(report.pc, Some(0x1000));
+        assert!(report.peak_rssi.is_some());
+        assert_eq!(report.first_seen, None);
+    }
+
+    assert_eq!(
+        client.events().unwrap(),
+        vec![ReaderEvent::RoSpec { event: 1, id: 1 }]
+    );
+
+    {
+        let state = state.lock().unwrap();
+        assert_eq!(state.rounds, 3);
+        // Power index 15 is 5 dB below the highest level
+        assert_eq!(state.levels, vec![Some(-5); 3]);
+    }
+
+    // Back to inactive, and started again right away once enabled
+    let response = client.transact(message::GET_ROSPECS, Vec::new()).unwrap();
+    let mut d = response.decoder();
+    d.required(param::LLRP_STATUS).unwrap();
+    let rospec = RoSpec::decode(&mut d.required(param::ROSPEC).unwrap()).unwrap();
+    assert_eq!(rospec.state, RoSpecState::Inactive);
+
+    let mut immediate = self::rospec(2, 1, None);
+    immediate.start = StartTrigger::Immediate;
+    client.add_rospec(&immediate).unwrap();
+    client.enable_rospec(2).unwrap();
+
+    assert_eq!(
+        client.events().unwrap(),
+        vec![ReaderEvent::RoSpec { event: 0, id: 2 }]
+    );
+    // Default content: ROSpecID, antenna, first seen and seen count
+    let reports = client.reports().unwrap();
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].rospec_id, Some(2));
+    assert!(reports[0].first_seen.is_some());
+    assert_eq!(reports[0].peak_rssi, None);
+
+    // Nothing left to report
+    client.send(message::GET_REPORT, Vec::new()).unwrap();
+    assert_eq!(client.reports().unwrap(), vec![]);
+
+    client.delete_rospec(0).unwrap();
+    client.close().unwrap();
+}
+
+#[test]
+fn read_write() {
+    let mut tag = fake_tag(1);
+    tag.user = vec![0x12, 0x34, 0x56, 0x78, 0, 0, 0, 0];
+    let (mut client, state) = start(vec![tag, fake_tag(2)]);
+
+    let spec = access_spec(
+        5,
+        &[0xE2, 0x00, 0x00, 0x01],
+        vec![
+            OpSpec::Write {
+                id: 1,
+                password: 0,
+                bank: 3,
+                pointer: 2,
+                data: vec![0xBEEF, 0xCAFE],
+            },
+            OpSpec::Read {
+                id: 2,
+                password: 0,
+                bank: 3,
+                pointer: 1,
+                count: 3,
+            },
+            // Past the end, but never run as the read fails
+            OpSpec::Read {
+                id: 3,
+                password: 0,
+                bank: 3,
+                pointer: 8,
+                count: 1,
+            },
+        ],
+    );
+    client.add_access_spec(&spec).unwrap();
+    client.enable_access_spec(5).unwrap();
+    assert_eq!(client.access_specs().unwrap().len(), 1);
+
+    client.add_rospec(&rospec(1, 2, None)).unwrap();
+    client.enable_rospec(1).unwrap();
+    client.start_rospec(1).unwrap();
+
+    // Sent right away
+    let reports = client.reports().unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].epc.bytes, [0xE2, 0x00, 0x00, 0x01]);
+    assert_eq!(
+        reports[0].results,
+        vec![
+            OpResult::Write {
+                id: 1,
+                result: 0,
+                words: 2
+            },
+            OpResult::Read {
+                id: 2,
+                result: 0,
+                data: vec![0x5678, 0xBEEF, 0xCAFE]
+            },
+            OpResult::Read {
+                id: 3,
+                result: 1,
+                data: vec![]
+            },
+        ]
+    );
+
+    // Inventory report of both tags
+    assert_eq!(client.reports().unwrap().len(), 2);
+
+    // Run once, then deleted
+    assert_eq!(client.access_specs().unwrap(), vec![]);
+    assert_eq!(
+        state.lock().unwrap().tags[0].user,
+        [0x12, 0x34, 0x56, 0x78, 0xBE, 0xEF, 0xCA, 0xFE]
+    );
+    assert_eq!(state.lock().unwrap().selected, None);
+}
+
+#[test]
+fn lock_kill() {
+    let mut locked = fake_tag(1);
+    locked.access_password = 0x11223344;
+    let mut killed = fake_tag(2);
+    killed.kill_password = 0xDEADBEEF;
+    let (mut client, state) = start(vec![locked, killed, fake_tag(3)]);
+
+    // Negotiate LLRP 1.1 for its result codes
+    let response = client
+        .request(message::GET_SUPPORTED_VERSION, Vec::new())
+        .unwrap();
+    assert_eq!(response.body[..2], [VERSION_1_0_1, VERSION_1_1]);
+    client
+        .transact(message::SET_PROTOCOL_VERSION, vec![VERSION_1_1])
+        .unwrap();
+    client.set_version(VERSION_1_1);
+
+    let lock = |id, password| {
+        access_spec(
+            id,
+            &[0xE2, 0x00, 0x00, 0x01],
+            vec![OpSpec::Lock {
+                id: 1,
+                password,
+                payloads: vec![(LockPrivilege::ReadWrite, LockField::User)],
+            }],
+        )
+    };
+    client.add_access_spec(&lock(1, 0x12345678)).unwrap();
+    client.add_access_spec(&lock(2, 0x11223344)).unwrap();
+    client
+        .add_access_spec(&access_spec(
+            3,
+            &[0xE2, 0x00, 0x00, 0x02],
+            vec![OpSpec::Kill {
+                id: 1,
+                password: 0xDEADBEEF,
+            }],
+        ))
+        .unwrap();
+    client
+        .add_access_spec(&access_spec(
+            4,
+            &[0xE2, 0x00, 0x00, 0x03],
+            vec![OpSpec::Kill { id: 1, password: 0 }],
+        ))
+        .unwrap();
+    client.enable_access_spec(0).unwrap();
+
+    client.add_rospec(&rospec(1, 2, None)).unwrap();
+    client.enable_rospec(1).unwrap();
+    client.start_rospec(1).unwrap();
+
+    let mut results = Vec::new();
+    for _ in 0..4 {
+        let report = client.reports().unwrap().remove(0);
+        results.push((report.epc.bytes[3], report.results));
+    }
+    results.sort_by_key(|(epc, results)| (*epc, results[0].result()));
+
+    assert_eq!(
+        results,
+        vec![
+            // Wrong access password first (1.1 code 5), then locked
+            (1, vec![OpResult::Lock { id: 1, result: 0 }]),
+            (1, vec![OpResult::Lock { id: 1, result: 5 }]),
+            (2, vec![OpResult::Kill { id: 1, result: 0 }]),
+            // Zero kill password
+            (3, vec![OpResult::Kill { id: 1, result: 1 }]),
+        ]
+    );
+
+    let response = client.recv_kind(message::RO_ACCESS_REPORT).unwrap();
+    assert_eq!(response.version, VERSION_1_1);
+    let tags = decode_report(&response.body).unwrap();
+    // The killed tag was found in the first round only
+    assert_eq!(tags.len(), 3);
+
+    let state = state.lock().unwrap();
+    assert!(state.tags[0].user_locked);
+    assert!(state.tags[1].killed);
+}
+
+#[test]
+fn errors() {
+    let (mut client, _) = start(vec![]);
+
+    // Unsupported message type
+    let response = client.request(message::KEEPALIVE, Vec::new()).unwrap();
+    assert_eq!(response.kind, message::ERROR_MESSAGE);
+    assert_eq!(status(&response), StatusCode::UnsupportedMessage as u16);
+
+    client.add_rospec(&rospec(1, 1, None)).unwrap();
+
+    let mut e = Encoder::new();
+    rospec(1, 1, None).encode(&mut e);
+    let response = client.request(message::ADD_ROSPEC, e.into_bytes()).unwrap();
+    assert_eq!(response.kind, message::ADD_ROSPEC_RESPONSE);
+    assert_eq!(status(&response), StatusCode::Invalid as u16);
+
+    let mut loud = rospec(2, 1, None);
+    loud.ai_specs[0].inventory[0].antennas[0].transmit_power = Some(21);
+    let mut e = Encoder::new();
+    loud.encode(&mut e);
+    let response = client.request(message::ADD_ROSPEC, e.into_bytes()).unwrap();
+    assert_eq!(status(&response), StatusCode::OutOfRange as u16);
+
+    // Disabled, unknown
+    let response = client
+        .request(message::START_ROSPEC, 1u32.to_be_bytes().to_vec())
+        .unwrap();
+    assert_eq!(status(&response), StatusCode::Invalid as u16);
+    let response = client
+        .request(message::ENABLE_ROSPEC, 9u32.to_be_bytes().to_vec())
+        .unwrap();
+    assert_eq!(status(&response), StatusCode::FieldError as u16);
+
+    // Truncated message
+    let response = client.request(message::DELETE_ROSPEC, vec![0, 0]).unwrap();
+    assert_eq!(status(&response), StatusCode::FieldError as u16);
+
+    // Only one client at a time
+    let address = client.peer_addr().unwrap();
+    let mut second = Client::connect(address).unwrap();
+    second.set_timeout(Some(Duration::from_secs(10))).unwrap();
+    assert_eq!(
+        second.events().unwrap(),
+        vec![ReaderEvent::ConnectionAttempt(2)]
+    );
+    assert!(second.recv().is_err());
+
+    // Free again after closing
+    client.close().unwrap();
+    let mut third = Client::connect(address).unwrap();
+    third.set_timeout(Some(Duration::from_secs(10))).unwrap();
+    assert_eq!(
+        third.events().unwrap(),
+        vec![ReaderEvent::ConnectionAttempt(0)]
+    );
+}
@@ -0,0 +1,392 @@
+//! REST and WebSocket endpoints
+
+use crate::error::ApiError;
+use crate::reader::{Event, InventoryState, ReaderHandle};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Creates the routes of the API
+pub(crate) fn router(reader: ReaderHandle) -> Router {
+    Router::new()
+        .route("/config", get(get_config).put(put_config))
+        .route("/tune", post(tune))
+        .route("/read", post(read))
+        .route("/write", post(write))
+        .route("/lock", post(lock))
+        .route("/sweep", post(sweep))
+        .route("/inventory", get(inventory))
+        .route("/inventory/start", post(start_inventory))
+        .route("/inventory/stop", post(stop_inventory))
+        .route("/events", get(events))
+        .with_state(reader)
+}
+
+#[derive(Deserialize)]
+/// Body of `POST /tune`
+pub(crate) struct TuneRequest {
+    /// Tuning algorithm to run
+    #[serde(default = "default_tuning")]
+    pub(crate) algorithm: TuningAlgorithm,
+}
+
+/// Tuning algorithm used unless requested otherwise
+fn default_tuning() -> TuningAlgorithm {
+    TuningAlgorithm::Exact
+}
+
+#[derive(Deserialize)]
+/// Body of `POST /read`
+pub(crate) struct ReadRequest {
+    /// EPC (hex) of the tag to read, any tag if not set
+    pub(crate) epc: Option<String>,
+    /// Memory bank to read from
+    pub(crate) bank: MemoryBank,
+    /// Word address to start at
+    pub(crate) address: u32,
+    /// Number of bytes to read
+    pub(crate) bytes: u8,
+    /// Access password (hex)
+    pub(crate) password: Option<String>,
+}
+
+#[derive(Serialize)]
+/// Response of `POST /read`
+pub(crate) struct ReadResponse {
+    /// Bytes read (hex)
+    pub(crate) data: String,
+}
+
+#[derive(Deserialize)]
+/// Body of `POST /write`
+pub(crate) struct WriteRequest {
+    /// EPC (hex) of the tag to write, any tag if not set
+    pub(crate) epc: Option<String>,
+    /// Memory bank to write to
+    pub(crate) bank: MemoryBank,
+    /// Word address to start at
+    pub(crate) address: u32,
+    /// Bytes to write (hex), a whole number of words
+    pub(crate) data: String,
+    /// Access password (hex)
+    pub(crate) password: Option<String>,
+}
+
+#[derive(Serialize)]
+/// Response of `POST /write`
+pub(crate) struct WriteResponse {
+    /// Number of words written
+    pub(crate) words_written: usize,
+}
+
+#[derive(Deserialize)]
+/// Body of `POST /lock`
+pub(crate) struct LockRequest {
+    /// EPC (hex) of the tag to lock, any tag if not set
+    pub(crate) epc: Option<String>,
+    /// Access password (hex)
+    pub(crate) password: Option<String>,
+    /// Memory banks and passwords to lock or unlock
+    pub(crate) lock: Gen2LockCfg,
+}
+
+#[derive(Deserialize)]
+/// Body of `POST /sweep`
+pub(crate) struct SweepRequest {
+    /// Number of RSSI measurements per channel
+    #[serde(default = "default_count")]
+    pub(crate) count: u8,
+}
+
+/// Number of RSSI measurements per channel unless requested otherwise
+fn default_count() -> u8 {
+    16
+}
+
+#[derive(Serialize)]
+/// Response of the `/inventory` endpoints
+pub(crate) struct InventoryResponse {
+    /// State of the continuous inventory
+    pub(crate) state: InventoryState,
+}
+
+/// `GET /config`: configuration applied to the reader
+async fn get_config(State(reader): State<ReaderHandle>) -> Result<Json<Gen2Cfg>, ApiError> {
+    let cfg = reader
+        .run(|session| Ok(session.reader()?.current_config()?))
+        .await?;
+
+    Ok(Json(cfg))
+}
+
+/// `PUT /config`: applies a configuration and tunes the reader again
+async fn put_config(
+    State(reader): State<ReaderHandle>,
+    Json(cfg): Json<Gen2Cfg>,
+) -> Result<Json<Gen2Cfg>, ApiError> {
+    let cfg = reader
+        .run(move |session| {
+            session.configure(&cfg)?;
+            Ok(session.reader()?.current_config()?)
+        })
+        .await?;
+
+    Ok(Json(cfg))
+}
+
+/// `POST /tune`: tunes the reader, returns the tuned channel list
+async fn tune(
+    State(reader): State<ReaderHandle>,
+    Json(request): Json<TuneRequest>,
+) -> Result<Json<ChannelListCfg>, ApiError> {
+    let channels = reader
+        .run(move |session| {
+            let reader = session.reader()?;
+            reader.tune(request.algorithm)?;
+            Ok(reader.get_channel_list()?)
+        })
+        .await?;
+
+    Ok(Json(channels))
+}
+
+/// `POST /read`: reads tag memory
+async fn read(
+    State(reader): State<ReaderHandle>,
+    Json(request): Json<ReadRequest>,
+) -> Result<Json<ReadResponse>, ApiError> {
+    let epc = parse_epc(request.epc.as_deref())?;
+    let password = parse_password(request.password.as_deref())?;
+
+    let data = reader
+        .run(move |session| {
+            session.access(epc.as_ref(), |reader| {
+                Ok(reader.read(request.bank, request.address, request.bytes, password)?)
+            })
+        })
+        .await?;
+
+    Ok(Json(ReadResponse { data: hex(&data) }))
+}
+
+/// `POST /write`: writes tag memory, one word at a time
+async fn write(
+    State(reader): State<ReaderHandle>,
+    Json(request): Json<WriteRequest>,
+) -> Result<Json<WriteResponse>, ApiError> {
+    let epc = parse_epc(request.epc.as_deref())?;
+    let password = parse_password(request.password.as_deref())?;
+    let words = parse_words(request.address, &request.data)?;
+
+    let words_written = reader
+        .run(move |session| {
+            session.access(epc.as_ref(), |reader| {
+                for (i, &(address, word)) in words.iter().enumerate() {
+                    reader
+                        .write(request.bank, address, word, password)
+                        .map_err(|e| {
+                            let mut e = ApiError::from(e);
+                            e.message = format!("{} after writing {} words", e.message, i);
+                            e
+                        })?;
+                }
+
+                Ok(words.len())
+            })
+        })
+        .await?;
+
+    Ok(Json(WriteResponse { words_written }))
+}
+
+/// `POST /lock`: changes the lock state of a tag
+async fn lock(
+    State(reader): State<ReaderHandle>,
+    Json(request): Json<LockRequest>,
+) -> Result<StatusCode, ApiError> {
+    let epc = parse_epc(request.epc.as_deref())?;
+    let password = parse_password(request.password.as_deref())?;
+
+    reader
+        .run(move |session| {
+            session.access(epc.as_ref(), |reader| {
+                Ok(reader.lock(&request.lock, password)?)
+            })
+        })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `POST /sweep`: measures the RSSI on every channel of the channel list
+async fn sweep(
+    State(reader): State<ReaderHandle>,
+    Json(request): Json<SweepRequest>,
+) -> Result<Json<Vec<Gen2RssiMeasurement>>, ApiError> {
+    if request.count == 0 {
+        return Err(ApiError::bad_request("count must be at least 1"));
+    }
+
+    let measurements = reader
+        .run(move |session| Ok(session.reader()?.measure_rssi_channels(request.count)?))
+        .await?;
+
+    Ok(Json(measurements))
+}
+
+/// `GET /inventory`: state of the continuous inventory
+async fn inventory(
+    State(reader): State<ReaderHandle>,
+) -> Result<Json<InventoryResponse>, ApiError> {
+    let state = reader.run(|session| Ok(session.state())).await?;
+
+    Ok(Json(InventoryResponse { state }))
+}
+
+/// `POST /inventory/start`: starts the continuous inventory
+async fn start_inventory(
+    State(reader): State<ReaderHandle>,
+) -> Result<Json<InventoryResponse>, ApiError> {
+    set_inventory(reader, true).await
+}
+
+/// `POST /inventory/stop`: stops the continuous inventory
+async fn stop_inventory(
+    State(reader): State<ReaderHandle>,
+) -> Result<Json<InventoryResponse>, ApiError> {
+    set_inventory(reader, false).await
+}
+
+/// Starts or stops the continuous inventory
+async fn set_inventory(
+    reader: ReaderHandle,
+    running: bool,
+) -> Result<Json<InventoryResponse>, ApiError> {
+    let state = reader
+        .run(move |session| {
+            session.set_running(running)?;
+            Ok(session.state())
+        })
+        .await?;
+
+    Ok(Json(InventoryResponse { state }))
+}
+
+/// `GET /events`: WebSocket stream of inventory events
+async fn events(ws: WebSocketUpgrade, State(reader): State<ReaderHandle>) -> Response {
+    let events = reader.subscribe();
+
+    ws.on_upgrade(move |socket| stream_events(socket, events))
+}
+
+/// Sends events to a WebSocket client as JSON text messages until it
+/// disconnects
+async fn stream_events(mut socket: WebSocket, mut events: broadcast::Receiver<Event>) {
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => Event::Lagged { skipped },
+                    Err(RecvError::Closed) => return,
+                };
+
+                let text = match serde_json::to_string(&event) {
+                    Ok(text) => text,
+                    Err(_) => continue,
+                };
+
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    return;
+                }
+            }
+            message = socket.recv() => match message {
+                // Clients have nothing to say, only closing matters
+                Some(Ok(_)) => (),
+                Some(Err(_)) | None => return,
+            },
+        }
+    }
+}
+
+/// Parses hex bytes, optionally separated by ':' as in [`HexID`]'s
+/// `Display` output
+pub(crate) fn parse_hex(field: &str, value: &str) -> Result<Vec<u8>, ApiError> {
+    let digits: String = value.chars().filter(|&c| c != ':').collect();
+    let invalid = || ApiError::bad_request(format!("{} must be hex bytes", field));
+
+    if digits.len() & 1 != 0 || !digits.is_ascii() {
+        return Err(invalid());
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| invalid()))
+        .collect()
+}
+
+/// Parses the data of a write starting at word `address` into the words
+/// to write, with their addresses
+pub(crate) fn parse_words(address: u32, value: &str) -> Result<Vec<(u32, [u8; 2])>, ApiError> {
+    let data = parse_hex("data", value)?;
+
+    if data.is_empty() || data.len() & 1 != 0 {
+        return Err(ApiError::bad_request(
+            "data must be a whole number of words",
+        ));
+    }
+
+    data.chunks(2)
+        .enumerate()
+        .map(|(i, word)| {
+            let address = u32::try_from(i)
+                .ok()
+                .and_then(|i| address.checked_add(i))
+                .ok_or_else(|| ApiError::bad_request("data runs past the last word address"))?;
+
+            Ok((address, [word[0], word[1]]))
+        })
+        .collect()
+}
+
+/// Parses an optional EPC
+pub(crate) fn parse_epc(value: Option<&str>) -> Result<Option<Epc>, ApiError> {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    let id = parse_hex("epc", value)?;
+
+    if id.is_empty() {
+        return Err(ApiError::bad_request("epc must not be empty"));
+    }
+
+    Ok(Some(Epc::from_id(id)))
+}
+
+/// Parses an optional 4 byte password
+pub(crate) fn parse_password(value: Option<&str>) -> Result<Option<Password>, ApiError> {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    let bytes: [u8; 4] = parse_hex("password", value)?
+        .try_into()
+        .map_err(|_| ApiError::bad_request("password must be 4 bytes"))?;
+
+    Ok(Some(Password::from(bytes)))
+}
+
+/// Formats bytes as upper case hex without separators
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
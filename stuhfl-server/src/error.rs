@@ -0,0 +1,54 @@
+//! Errors returned by the API
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use libstuhfl::error::Error;
+use serde_json::json;
+
+/// Error response, sent as `{"error": "..."}`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct ApiError {
+    /// HTTP status of the response
+    pub(crate) status: StatusCode,
+    /// Description of what went wrong
+    pub(crate) message: String,
+}
+
+impl ApiError {
+    /// Invalid request parameters
+    pub(crate) fn bad_request<S: Into<String>>(message: S) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+        }
+    }
+
+    /// The reader can't be used anymore
+    pub(crate) fn unavailable<S: Into<String>>(message: S) -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let status = match e {
+            Error::Param => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self {
+            status,
+            message: e.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
@@ -0,0 +1,126 @@
+//! `stuhfl-server` - shares an ST25RU3993 evaluation board over HTTP.
+//!
+//! The server owns the reader and queues all requests onto a single
+//! connection. It offers a REST API for the configuration, tuning, tag
+//! access and RSSI sweeps, and a WebSocket stream (`/events`) of the
+//! continuous inventory. Tag access requests pause the continuous
+//! inventory between two rounds and resume it afterwards.
+//!
+//! | Endpoint                 | Body               | Response              |
+//! |--------------------------|--------------------|-----------------------|
+//! | `GET /config`            |                    | `Gen2Cfg`             |
+//! | `PUT /config`            | `Gen2Cfg`          | `Gen2Cfg`             |
+//! | `POST /tune`             | `{algorithm}`      | `ChannelListCfg`      |
+//! | `POST /read`             | `{epc, bank, address, bytes, password}` | `{data}` |
+//! | `POST /write`            | `{epc, bank, address, data, password}`  | `{words_written}` |
+//! | `POST /lock`             | `{epc, password, lock: Gen2LockCfg}`    | 204 |
+//! | `POST /sweep`            | `{count}`          | `[Gen2RssiMeasurement]` |
+//! | `GET /inventory`         |                    | `{state}`             |
+//! | `POST /inventory/start`  |                    | `{state}`             |
+//! | `POST /inventory/stop`   |                    | `{state}`             |
+//! | `GET /events`            | WebSocket          | `{type, ...}` events  |
+//!
+//! EPCs, passwords and data are hex strings, errors are returned as
+//! `{"error": "..."}`.
+
+#![warn(missing_docs)]
+#![warn(clippy::missing_docs_in_private_items)]
+
+mod api;
+mod error;
+mod reader;
+#[cfg(test)]
+mod tests;
+
+use clap::Parser;
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use stuhfl_common::Tuning;
+
+#[derive(Parser)]
+#[command(
+    name = "stuhfl-server",
+    version,
+    about = "HTTP and WebSocket service for ST25RU3993 RFID readers"
+)]
+/// Command line arguments
+struct Cli {
+    /// Serial port of the reader [default: first reader found]
+    #[arg(long, conflicts_with = "serial")]
+    port: Option<String>,
+
+    /// USB serial number of the reader
+    #[arg(long)]
+    serial: Option<String>,
+
+    /// Gen2 configuration file to start with [default: the library's
+    /// default configuration]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Tuning algorithm run at start and after each configuration change
+    #[arg(long, value_enum, default_value_t = Tuning::Fast)]
+    tuning: Tuning,
+
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+
+    /// Start the continuous inventory right away
+    #[arg(long)]
+    inventory: bool,
+}
+
+/// Connects, configures and tunes the reader as requested on the
+/// command line
+fn connect(cli: &Cli) -> Result<(Gen2Reader, Gen2Cfg), Box<dyn Error>> {
+    let cfg = stuhfl_common::load_config(cli.config.as_deref())?;
+    let reader = stuhfl_common::connect(cli.port.as_deref(), cli.serial.as_deref())?;
+
+    let mut reader = reader.configure_gen2(&cfg)?;
+    reader.tune(cli.tuning.into())?;
+
+    Ok((reader, cfg))
+}
+
+/// Runs the server until it fails or is interrupted with Ctrl-C
+fn serve(cli: &Cli) -> Result<(), Box<dyn Error>> {
+    let (reader, cfg) = connect(cli)?;
+    let (handle, thread) = reader::spawn(reader, cfg, cli.tuning.into(), cli.inventory);
+
+    let runtime = tokio::runtime::Runtime::new()?;
+
+    let result = runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(cli.listen).await?;
+        eprintln!("Listening on http://{}", cli.listen);
+
+        tokio::select! {
+            result = axum::serve(listener, api::router(handle)) => result,
+            _ = tokio::signal::ctrl_c() => Ok(()),
+        }
+    });
+
+    // Dropping the runtime drops the last reader handles, letting the
+    // reader thread finish its round and disconnect
+    drop(runtime);
+    let _ = thread.join();
+
+    Ok(result?)
+}
+
+/// Starts the server, reporting errors on stderr
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match serve(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,311 @@
+//! Reader thread owning the [`Gen2Reader`]
+//!
+//! All requests are queued onto this thread and run one at a time. While
+//! the continuous inventory is running, requests are run between two
+//! inventory rounds, so tag access never interrupts a round.
+
+use crate::error::ApiError;
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use serde::Serialize;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread::{self, JoinHandle};
+use tokio::sync::{broadcast, oneshot};
+
+/// Number of events buffered for each WebSocket client
+const EVENT_BUFFER: usize = 1024;
+
+#[derive(Serialize, Copy, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+/// State of the continuous inventory
+pub(crate) enum InventoryState {
+    /// No inventory rounds are run
+    Stopped,
+    /// Inventory rounds are run back to back
+    Running,
+    /// A request is being handled, inventory continues afterwards
+    Paused,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+/// Sent to WebSocket clients
+pub(crate) enum Event {
+    /// A tag was found
+    Tag {
+        /// The tag
+        tag: InventoryTag,
+    },
+    /// An inventory round finished
+    Round {
+        /// Statistics of the round
+        statistics: InventoryStatistics,
+    },
+    /// The continuous inventory changed state
+    Inventory {
+        /// New state
+        state: InventoryState,
+    },
+    /// The continuous inventory stopped after an error
+    Error {
+        /// Description of the error
+        message: String,
+    },
+    /// Events were dropped because the client didn't keep up
+    Lagged {
+        /// Number of events dropped
+        skipped: u64,
+    },
+}
+
+/// Work queued onto the reader thread
+type Job = Box<dyn FnOnce(&mut Session) + Send>;
+
+/// Reader operations used to change the configuration, so that the
+/// recovery from a rejected configuration can be tested without a board
+pub(crate) trait Configurable {
+    /// See [`Gen2Reader::reconfigure()`]
+    fn reconfigure(&mut self, cfg: &Gen2Cfg) -> libstuhfl::error::Result<()>;
+    /// See [`ProtocolCommands::tune()`]
+    fn tune(&mut self, algo: TuningAlgorithm) -> libstuhfl::error::Result<()>;
+}
+
+impl Configurable for Gen2Reader {
+    fn reconfigure(&mut self, cfg: &Gen2Cfg) -> libstuhfl::error::Result<()> {
+        Gen2Reader::reconfigure(self, cfg)
+    }
+
+    fn tune(&mut self, algo: TuningAlgorithm) -> libstuhfl::error::Result<()> {
+        ProtocolCommands::tune(self, algo)
+    }
+}
+
+/// Error returned once the reader is lost
+fn reader_lost() -> ApiError {
+    ApiError::unavailable("reader lost, restart the server")
+}
+
+/// Applies `new` to the reader in `slot` and tunes it again. If the reader
+/// rejects `new`, `current` is applied and tuned again before the error is
+/// returned. The reader is only given up if that fails as well.
+pub(crate) fn reconfigure<R: Configurable>(
+    slot: &mut Option<R>,
+    current: &mut Gen2Cfg,
+    new: &Gen2Cfg,
+    tuning: TuningAlgorithm,
+) -> Result<(), ApiError> {
+    let reader = slot.as_mut().ok_or_else(reader_lost)?;
+
+    if let Err(e) = reader.reconfigure(new) {
+        // Go back to the configuration that worked
+        let restored = reader
+            .reconfigure(current)
+            .and_then(|()| reader.tune(tuning));
+
+        if restored.is_err() {
+            *slot = None;
+        }
+
+        return Err(e.into());
+    }
+
+    *current = new.clone();
+
+    Ok(reader.tune(tuning)?)
+}
+
+/// The reader and the state of the continuous inventory
+pub(crate) struct Session {
+    /// The reader, `None` if it was lost while changing the configuration
+    reader: Option<Gen2Reader>,
+    /// Last configuration the reader accepted
+    cfg: Gen2Cfg,
+    /// Tuning algorithm run after each configuration change
+    tuning: TuningAlgorithm,
+    /// Whether the continuous inventory is running
+    running: bool,
+}
+
+impl Session {
+    /// The reader, if it's still usable
+    pub(crate) fn reader(&mut self) -> Result<&mut Gen2Reader, ApiError> {
+        self.reader.as_mut().ok_or_else(reader_lost)
+    }
+
+    /// Applies a new configuration and tunes the reader again. A rejected
+    /// configuration leaves the reader with the previous one.
+    pub(crate) fn configure(&mut self, cfg: &Gen2Cfg) -> Result<(), ApiError> {
+        let result = reconfigure(&mut self.reader, &mut self.cfg, cfg, self.tuning);
+
+        if self.reader.is_none() {
+            self.running = false;
+        }
+
+        result
+    }
+
+    /// Runs `access` on the tag with `epc`, or on any tag if `None`. The
+    /// select list is cleared again afterwards, so inventory keeps
+    /// finding all tags.
+    pub(crate) fn access<T>(
+        &mut self,
+        epc: Option<&Epc>,
+        access: impl FnOnce(&mut Gen2Reader) -> Result<T, ApiError>,
+    ) -> Result<T, ApiError> {
+        let reader = self.reader()?;
+
+        let epc = match epc {
+            Some(epc) => epc,
+            None => return access(reader),
+        };
+
+        reader.select(epc)?;
+        let result = access(reader);
+        reader.clear_select()?;
+
+        result
+    }
+
+    /// Starts or stops the continuous inventory
+    pub(crate) fn set_running(&mut self, running: bool) -> Result<(), ApiError> {
+        self.reader()?;
+        self.running = running;
+
+        Ok(())
+    }
+
+    /// State of the continuous inventory, outside of requests
+    pub(crate) fn state(&self) -> InventoryState {
+        if self.running {
+            InventoryState::Running
+        } else {
+            InventoryState::Stopped
+        }
+    }
+
+    /// Runs a single inventory round, sending the tags and statistics
+    /// as events
+    fn round(&mut self, events: &broadcast::Sender<Event>) -> Result<(), ApiError> {
+        let tags = events.clone();
+
+        let statistics = self.reader()?.inventory(
+            1,
+            Box::new(move |tag| {
+                let _ = tags.send(Event::Tag { tag });
+            }),
+        )?;
+
+        let _ = events.send(Event::Round { statistics });
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+/// Queues requests onto the reader thread
+pub(crate) struct ReaderHandle {
+    /// Requests for the reader thread
+    jobs: mpsc::Sender<Job>,
+    /// Events of the reader thread
+    events: broadcast::Sender<Event>,
+}
+
+impl ReaderHandle {
+    /// Runs `f` on the reader thread and waits for its result
+    pub(crate) async fn run<T, F>(&self, f: F) -> Result<T, ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Session) -> Result<T, ApiError> + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+
+        self.jobs
+            .send(Box::new(move |session| {
+                let _ = reply.send(f(session));
+            }))
+            .map_err(|_| ApiError::unavailable("reader thread stopped"))?;
+
+        result
+            .await
+            .map_err(|_| ApiError::unavailable("reader thread stopped"))?
+    }
+
+    /// Receives the events sent from now on
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.events.subscribe()
+    }
+}
+
+/// Starts the reader thread for a reader configured with `cfg`. It stops
+/// once all handles are dropped.
+pub(crate) fn spawn(
+    reader: Gen2Reader,
+    cfg: Gen2Cfg,
+    tuning: TuningAlgorithm,
+    running: bool,
+) -> (ReaderHandle, JoinHandle<()>) {
+    let (jobs, queue) = mpsc::channel();
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+
+    let session = Session {
+        reader: Some(reader),
+        cfg,
+        tuning,
+        running,
+    };
+
+    let thread = {
+        let events = events.clone();
+        thread::spawn(move || run(session, queue, events))
+    };
+
+    (ReaderHandle { jobs, events }, thread)
+}
+
+/// Runs requests as they come in, and inventory rounds in between while
+/// the continuous inventory is running
+fn run(mut session: Session, queue: Receiver<Job>, events: broadcast::Sender<Event>) {
+    loop {
+        let job = if session.running {
+            match queue.try_recv() {
+                Ok(job) => Some(job),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        } else {
+            match queue.recv() {
+                Ok(job) => Some(job),
+                Err(_) => return,
+            }
+        };
+
+        let job = match job {
+            Some(job) => job,
+            None => {
+                if let Err(e) = session.round(&events) {
+                    session.running = false;
+                    let _ = events.send(Event::Error { message: e.message });
+                    let _ = events.send(Event::Inventory {
+                        state: InventoryState::Stopped,
+                    });
+                }
+                continue;
+            }
+        };
+
+        let was_running = session.running;
+        if was_running {
+            let _ = events.send(Event::Inventory {
+                state: InventoryState::Paused,
+            });
+        }
+
+        job(&mut session);
+
+        if was_running || session.running {
+            let _ = events.send(Event::Inventory {
+                state: session.state(),
+            });
+        }
+    }
+}
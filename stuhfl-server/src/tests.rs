@@ -0,0 +1,201 @@
+use crate::api::*;
+use crate::error::ApiError;
+use crate::reader::{reconfigure, Configurable, Event, InventoryState};
+use crate::Cli;
+use axum::http::StatusCode;
+use clap::{CommandFactory, Parser};
+use libstuhfl::error::{Error, Result};
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use serde_json::json;
+use stuhfl_common::test_support;
+
+type TestResult = std::result::Result<(), Box<dyn std::error::Error>>;
+
+/// Reader rejecting some configurations
+#[derive(Default)]
+struct FakeReader {
+    /// Configurations rejected by the reader
+    rejected: Vec<Gen2Cfg>,
+    /// Whether tuning fails
+    tuning_fails: bool,
+    /// Configurations applied, in order
+    applied: Vec<Gen2Cfg>,
+    /// Number of times the reader was tuned
+    tuned: u32,
+}
+
+impl Configurable for FakeReader {
+    fn reconfigure(&mut self, cfg: &Gen2Cfg) -> Result<()> {
+        if self.rejected.contains(cfg) {
+            return Err(Error::Param);
+        }
+
+        self.applied.push(cfg.clone());
+        Ok(())
+    }
+
+    fn tune(&mut self, _algo: TuningAlgorithm) -> Result<()> {
+        if self.tuning_fails {
+            return Err(Error::Generic);
+        }
+
+        self.tuned += 1;
+        Ok(())
+    }
+}
+
+/// Default configuration with a fixed channel list and the given Tx level
+fn cfg(tx_output_level: i8) -> Gen2Cfg {
+    Gen2Cfg::builder()
+        .channel_list(ChannelListCfg::from_profile(Profile::Europe))
+        .tx_rx_cfg(
+            TxRxCfg::builder()
+                .tx_output_level(tx_output_level)
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn cli_definition() {
+    Cli::command().debug_assert();
+
+    assert!(Cli::try_parse_from(["stuhfl-server", "--listen", "0.0.0.0:80"]).is_ok());
+    assert!(Cli::try_parse_from(["stuhfl-server", "--listen", "localhost"]).is_err());
+    assert!(Cli::try_parse_from(["stuhfl-server", "--port", "COM3", "--serial", "1"]).is_err());
+}
+
+#[test]
+fn value_parsers() {
+    assert_eq!(parse_hex("data", "E2:80:11"), Ok(vec![0xE2, 0x80, 0x11]));
+    assert_eq!(parse_hex("data", ""), Ok(vec![]));
+    assert!(parse_hex("data", "E28").is_err());
+    assert!(parse_hex("data", "E2G0").is_err());
+    assert!(parse_hex("data", "ÉÉ").is_err());
+
+    assert_eq!(parse_epc(None), Ok(None));
+    assert_eq!(
+        parse_epc(Some("E280")),
+        Ok(Some(Epc::from_id(vec![0xE2, 0x80])))
+    );
+    assert!(parse_epc(Some("")).is_err());
+
+    assert!(parse_password(None).is_ok_and(|p| p.is_none()));
+    assert!(parse_password(Some("00000000")).is_ok_and(|p| p.is_some()));
+    assert!(parse_password(Some("000000")).is_err());
+
+    assert_eq!(hex(&[0x0A, 0xFF]), "0AFF");
+
+    assert_eq!(
+        parse_words(2, "E2801100"),
+        Ok(vec![(2, [0xE2, 0x80]), (3, [0x11, 0x00])])
+    );
+    assert_eq!(
+        parse_words(u32::MAX, "E280"),
+        Ok(vec![(u32::MAX, [0xE2, 0x80])])
+    );
+    assert!(parse_words(0, "").is_err());
+    assert!(parse_words(0, "E28011").is_err());
+    assert_eq!(
+        parse_words(u32::MAX, "E2801100").unwrap_err().status,
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[test]
+fn request_bodies() -> TestResult {
+    let read: ReadRequest = serde_json::from_value(json!({
+        "epc": "E280",
+        "bank": "User",
+        "address": 0,
+        "bytes": 8,
+    }))?;
+    assert!(read.bank == MemoryBank::User);
+    assert!(read.password.is_none());
+
+    let lock: LockRequest = serde_json::from_value(json!({
+        "password": "55555555",
+        "lock": {"access_password": "Lock", "user": "PermaLock"},
+    }))?;
+    assert_eq!(lock.lock.payload(), [0x30, 0xC8, 0x30]);
+
+    // Nothing to lock
+    assert!(serde_json::from_value::<LockRequest>(json!({"lock": {}})).is_err());
+
+    let tune: TuneRequest = serde_json::from_value(json!({}))?;
+    assert_eq!(tune.algorithm, TuningAlgorithm::Exact);
+
+    let sweep: SweepRequest = serde_json::from_value(json!({}))?;
+    assert_eq!(sweep.count, 16);
+
+    Ok(())
+}
+
+#[test]
+fn events() -> TestResult {
+    let tag = InventoryTag {
+        timestamp: 12,
+        agc: 1,
+        ..test_support::tag(vec![0xE2, 0x80])
+    };
+
+    let event = serde_json::to_value(Event::Tag { tag })?;
+    assert_eq!(event["type"], "tag");
+    assert_eq!(event["tag"]["timestamp"], 12);
+    assert_eq!(event["tag"]["epc"]["id"], json!([0xE2, 0x80]));
+
+    let event = serde_json::to_value(Event::Inventory {
+        state: InventoryState::Paused,
+    })?;
+    assert_eq!(event, json!({"type": "inventory", "state": "paused"}));
+
+    let event = serde_json::to_value(Event::Lagged { skipped: 3 })?;
+    assert_eq!(event, json!({"type": "lagged", "skipped": 3}));
+
+    Ok(())
+}
+
+#[test]
+fn errors() {
+    assert_eq!(ApiError::from(Error::Param).status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        ApiError::from(Error::Timeout).status,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+    assert_eq!(
+        ApiError::unavailable("gone").status,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+}
+
+#[test]
+fn rejected_configuration() {
+    let mut slot = Some(FakeReader {
+        rejected: vec![cfg(-19)],
+        ..Default::default()
+    });
+    let mut current = cfg(0);
+
+    reconfigure(&mut slot, &mut current, &cfg(-6), TuningAlgorithm::Fast).unwrap();
+    assert!(current == cfg(-6));
+
+    // The previous configuration is applied again, and the reader kept
+    let error = reconfigure(&mut slot, &mut current, &cfg(-19), TuningAlgorithm::Fast);
+    assert_eq!(error.unwrap_err().status, StatusCode::BAD_REQUEST);
+    assert!(current == cfg(-6));
+
+    let reader = slot.as_mut().unwrap();
+    assert!(reader.applied == [cfg(-6), cfg(-6)]);
+    assert_eq!(reader.tuned, 2);
+
+    // Only given up if the previous configuration can't be restored
+    reader.tuning_fails = true;
+    assert!(reconfigure(&mut slot, &mut current, &cfg(-19), TuningAlgorithm::Fast).is_err());
+    assert!(slot.is_none());
+
+    let error = reconfigure(&mut slot, &mut current, &cfg(-6), TuningAlgorithm::Fast);
+    assert_eq!(error.unwrap_err().status, StatusCode::SERVICE_UNAVAILABLE);
+}
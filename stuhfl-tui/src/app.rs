@@ -0,0 +1,313 @@
+//! Dashboard state and hotkeys
+
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use libstuhfl::rssi::RssiModel;
+use ratatui::crossterm::event::KeyCode;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Number of inventory rounds kept for the sparklines
+pub(crate) const HISTORY_LEN: usize = 200;
+
+#[derive(Clone, PartialEq, Debug)]
+/// A tag seen during the session
+pub(crate) struct TagEntry {
+    /// Tag EPC
+    pub(crate) epc: Epc,
+    /// Tag TID, empty unless read during inventory
+    pub(crate) tid: Tid,
+    /// Number of times the tag was read
+    pub(crate) count: u64,
+    /// Received power of the last read (dBm)
+    pub(crate) rssi_dbm: f32,
+    /// Antenna of the last read
+    pub(crate) antenna: Antenna,
+    /// Time of the last read
+    pub(crate) last_seen: Instant,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+/// The parts of [`InventoryStatistics`] shown on the dashboard
+pub(crate) struct RoundStats {
+    /// Q at the end of the round
+    pub(crate) q: u8,
+    /// Number of collisions
+    pub(crate) collisions: u32,
+    /// Number of CRC errors
+    pub(crate) crc_errors: u32,
+    /// Frequency used (kHz)
+    pub(crate) frequency: u32,
+    /// Receiver sensitivity (dB)
+    pub(crate) sensitivity: i8,
+    /// Tuning status reported by the firmware
+    pub(crate) tuning_status: TuningStatus,
+}
+
+impl From<&InventoryStatistics> for RoundStats {
+    fn from(stats: &InventoryStatistics) -> Self {
+        Self {
+            q: stats.final_q,
+            collisions: stats.collision_count,
+            crc_errors: stats.crc_err_count,
+            frequency: stats.frequency,
+            sensitivity: stats.sensitivity,
+            tuning_status: stats.tuning_status,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// Columns the tag table can be sorted by
+pub(crate) enum SortColumn {
+    /// EPC, ascending
+    Epc,
+    /// TID, ascending
+    Tid,
+    /// Read count, highest first
+    Count,
+    /// RSSI, strongest first
+    Rssi,
+    /// Antenna, ascending
+    Antenna,
+    /// Last seen, most recent first
+    LastSeen,
+}
+
+impl SortColumn {
+    /// All columns, in table order
+    pub(crate) const ALL: [SortColumn; 6] = [
+        SortColumn::Epc,
+        SortColumn::Tid,
+        SortColumn::Count,
+        SortColumn::Rssi,
+        SortColumn::Antenna,
+        SortColumn::LastSeen,
+    ];
+
+    /// Column header
+    pub(crate) fn title(self) -> &'static str {
+        match self {
+            SortColumn::Epc => "EPC",
+            SortColumn::Tid => "TID",
+            SortColumn::Count => "Reads",
+            SortColumn::Rssi => "RSSI",
+            SortColumn::Antenna => "Ant",
+            SortColumn::LastSeen => "Last seen",
+        }
+    }
+
+    /// Column after (`step` = 1) or before (`step` = -1) this one
+    fn next(self, step: isize) -> Self {
+        let i = Self::ALL.iter().position(|&c| c == self).unwrap() as isize;
+        let n = Self::ALL.len() as isize;
+
+        Self::ALL[(i + step).rem_euclid(n) as usize]
+    }
+}
+
+/// What the dashboard should do after a key press
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum Action {
+    /// Nothing besides redrawing
+    None,
+    /// Apply a new configuration
+    Configure(Box<Gen2Cfg>),
+    /// Leave the dashboard
+    Quit,
+}
+
+/// Dashboard state
+pub(crate) struct App {
+    /// Configuration currently applied to the reader
+    pub(crate) cfg: Gen2Cfg,
+    /// Whether a configuration change is in progress
+    pub(crate) configuring: bool,
+    /// Tags seen, by EPC
+    tags: HashMap<Vec<u8>, TagEntry>,
+    /// Column the table is sorted by
+    pub(crate) sort: SortColumn,
+    /// Whether the sort order is reversed
+    pub(crate) reverse: bool,
+    /// Statistics of the last rounds, oldest first
+    pub(crate) history: VecDeque<RoundStats>,
+    /// Number of rounds run
+    pub(crate) rounds: u64,
+    /// Message shown in the footer
+    pub(crate) status: String,
+    /// Converts RSSI readings to dBm
+    model: RssiModel,
+}
+
+impl App {
+    /// Creates the dashboard state for a reader configured with `cfg`
+    pub(crate) fn new(cfg: Gen2Cfg) -> Self {
+        Self {
+            cfg,
+            configuring: false,
+            tags: HashMap::new(),
+            sort: SortColumn::LastSeen,
+            reverse: false,
+            history: VecDeque::with_capacity(HISTORY_LEN),
+            rounds: 0,
+            status: "Running inventory".to_owned(),
+            model: RssiModel::default(),
+        }
+    }
+
+    /// Records an inventory round and the tags found during it
+    pub(crate) fn round(&mut self, stats: RoundStats, tags: Vec<InventoryTag>, now: Instant) {
+        for tag in tags {
+            let rssi_dbm =
+                self.model
+                    .raw_to_dbm(tag.rssi_log_i, tag.rssi_log_q, tag.agc, stats.sensitivity);
+
+            let entry = self
+                .tags
+                .entry(tag.epc.get_id().to_vec())
+                .or_insert_with(|| TagEntry {
+                    epc: tag.epc.clone(),
+                    tid: tag.tid.clone(),
+                    count: 0,
+                    rssi_dbm,
+                    antenna: tag.antenna,
+                    last_seen: now,
+                });
+
+            entry.count += 1;
+            entry.rssi_dbm = rssi_dbm;
+            entry.antenna = tag.antenna;
+            entry.last_seen = now;
+
+            if !tag.tid.get_id().is_empty() {
+                entry.tid = tag.tid;
+            }
+        }
+
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(stats);
+        self.rounds += 1;
+    }
+
+    /// Number of different tags seen
+    pub(crate) fn tag_count(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Tags in table order
+    pub(crate) fn sorted_tags(&self) -> Vec<&TagEntry> {
+        let mut tags: Vec<&TagEntry> = self.tags.values().collect();
+
+        tags.sort_by(|a, b| {
+            let order = match self.sort {
+                SortColumn::Epc => a.epc.get_id().cmp(b.epc.get_id()),
+                SortColumn::Tid => a.tid.get_id().cmp(b.tid.get_id()),
+                SortColumn::Count => b.count.cmp(&a.count),
+                SortColumn::Rssi => b.rssi_dbm.total_cmp(&a.rssi_dbm),
+                SortColumn::Antenna => (a.antenna as u8).cmp(&(b.antenna as u8)),
+                SortColumn::LastSeen => b.last_seen.cmp(&a.last_seen),
+            };
+
+            // Keep rows in place when the sort key is equal
+            let order = order.then_with(|| a.epc.get_id().cmp(b.epc.get_id()));
+
+            if self.reverse {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+
+        tags
+    }
+
+    /// Time between the last read of `entry` and `now`
+    pub(crate) fn age(entry: &TagEntry, now: Instant) -> Duration {
+        now.saturating_duration_since(entry.last_seen)
+    }
+
+    /// Handles a key press
+    pub(crate) fn key(&mut self, key: KeyCode) -> Action {
+        let cfg = match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Tab | KeyCode::Right => {
+                self.sort = self.sort.next(1);
+                return Action::None;
+            }
+            KeyCode::BackTab | KeyCode::Left => {
+                self.sort = self.sort.next(-1);
+                return Action::None;
+            }
+            KeyCode::Char('r') => {
+                self.reverse = !self.reverse;
+                return Action::None;
+            }
+            KeyCode::Char('c') => {
+                self.tags.clear();
+                return Action::None;
+            }
+            _ if self.configuring => return Action::None,
+            KeyCode::Char('+') | KeyCode::Char('=') => self
+                .cfg
+                .with_tx_output_level(self.cfg.tx_output_level() + 1),
+            KeyCode::Char('-') => self
+                .cfg
+                .with_tx_output_level(self.cfg.tx_output_level() - 1),
+            KeyCode::Char('s') => Ok(self.cfg.with_session(next_session(self.cfg.session()))),
+            KeyCode::Char('t') => Ok(self.cfg.with_target(match self.cfg.target() {
+                Gen2QueryTarget::A => Gen2QueryTarget::B,
+                Gen2QueryTarget::B => Gen2QueryTarget::A,
+            })),
+            KeyCode::Char('a') => Ok(self.cfg.with_antenna(next_antenna(self.cfg.antenna()))),
+            _ => return Action::None,
+        };
+
+        match cfg {
+            Ok(cfg) => {
+                self.configuring = true;
+                self.status = "Applying configuration...".to_owned();
+                Action::Configure(Box::new(cfg))
+            }
+            Err(_) => {
+                self.status = "Tx power out of range [-19dB..0dB]".to_owned();
+                Action::None
+            }
+        }
+    }
+
+    /// Records that the reader now runs with `cfg`
+    pub(crate) fn configured(&mut self, cfg: Gen2Cfg) {
+        self.cfg = cfg;
+        self.configuring = false;
+        self.status = "Configuration applied".to_owned();
+    }
+
+    /// Records that the reader rejected a configuration change and
+    /// still runs with [`Self::cfg`]
+    pub(crate) fn rejected(&mut self, error: String) {
+        self.configuring = false;
+        self.status = format!("Configuration rejected: {}", error);
+    }
+}
+
+/// Session used after `session`
+fn next_session(session: Gen2Session) -> Gen2Session {
+    match session {
+        Gen2Session::Session0 => Gen2Session::Session1,
+        Gen2Session::Session1 => Gen2Session::Session2,
+        Gen2Session::Session2 => Gen2Session::Session3,
+        Gen2Session::Session3 => Gen2Session::Session0,
+    }
+}
+
+/// Antenna used after `antenna`
+fn next_antenna(antenna: Antenna) -> Antenna {
+    match antenna {
+        Antenna::Antenna1 => Antenna::Antenna2,
+        Antenna::Antenna2 => Antenna::Antenna3,
+        Antenna::Antenna3 => Antenna::Antenna4,
+        Antenna::Antenna4 => Antenna::Antenna1,
+    }
+}
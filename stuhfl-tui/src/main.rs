@@ -0,0 +1,142 @@
+//! `stuhfl-tui` - live inventory dashboard for ST25RU3993 evaluation boards.
+//!
+//! Runs inventory rounds continuously and shows the tags found, per-round
+//! statistics and the tuning status. Tx power, session, target and antenna
+//! can be changed with hotkeys while the inventory keeps running.
+
+#![warn(missing_docs)]
+#![warn(clippy::missing_docs_in_private_items)]
+
+mod app;
+#[cfg(test)]
+mod tests;
+mod ui;
+mod worker;
+
+use app::{Action, App};
+use clap::Parser;
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use ratatui::crossterm::event::{self, Event as TermEvent, KeyEventKind};
+use ratatui::DefaultTerminal;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+use stuhfl_common::Tuning;
+use worker::{Control, Event};
+
+/// How often the screen is redrawn while waiting for input
+const TICK: Duration = Duration::from_millis(100);
+
+#[derive(Parser)]
+#[command(
+    name = "stuhfl-tui",
+    version,
+    about = "Live inventory dashboard for ST25RU3993 RFID readers"
+)]
+/// Command line arguments
+struct Cli {
+    /// Serial port of the reader [default: first reader found]
+    #[arg(long, conflicts_with = "serial")]
+    port: Option<String>,
+
+    /// USB serial number of the reader
+    #[arg(long)]
+    serial: Option<String>,
+
+    /// Gen2 configuration file to start with [default: the library's
+    /// default configuration]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Tuning algorithm run at start and after each configuration change
+    #[arg(long, value_enum, default_value_t = Tuning::Fast)]
+    tuning: Tuning,
+}
+
+/// Connects, configures and tunes the reader as requested on the
+/// command line
+fn connect(cli: &Cli) -> Result<(Gen2Reader, Gen2Cfg), Box<dyn Error>> {
+    let cfg = stuhfl_common::load_config(cli.config.as_deref())?;
+    let reader = stuhfl_common::connect(cli.port.as_deref(), cli.serial.as_deref())?;
+
+    let mut reader = reader.configure_gen2(&cfg)?;
+    reader.tune(cli.tuning.into())?;
+
+    Ok((reader, cfg))
+}
+
+/// Draws the dashboard and handles input until the user quits
+fn run(
+    terminal: &mut DefaultTerminal,
+    mut app: App,
+    controls: Sender<Control>,
+    events: Receiver<Event>,
+) -> Result<(), Box<dyn Error>> {
+    loop {
+        for event in events.try_iter() {
+            match event {
+                Event::Round(stats, tags) => app.round(stats, tags, Instant::now()),
+                Event::Configured(cfg) => app.configured(cfg),
+                Event::Rejected(e) => app.rejected(e),
+                Event::Failed(e) => app.status = format!("Reader stopped: {}", e),
+            }
+        }
+
+        terminal.draw(|frame| ui::draw(frame, &app, Instant::now()))?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+
+        if let TermEvent::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match app.key(key.code) {
+                Action::None => (),
+                Action::Configure(cfg) => {
+                    if controls.send(Control::Configure(*cfg)).is_err() {
+                        app.configuring = false;
+                    }
+                }
+                Action::Quit => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Starts the dashboard, reporting errors on stderr
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let (reader, cfg) = match connect(&cli) {
+        Ok(connected) => connected,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (control_tx, control_rx) = mpsc::channel();
+    let (event_tx, event_rx) = mpsc::channel();
+    let worker = worker::spawn(reader, cfg.clone(), cli.tuning.into(), control_rx, event_tx);
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, App::new(cfg), control_tx, event_rx);
+    ratatui::restore();
+
+    // The control channel is closed, the worker stops after its round
+    let _ = worker.join();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::app::*;
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use ratatui::crossterm::event::KeyCode;
+use std::time::{Duration, Instant};
+use stuhfl_common::test_support;
+
+/// Default configuration with a fixed channel list, the default list is read
+/// from the working directory
+fn cfg() -> Gen2Cfg {
+    Gen2Cfg::builder()
+        .channel_list(ChannelListCfg::from_profile(Profile::Europe))
+        .build()
+        .unwrap()
+}
+
+fn stats(q: u8) -> RoundStats {
+    RoundStats {
+        q,
+        collisions: 1,
+        crc_errors: 0,
+        frequency: 865700,
+        sensitivity: -68,
+        tuning_status: TuningStatus::Tuned,
+    }
+}
+
+fn tag(epc: u8, antenna: Antenna) -> InventoryTag {
+    InventoryTag {
+        antenna,
+        ..test_support::tag(vec![0xE2, epc])
+    }
+}
+
+#[test]
+fn tag_table() {
+    let mut app = App::new(cfg());
+    let start = Instant::now();
+
+    app.round(
+        stats(4),
+        vec![tag(1, Antenna::Antenna1), tag(2, Antenna::Antenna2)],
+        start,
+    );
+    app.round(
+        stats(4),
+        vec![tag(2, Antenna::Antenna1)],
+        start + Duration::from_secs(1),
+    );
+
+    assert_eq!(app.tag_count(), 2);
+    assert_eq!(app.rounds, 2);
+
+    // Most recent first by default
+    let tags = app.sorted_tags();
+    assert_eq!(tags[0].epc.get_id(), &[0xE2, 2]);
+    assert_eq!(tags[0].count, 2);
+    assert_eq!(tags[0].antenna, Antenna::Antenna1);
+
+    app.key(KeyCode::BackTab);
+    assert_eq!(app.sort, SortColumn::Antenna);
+    app.key(KeyCode::Char('r'));
+    let tags = app.sorted_tags();
+    assert_eq!(tags[0].epc.get_id(), &[0xE2, 2]);
+
+    app.sort = SortColumn::Epc;
+    app.reverse = false;
+    let tags = app.sorted_tags();
+    assert_eq!(tags[0].epc.get_id(), &[0xE2, 1]);
+
+    app.key(KeyCode::Char('c'));
+    assert_eq!(app.tag_count(), 0);
+}
+
+#[test]
+fn round_history() {
+    let mut app = App::new(cfg());
+    let now = Instant::now();
+
+    for q in 0..HISTORY_LEN + 10 {
+        app.round(stats(q as u8), Vec::new(), now);
+    }
+
+    assert_eq!(app.history.len(), HISTORY_LEN);
+    assert_eq!(app.history.front().unwrap().q, 10);
+}
+
+#[test]
+fn configuration_keys() {
+    let mut app = App::new(cfg().with_tx_output_level(0).unwrap());
+
+    // Already at the highest power
+    assert_eq!(app.key(KeyCode::Char('+')), Action::None);
+    assert!(!app.configuring);
+
+    let Action::Configure(lower) = app.key(KeyCode::Char('-')) else {
+        panic!("expected a configuration change");
+    };
+    assert_eq!(lower.tx_output_level(), -1);
+    assert!(app.configuring);
+
+    // Ignored until the reader has applied the change
+    assert_eq!(app.key(KeyCode::Char('s')), Action::None);
+    app.configured(*lower);
+
+    let session = app.cfg.session();
+    let Action::Configure(cfg) = app.key(KeyCode::Char('s')) else {
+        panic!("expected a configuration change");
+    };
+    assert_ne!(cfg.session(), session);
+    app.configured(*cfg);
+
+    let target = app.cfg.target();
+    let Action::Configure(cfg) = app.key(KeyCode::Char('t')) else {
+        panic!("expected a configuration change");
+    };
+    assert_ne!(cfg.target(), target);
+    app.configured(*cfg);
+
+    let Action::Configure(cfg) = app.key(KeyCode::Char('a')) else {
+        panic!("expected a configuration change");
+    };
+    assert_ne!(cfg.antenna(), app.cfg.antenna());
+
+    // A rejected change keeps the last configuration and unlocks the keys
+    let antenna = app.cfg.antenna();
+    app.rejected("Parameter error".to_owned());
+    assert!(!app.configuring);
+    assert_eq!(app.cfg.antenna(), antenna);
+    assert!(app.status.contains("Parameter error"));
+    assert!(matches!(app.key(KeyCode::Char('a')), Action::Configure(_)));
+
+    assert_eq!(app.key(KeyCode::Char('q')), Action::Quit);
+}
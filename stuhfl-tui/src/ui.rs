@@ -0,0 +1,192 @@
+//! Drawing the dashboard
+
+use crate::app::{App, RoundStats, SortColumn, TagEntry};
+use libstuhfl::gen2::Gen2QueryTarget;
+use libstuhfl::prelude::*;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Sparkline, Table};
+use ratatui::Frame;
+use std::time::Instant;
+
+/// Draws the whole dashboard
+pub(crate) fn draw(frame: &mut Frame, app: &App, now: Instant) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(5),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let [tags, charts] =
+        Layout::horizontal([Constraint::Min(60), Constraint::Length(40)]).areas(body);
+
+    draw_header(frame, app, header);
+    draw_tags(frame, app, now, tags);
+    draw_charts(frame, app, charts);
+    draw_footer(frame, app, footer);
+}
+
+/// Reader settings and totals
+fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
+    let tuning = match app.history.back().map(|stats| stats.tuning_status) {
+        Some(TuningStatus::Tuned) => Span::styled("tuned", Style::new().fg(Color::Green)),
+        Some(TuningStatus::Tuning) => Span::styled("tuning", Style::new().fg(Color::Yellow)),
+        Some(TuningStatus::Untuned) => Span::styled("untuned", Style::new().fg(Color::Red)),
+        None => Span::raw("-"),
+    };
+
+    let line = Line::from(vec![
+        Span::raw(" Tuning: "),
+        tuning,
+        Span::raw(format!(
+            "   Tx power: {}dB   Session: {}   Target: {}   Antenna: {}   Rounds: {}   Tags: {}",
+            app.cfg.tx_output_level(),
+            app.cfg.session() as u8,
+            target_name(app.cfg.target()),
+            app.cfg.antenna() as u8 + 1,
+            app.rounds,
+            app.tag_count(),
+        )),
+    ]);
+
+    let title = if app.configuring {
+        "stuhfl-tui (configuring)"
+    } else {
+        "stuhfl-tui"
+    };
+
+    frame.render_widget(
+        Paragraph::new(line).block(Block::new().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+/// Table of the tags seen
+fn draw_tags(frame: &mut Frame, app: &App, now: Instant, area: Rect) {
+    let header = Row::new(SortColumn::ALL.iter().map(|&column| {
+        if column == app.sort {
+            let arrow = if app.reverse { "^" } else { "v" };
+            Cell::from(format!("{} {}", column.title(), arrow))
+                .style(Style::new().add_modifier(Modifier::REVERSED))
+        } else {
+            Cell::from(column.title())
+        }
+    }))
+    .style(Style::new().add_modifier(Modifier::BOLD));
+
+    let rows = app
+        .sorted_tags()
+        .into_iter()
+        .map(|entry| tag_row(entry, now));
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(24),
+            Constraint::Min(24),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(5),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header)
+    .block(Block::new().borders(Borders::ALL).title("Tags"));
+
+    frame.render_widget(table, area);
+}
+
+/// Table row of a single tag
+fn tag_row(entry: &TagEntry, now: Instant) -> Row<'static> {
+    Row::new(vec![
+        hex(entry.epc.get_id()),
+        hex(entry.tid.get_id()),
+        entry.count.to_string(),
+        format!("{:.1}", entry.rssi_dbm),
+        (entry.antenna as u8 + 1).to_string(),
+        format!("{:.1}s", App::age(entry, now).as_secs_f32()),
+    ])
+}
+
+/// Sparklines of the last inventory rounds
+fn draw_charts(frame: &mut Frame, app: &App, area: Rect) {
+    let areas = Layout::vertical([Constraint::Fill(1); 4]).split(area);
+    let last = app.history.back();
+
+    let charts: [(String, Vec<u64>, Color); 4] = [
+        (
+            format!("Q: {}", last.map_or(0, |s| s.q)),
+            series(app, |s| s.q.into()),
+            Color::Cyan,
+        ),
+        (
+            format!("Collisions: {}", last.map_or(0, |s| s.collisions)),
+            series(app, |s| s.collisions.into()),
+            Color::Yellow,
+        ),
+        (
+            format!("CRC errors: {}", last.map_or(0, |s| s.crc_errors)),
+            series(app, |s| s.crc_errors.into()),
+            Color::Red,
+        ),
+        (
+            format!("Frequency: {} kHz", last.map_or(0, |s| s.frequency)),
+            frequency_series(app),
+            Color::Magenta,
+        ),
+    ];
+
+    for ((title, data, color), &area) in charts.into_iter().zip(areas.iter()) {
+        // Only the most recent rounds fit into the chart
+        let width = area.width.saturating_sub(2) as usize;
+        let data = &data[data.len().saturating_sub(width)..];
+
+        let sparkline = Sparkline::default()
+            .block(Block::new().borders(Borders::ALL).title(title))
+            .data(data)
+            .style(Style::new().fg(color));
+
+        frame.render_widget(sparkline, area);
+    }
+}
+
+/// Values of one statistic over the rounds in the history
+fn series(app: &App, value: impl Fn(&RoundStats) -> u64) -> Vec<u64> {
+    app.history.iter().map(value).collect()
+}
+
+/// Frequencies over the rounds in the history, relative to the lowest
+/// one so hopping shows up in the chart
+fn frequency_series(app: &App) -> Vec<u64> {
+    let lowest = app.history.iter().map(|s| s.frequency).min().unwrap_or(0);
+
+    series(app, |s| (s.frequency - lowest).into())
+}
+
+/// Hotkeys and status message
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let line = Line::from(vec![
+        Span::styled(
+            " q quit  tab sort  r reverse  c clear  +/- power  s session  t target  a antenna ",
+            Style::new().add_modifier(Modifier::REVERSED),
+        ),
+        Span::raw(format!(" {}", app.status)),
+    ]);
+
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+/// Name of an inventory target
+fn target_name(target: Gen2QueryTarget) -> &'static str {
+    match target {
+        Gen2QueryTarget::A => "A",
+        Gen2QueryTarget::B => "B",
+    }
+}
+
+/// Formats bytes as upper case hex without separators
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
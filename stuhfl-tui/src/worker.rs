@@ -0,0 +1,93 @@
+//! Reader thread running the inventory
+
+use crate::app::RoundStats;
+use libstuhfl::gen2::*;
+use libstuhfl::prelude::*;
+use std::sync::mpsc::{Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Sent from the reader thread to the dashboard
+pub(crate) enum Event {
+    /// An inventory round finished
+    Round(RoundStats, Vec<InventoryTag>),
+    /// A new configuration was applied and the reader was tuned again
+    Configured(Gen2Cfg),
+    /// A new configuration was rejected, the reader went back to the
+    /// previous one and was tuned again
+    Rejected(String),
+    /// The reader stopped after an error
+    Failed(String),
+}
+
+/// Sent from the dashboard to the reader thread
+pub(crate) enum Control {
+    /// Apply a new configuration
+    Configure(Gen2Cfg),
+}
+
+/// Starts the reader thread for a reader configured with `cfg`. It stops
+/// when the control channel is closed or the reader fails.
+pub(crate) fn spawn(
+    reader: Gen2Reader,
+    cfg: Gen2Cfg,
+    tuning: TuningAlgorithm,
+    controls: Receiver<Control>,
+    events: Sender<Event>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        if let Err(e) = run(reader, cfg, tuning, &controls, &events) {
+            let _ = events.send(Event::Failed(e.to_string()));
+        }
+    })
+}
+
+/// Runs inventory rounds until the dashboard goes away, applying
+/// configuration changes in between
+fn run(
+    mut reader: Gen2Reader,
+    mut cfg: Gen2Cfg,
+    tuning: TuningAlgorithm,
+    controls: &Receiver<Control>,
+    events: &Sender<Event>,
+) -> libstuhfl::error::Result<()> {
+    loop {
+        match controls.try_recv() {
+            Ok(Control::Configure(new_cfg)) => {
+                let event = match reader.reconfigure(&new_cfg) {
+                    Ok(()) => {
+                        cfg = new_cfg;
+                        Event::Configured(cfg.clone())
+                    }
+                    Err(e) => {
+                        // Go back to the last configuration that worked
+                        reader.reconfigure(&cfg)?;
+                        Event::Rejected(e.to_string())
+                    }
+                };
+
+                reader.tune(tuning)?;
+
+                if events.send(event).is_err() {
+                    return Ok(());
+                }
+            }
+            Err(TryRecvError::Empty) => (),
+            Err(TryRecvError::Disconnected) => return Ok(()),
+        }
+
+        let found = Arc::new(Mutex::new(Vec::new()));
+        let sink = found.clone();
+
+        let stats = reader.inventory(1, Box::new(move |tag| sink.lock().unwrap().push(tag)))?;
+
+        let tags = std::mem::take(&mut *found.lock().unwrap());
+
+        if events
+            .send(Event::Round(RoundStats::from(&stats), tags))
+            .is_err()
+        {
+            return Ok(());
+        }
+    }
+}